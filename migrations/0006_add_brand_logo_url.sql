@@ -0,0 +1 @@
+ALTER TABLE brands ADD COLUMN logo_url VARCHAR(255);
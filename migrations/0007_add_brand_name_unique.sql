@@ -0,0 +1 @@
+ALTER TABLE brands ADD CONSTRAINT brands_name_key UNIQUE (name);
@@ -0,0 +1,10 @@
+CREATE OR REPLACE FUNCTION notify_brands_changed() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('brands_changed', TG_OP);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER brands_changed
+AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON brands
+FOR EACH STATEMENT EXECUTE FUNCTION notify_brands_changed();
@@ -0,0 +1 @@
+ALTER TABLE toners ADD COLUMN brand UUID REFERENCES brands(id);
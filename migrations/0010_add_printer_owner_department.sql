@@ -0,0 +1 @@
+ALTER TABLE printers ADD COLUMN owner_department VARCHAR(50);
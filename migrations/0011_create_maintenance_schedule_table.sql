@@ -0,0 +1,6 @@
+CREATE TABLE maintenance_schedules (
+    id UUID PRIMARY KEY,
+    printer_id UUID NOT NULL REFERENCES printers(id) ON DELETE CASCADE,
+    interval_days INTEGER NOT NULL CHECK (interval_days > 0),
+    last_done_at TIMESTAMPTZ
+);
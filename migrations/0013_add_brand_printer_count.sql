@@ -0,0 +1,20 @@
+ALTER TABLE brands ADD COLUMN printer_count INTEGER NOT NULL DEFAULT 0;
+
+UPDATE brands
+SET printer_count = (SELECT COUNT(*) FROM printers WHERE printers.brand = brands.id);
+
+CREATE OR REPLACE FUNCTION update_brand_printer_count() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        UPDATE brands SET printer_count = printer_count + 1 WHERE id = NEW.brand;
+    END IF;
+    IF TG_OP IN ('DELETE', 'UPDATE') THEN
+        UPDATE brands SET printer_count = printer_count - 1 WHERE id = OLD.brand;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER printers_brand_count
+AFTER INSERT OR DELETE OR UPDATE OF brand ON printers
+FOR EACH ROW EXECUTE FUNCTION update_brand_printer_count();
@@ -0,0 +1,33 @@
+ALTER TABLE brands
+    ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE printers
+    ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE toners
+    ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE drums
+    ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+-- Bumps `updated_at` when a row really changes. The trigger-maintained
+-- `brands.printer_count` is not an edit of the brand, so it is ignored.
+CREATE OR REPLACE FUNCTION touch_updated_at() RETURNS trigger AS $$
+BEGIN
+    IF to_jsonb(NEW) - 'printer_count' - 'updated_at'
+        IS DISTINCT FROM to_jsonb(OLD) - 'printer_count' - 'updated_at' THEN
+        NEW.updated_at = NOW();
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER brands_touch_updated_at BEFORE UPDATE ON brands
+FOR EACH ROW EXECUTE FUNCTION touch_updated_at();
+CREATE TRIGGER printers_touch_updated_at BEFORE UPDATE ON printers
+FOR EACH ROW EXECUTE FUNCTION touch_updated_at();
+CREATE TRIGGER toners_touch_updated_at BEFORE UPDATE ON toners
+FOR EACH ROW EXECUTE FUNCTION touch_updated_at();
+CREATE TRIGGER drums_touch_updated_at BEFORE UPDATE ON drums
+FOR EACH ROW EXECUTE FUNCTION touch_updated_at();
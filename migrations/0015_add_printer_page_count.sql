@@ -0,0 +1 @@
+ALTER TABLE printers ADD COLUMN page_count BIGINT NOT NULL DEFAULT 0 CHECK (page_count >= 0);
@@ -0,0 +1,10 @@
+CREATE TABLE tags (
+    id UUID PRIMARY KEY,
+    name VARCHAR(30) NOT NULL UNIQUE
+);
+
+CREATE TABLE printer_tags (
+    printer_id UUID NOT NULL REFERENCES printers(id) ON DELETE CASCADE,
+    tag_id UUID NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (printer_id, tag_id)
+);
@@ -0,0 +1,54 @@
+ALTER TABLE brands
+    ADD COLUMN created_by VARCHAR(64),
+    ADD COLUMN updated_by VARCHAR(64);
+ALTER TABLE printers
+    ADD COLUMN created_by VARCHAR(64),
+    ADD COLUMN updated_by VARCHAR(64);
+ALTER TABLE toners
+    ADD COLUMN created_by VARCHAR(64),
+    ADD COLUMN updated_by VARCHAR(64);
+ALTER TABLE drums
+    ADD COLUMN created_by VARCHAR(64),
+    ADD COLUMN updated_by VARCHAR(64);
+
+CREATE TABLE audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    entity VARCHAR(20) NOT NULL,
+    entity_id UUID NOT NULL,
+    action VARCHAR(10) NOT NULL,
+    actor VARCHAR(64),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX audit_log_entity_idx ON audit_log (entity, entity_id);
+
+-- Records every change with its actor: the `created_by`/`updated_by` written
+-- by the handler, or the `app.actor` setting of the transaction for deletes.
+-- Updates that only touch trigger-maintained columns are not edits.
+CREATE OR REPLACE FUNCTION record_audit() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO audit_log (entity, entity_id, action, actor)
+        VALUES (TG_ARGV[0], NEW.id, 'create', NEW.created_by);
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF to_jsonb(NEW) - 'printer_count' - 'updated_at' - 'updated_by'
+            IS DISTINCT FROM to_jsonb(OLD) - 'printer_count' - 'updated_at' - 'updated_by' THEN
+            INSERT INTO audit_log (entity, entity_id, action, actor)
+            VALUES (TG_ARGV[0], NEW.id, 'update', NEW.updated_by);
+        END IF;
+    ELSE
+        INSERT INTO audit_log (entity, entity_id, action, actor)
+        VALUES (TG_ARGV[0], OLD.id, 'delete', NULLIF(current_setting('app.actor', true), ''));
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER brands_audit AFTER INSERT OR UPDATE OR DELETE ON brands
+FOR EACH ROW EXECUTE FUNCTION record_audit('brand');
+CREATE TRIGGER printers_audit AFTER INSERT OR UPDATE OR DELETE ON printers
+FOR EACH ROW EXECUTE FUNCTION record_audit('printer');
+CREATE TRIGGER toners_audit AFTER INSERT OR UPDATE OR DELETE ON toners
+FOR EACH ROW EXECUTE FUNCTION record_audit('toner');
+CREATE TRIGGER drums_audit AFTER INSERT OR UPDATE OR DELETE ON drums
+FOR EACH ROW EXECUTE FUNCTION record_audit('drum');
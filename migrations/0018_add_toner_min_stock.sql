@@ -0,0 +1 @@
+ALTER TABLE toners ADD COLUMN min_stock INTEGER NOT NULL DEFAULT 0 CHECK (min_stock >= 0);
@@ -0,0 +1,14 @@
+-- Drafts hold whatever the client sent so far, so nothing is constrained
+-- until the draft is finalized into a printer.
+CREATE TABLE printer_drafts (
+    id UUID PRIMARY KEY,
+    name TEXT,
+    model TEXT,
+    brand TEXT,
+    toner TEXT,
+    drum TEXT,
+    owner_department TEXT,
+    created_by VARCHAR(64),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1 @@
+ALTER TABLE brands ADD COLUMN country_code CHAR(2);
@@ -0,0 +1,3 @@
+ALTER TABLE toners
+    ADD COLUMN color VARCHAR(20),
+    ADD COLUMN page_yield INTEGER CHECK (page_yield > 0);
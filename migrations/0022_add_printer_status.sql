@@ -0,0 +1,4 @@
+ALTER TABLE printers
+    ADD COLUMN status VARCHAR(10) NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'retired')),
+    ADD COLUMN decommissioned_at TIMESTAMPTZ,
+    ADD COLUMN decommission_reason VARCHAR(200);
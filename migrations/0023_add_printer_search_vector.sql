@@ -0,0 +1,8 @@
+-- Names and models are identifiers rather than prose, so the `simple`
+-- configuration is used: words are lowercased but not stemmed. Name matches
+-- weigh more than model matches when ranking.
+ALTER TABLE printers ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('simple', name), 'A') || setweight(to_tsvector('simple', model), 'B')
+) STORED;
+
+CREATE INDEX printers_search_vector_idx ON printers USING GIN (search_vector);
@@ -0,0 +1,4 @@
+ALTER TABLE printers
+    DROP CONSTRAINT printers_status_check,
+    ALTER COLUMN status TYPE VARCHAR(20),
+    ADD CONSTRAINT printers_status_check CHECK (status IN ('active', 'maintenance', 'retired'));
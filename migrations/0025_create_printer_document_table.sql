@@ -0,0 +1,9 @@
+CREATE TABLE printer_documents (
+    id UUID PRIMARY KEY,
+    printer_id UUID NOT NULL REFERENCES printers(id) ON DELETE CASCADE,
+    label VARCHAR(100) NOT NULL,
+    url VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX printer_documents_printer_id_idx ON printer_documents (printer_id);
@@ -0,0 +1,9 @@
+-- Each pair is stored once, lowest id first, and read in both directions.
+CREATE TABLE toner_equivalents (
+    toner_id UUID NOT NULL REFERENCES toners(id) ON DELETE CASCADE,
+    equivalent_id UUID NOT NULL REFERENCES toners(id) ON DELETE CASCADE,
+    PRIMARY KEY (toner_id, equivalent_id),
+    CHECK (toner_id < equivalent_id)
+);
+
+CREATE INDEX toner_equivalents_equivalent_id_idx ON toner_equivalents (equivalent_id);
@@ -0,0 +1,6 @@
+CREATE TABLE printer_photos (
+    printer_id UUID PRIMARY KEY REFERENCES printers(id) ON DELETE CASCADE,
+    content_type VARCHAR(20) NOT NULL,
+    data BYTEA NOT NULL,
+    uploaded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
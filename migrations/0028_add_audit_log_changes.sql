@@ -0,0 +1,25 @@
+ALTER TABLE audit_log ADD COLUMN changes JSONB;
+
+-- Same as before, with updates also recording the `app.changes` setting of
+-- the transaction: the old and new values a correction endpoint changed.
+CREATE OR REPLACE FUNCTION record_audit() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO audit_log (entity, entity_id, action, actor)
+        VALUES (TG_ARGV[0], NEW.id, 'create', NEW.created_by);
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF to_jsonb(NEW) - 'printer_count' - 'updated_at' - 'updated_by'
+            IS DISTINCT FROM to_jsonb(OLD) - 'printer_count' - 'updated_at' - 'updated_by' THEN
+            INSERT INTO audit_log (entity, entity_id, action, actor, changes)
+            VALUES (
+                TG_ARGV[0], NEW.id, 'update', NEW.updated_by,
+                NULLIF(current_setting('app.changes', true), '')::jsonb
+            );
+        END IF;
+    ELSE
+        INSERT INTO audit_log (entity, entity_id, action, actor)
+        VALUES (TG_ARGV[0], OLD.id, 'delete', NULLIF(current_setting('app.actor', true), ''));
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
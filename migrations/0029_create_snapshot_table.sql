@@ -0,0 +1,17 @@
+-- Periodic copies of the catalog's headline counts, for charting trends.
+-- Taken at whole seconds, as timestamps are written, so a `taken_at` read
+-- back from the API can be used as a `from`/`to` bound as is.
+CREATE TABLE snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    taken_at TIMESTAMPTZ NOT NULL DEFAULT date_trunc('second', NOW()),
+    printers BIGINT NOT NULL,
+    active_printers BIGINT NOT NULL,
+    brands BIGINT NOT NULL,
+    toners BIGINT NOT NULL,
+    drums BIGINT NOT NULL,
+    low_stock_toners BIGINT NOT NULL,
+    toner_stock BIGINT NOT NULL,
+    drum_stock BIGINT NOT NULL
+);
+
+CREATE INDEX snapshots_taken_at_idx ON snapshots (taken_at);
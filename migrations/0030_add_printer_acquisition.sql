@@ -0,0 +1,11 @@
+-- Whether a printer is owned or leased. Only leased printers have a lease end
+-- date, and they must have one.
+ALTER TABLE printers
+    ADD COLUMN acquisition_type VARCHAR(10) NOT NULL DEFAULT 'owned'
+        CHECK (acquisition_type IN ('owned', 'leased')),
+    ADD COLUMN lease_end_date DATE,
+    ADD CONSTRAINT printers_lease_end_date_check
+        CHECK ((acquisition_type = 'leased') = (lease_end_date IS NOT NULL));
+
+CREATE INDEX printers_lease_end_date_idx ON printers (lease_end_date)
+    WHERE lease_end_date IS NOT NULL;
@@ -0,0 +1,6 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- Names compared for near duplicates: lowercase, letters and digits only, so
+-- "HP LaserJet 400" and "HP Laserjet400" have the same key.
+CREATE INDEX printers_name_key_trgm_idx ON printers
+    USING gin (regexp_replace(lower(name), '[^[:alnum:]]', '', 'g') gin_trgm_ops);
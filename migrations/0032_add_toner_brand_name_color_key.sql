@@ -0,0 +1,5 @@
+-- Toners are told apart like SKUs, by brand, name and color: the same name may
+-- exist under another brand or in another color. Colors compare ignoring case
+-- and surrounding spaces, and a missing brand or color counts as a value.
+CREATE UNIQUE INDEX toners_brand_name_color_key
+    ON toners (brand, name, lower(btrim(color))) NULLS NOT DISTINCT;
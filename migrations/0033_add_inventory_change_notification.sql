@@ -0,0 +1,15 @@
+-- Announces every audited change on `inventory_changes` for the `GET /events`
+-- stream. Postgres delivers notifications when the transaction commits, so
+-- rolled back changes are never announced.
+CREATE FUNCTION notify_inventory_change() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify(
+        'inventory_changes',
+        json_build_object('entity', NEW.entity, 'id', NEW.entity_id, 'operation', NEW.action)::text
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_notify AFTER INSERT ON audit_log
+FOR EACH ROW EXECUTE FUNCTION notify_inventory_change();
@@ -0,0 +1,4 @@
+ALTER TABLE brands
+    ADD COLUMN website_url VARCHAR(255),
+    ADD COLUMN support_email VARCHAR(254),
+    ADD COLUMN support_phone VARCHAR(32);
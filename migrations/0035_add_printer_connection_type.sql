@@ -0,0 +1,4 @@
+-- How a printer connects, for inventory reporting. Unknown when unset.
+ALTER TABLE printers
+    ADD COLUMN connection_type VARCHAR(10)
+        CHECK (connection_type IN ('usb', 'ethernet', 'wifi', 'bluetooth'));
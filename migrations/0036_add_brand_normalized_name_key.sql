@@ -0,0 +1,8 @@
+-- Brand names compare trimmed, lowercased and without diacritics. `unaccent`
+-- is only STABLE, since its dictionary could change, so it is wrapped with
+-- the dictionary pinned to be usable in an index.
+CREATE FUNCTION normalize_name(name text) RETURNS text
+    LANGUAGE sql IMMUTABLE STRICT PARALLEL SAFE
+    RETURN lower(public.unaccent('public.unaccent'::regdictionary, btrim(name)));
+
+CREATE UNIQUE INDEX brands_normalized_name_key ON brands ((normalize_name(name)));
@@ -0,0 +1,13 @@
+-- Printer names are unique per brand under every `PRINTER_NAME_SCOPE`.
+-- The default `global` scope also needs them unique across brands, but
+-- databases that ran with the `brand` scope, and so only have the per brand
+-- index, may already repeat names across brands and keep it that way.
+DO $$
+BEGIN
+    IF to_regclass('printers_brand_name_key') IS NULL THEN
+        CREATE UNIQUE INDEX IF NOT EXISTS printers_name_key ON printers (name);
+    END IF;
+END
+$$;
+
+CREATE UNIQUE INDEX IF NOT EXISTS printers_brand_name_key ON printers (brand, name);
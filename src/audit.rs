@@ -0,0 +1,31 @@
+/// Deletes the row with id `$1` from `table`, recording `$2` as the actor in
+/// the audit log.
+///
+/// Deletes leave no row to carry `updated_by`, so the audit trigger reads the
+/// actor from the `app.actor` setting instead. Setting it from a CTE scopes it
+/// to the statement's own transaction.
+pub fn delete_statement(table: &str) -> String {
+    format!(
+        r#"
+        WITH actor AS (SELECT set_config('app.actor', coalesce($2, ''), true))
+        DELETE FROM {table} WHERE id = $1 AND EXISTS (SELECT 1 FROM actor);
+        "#
+    )
+}
+
+/// Updates the row with id `$1` of `table`, setting `column` to `$2` and
+/// `updated_by` to `$3`, and records `$4` as the changes in the audit log.
+///
+/// Like the actor of deletes, the changes reach the audit trigger through a
+/// transaction-scoped `app.changes` setting, given as a JSON object of
+/// `{ "<column>": { "old", "new" } }`.
+pub fn update_statement(table: &str, column: &str) -> String {
+    format!(
+        r#"
+        WITH changes AS (SELECT set_config('app.changes', $4, true))
+        UPDATE {table} SET {column} = $2, updated_by = $3
+        WHERE id = $1 AND EXISTS (SELECT 1 FROM changes)
+        RETURNING *;
+        "#
+    )
+}
@@ -0,0 +1,105 @@
+use std::sync::OnceLock;
+
+static PREFIX: OnceLock<(String, bool)> = OnceLock::new();
+
+/// Sets the prefix added to the links the API emits. Called once at startup
+/// with an empty prefix when the API is served at the root.
+///
+/// `nested` tells whether the router is nested under the prefix, in which
+/// case requested paths already include it.
+pub fn init(prefix: &str, nested: bool) {
+    let _ = PREFIX.set((String::from(prefix), nested));
+}
+
+/// Turns a requested path into the path clients reach it at.
+pub fn public(path: &str) -> String {
+    let (prefix, nested) = PREFIX
+        .get()
+        .map_or(("", false), |(prefix, nested)| (prefix.as_str(), *nested));
+    public_path(prefix, nested, path)
+}
+
+/// Turns a route, such as `/api/v1/brand/{id}`, into the path clients reach
+/// it at.
+pub fn route(path: &str) -> String {
+    route_path(PREFIX.get().map_or("", |(prefix, _)| prefix.as_str()), path)
+}
+
+fn public_path(prefix: &str, nested: bool, path: &str) -> String {
+    if nested {
+        String::from(path)
+    } else {
+        format!("{prefix}{path}")
+    }
+}
+
+fn route_path(prefix: &str, path: &str) -> String {
+    format!("{prefix}{path}")
+}
+
+/// A base path is `/` followed by one or more non-empty segments of unreserved
+/// URL characters, without a trailing slash, such as `/inventory` or
+/// `/apps/inventory`.
+pub fn is_valid(base_path: &str) -> bool {
+    base_path.starts_with('/')
+        && base_path[1..].split('/').all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~'))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_base_paths() {
+        for base_path in ["/inventory", "/apps/inventory", "/a-b.c_d~e", "/v1"] {
+            assert!(is_valid(base_path), "{base_path}");
+        }
+    }
+
+    #[test]
+    fn invalid_base_paths() {
+        for base_path in [
+            "",
+            "/",
+            "inventory",
+            "/inventory/",
+            "//inventory",
+            "/apps//inventory",
+            "/inven tory",
+            "/inventory?x=1",
+            "/inventário",
+        ] {
+            assert!(!is_valid(base_path), "{base_path}");
+        }
+    }
+
+    #[test]
+    fn requested_paths_get_the_prefix_unless_nested() {
+        let path = "/api/v1/printers?limit=1";
+
+        assert_eq!(
+            public_path("/inventory", false, path),
+            "/inventory/api/v1/printers?limit=1"
+        );
+        // Nested routers see the prefix in the requested path already.
+        assert_eq!(
+            public_path("/inventory", true, "/inventory/api/v1/printers"),
+            "/inventory/api/v1/printers"
+        );
+        assert_eq!(public_path("", false, path), path);
+    }
+
+    #[test]
+    fn routes_always_get_the_prefix() {
+        assert_eq!(
+            route_path("/inventory", "/api/v1/brand/1"),
+            "/inventory/api/v1/brand/1"
+        );
+        assert_eq!(route_path("", "/api/v1/brand/1"), "/api/v1/brand/1");
+    }
+}
@@ -0,0 +1,85 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use serde_json::Value;
+use tracing::info;
+
+pub const BRANDS: &str = "brands";
+
+/// Channels the table triggers notify on, with the namespace each one
+/// invalidates.
+pub const CHANNELS: [(&str, &str); 1] = [("brands_changed", BRANDS)];
+
+/// Entries kept at most. Past it, an arbitrary entry makes room for the new
+/// one.
+const MAX_ENTRIES: usize = 1024;
+
+/// In-memory cache for serialized list and count responses.
+///
+/// Keys are namespaced as `<namespace>:<key>` so a whole entity can be
+/// invalidated at once when its table changes. Every invalidation bumps a
+/// generation; a value computed before one is not stored after it.
+#[derive(Default)]
+pub struct Cache {
+    inner: RwLock<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<String, Value>,
+    generation: u64,
+}
+
+impl Cache {
+    pub fn get(&self, namespace: &str, key: &str) -> Option<Value> {
+        self.inner
+            .read()
+            .unwrap()
+            .entries
+            .get(&format!("{namespace}:{key}"))
+            .cloned()
+    }
+
+    /// Generation to pass to `insert` for a value about to be computed.
+    pub fn generation(&self) -> u64 {
+        self.inner.read().unwrap().generation
+    }
+
+    /// Stores `value` unless the cache was invalidated since `generation` was
+    /// read.
+    pub fn insert(&self, namespace: &str, key: &str, value: Value, generation: u64) {
+        let mut inner = self.inner.write().unwrap();
+        if inner.generation != generation {
+            return;
+        }
+
+        let key = format!("{namespace}:{key}");
+        if inner.entries.len() >= MAX_ENTRIES && !inner.entries.contains_key(&key) {
+            if let Some(evicted) = inner.entries.keys().next().cloned() {
+                inner.entries.remove(&evicted);
+            }
+        }
+        inner.entries.insert(key, value);
+    }
+
+    pub fn invalidate(&self, namespace: &str) {
+        let prefix = format!("{namespace}:");
+        let mut inner = self.inner.write().unwrap();
+        inner.generation += 1;
+        inner.entries.retain(|key, _| !key.starts_with(&prefix));
+    }
+
+    /// Drops the entries of the namespace `channel` notifies for, so edits
+    /// made outside the API are seen.
+    pub fn invalidate_channel(&self, channel: &str) {
+        if let Some((_, namespace)) = CHANNELS.iter().find(|(name, _)| *name == channel) {
+            info!("Invalidating '{}' cache entries", namespace);
+            self.invalidate(namespace);
+        }
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.inner.write().unwrap();
+        inner.generation += 1;
+        inner.entries.clear();
+    }
+}
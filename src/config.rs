@@ -1,9 +1,17 @@
 use dotenvy::Error as DotenvError;
+use regex::Regex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::postgres::PgSslMode;
+use std::{env, path::Path, str::FromStr};
+
+use crate::base_path;
 
 #[derive(Debug)]
 #[allow(dead_code)]
 pub enum ConfigError {
     DotenvError(DotenvError),
+    InvalidValue { key: &'static str, value: String },
 }
 
 impl From<DotenvError> for ConfigError {
@@ -12,11 +20,451 @@ impl From<DotenvError> for ConfigError {
     }
 }
 
-pub struct Config;
+/// How a soft validation rule reacts when it is violated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidationMode {
+    Off,
+    Warn,
+    Strict,
+}
+
+impl FromStr for ValidationMode {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "off" => Ok(ValidationMode::Off),
+            "warn" => Ok(ValidationMode::Warn),
+            "strict" => Ok(ValidationMode::Strict),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Set of printers within which a printer name must be unique.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrinterNameScope {
+    Global,
+    Brand,
+}
+
+impl FromStr for PrinterNameScope {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "global" => Ok(PrinterNameScope::Global),
+            "brand" => Ok(PrinterNameScope::Brand),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Format used when generating new resource ids.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdFormat {
+    Uuid,
+    Ulid,
+}
+
+impl FromStr for IdFormat {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "uuid" => Ok(IdFormat::Uuid),
+            "ulid" => Ok(IdFormat::Ulid),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Permission granted to an API key. Each scope includes the ones below it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    Read,
+    Write,
+    Admin,
+}
+
+impl Scope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Write => "write",
+            Scope::Admin => "admin",
+        }
+    }
+}
+
+impl FromStr for Scope {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "read" => Ok(Scope::Read),
+            "write" => Ok(Scope::Write),
+            "admin" => Ok(Scope::Admin),
+            _ => Err(()),
+        }
+    }
+}
+
+pub struct ApiKey {
+    /// SHA-256 of the key, compared in constant time against the presented
+    /// key's digest.
+    pub digest: [u8; 32],
+    pub scope: Scope,
+    /// Recorded as the actor of the key's mutations: the configured name, or
+    /// a fingerprint of the key so the secret itself is never stored.
+    pub id: String,
+}
+
+/// `NAME_PATTERN` as configured, and compiled to match whole names.
+pub struct NamePattern {
+    pub source: String,
+    pub regex: Regex,
+}
+
+/// Width of the `printers.model` column.
+const PRINTER_MODEL_COLUMN_LENGTH: usize = 50;
+
+pub struct Config {
+    pub default_page_size: i64,
+    pub max_page_size: i64,
+    /// Largest `offset` a page may start at, as skipping rows costs Postgres
+    /// as much as reading them.
+    pub max_offset: i64,
+    pub printer_model_min_length: usize,
+    pub printer_model_max_length: usize,
+    pub toner_brand_validation: ValidationMode,
+    /// Turns every soft validation warning into a rejection.
+    pub strict_validation: bool,
+    pub request_timeout_seconds: u64,
+    pub id_format: IdFormat,
+    pub printer_departments: Vec<String>,
+    pub db_connect_max_attempts: u32,
+    pub db_connect_retry_delay_ms: u64,
+    pub printer_name_scope: PrinterNameScope,
+    pub count_reconcile_interval_seconds: u64,
+    pub printer_qr_base_url: Option<String>,
+    pub api_keys: Vec<ApiKey>,
+    pub search_max_results: i64,
+    /// Window, in days, over which toner consumption is averaged.
+    pub reorder_lookback_days: i32,
+    /// Days of consumption a suggested toner order should cover beyond `min_stock`.
+    pub reorder_coverage_days: i32,
+    /// Days an unsaved printer draft is kept.
+    pub printer_draft_ttl_days: i32,
+    /// Documents a single printer may have attached.
+    pub printer_max_documents: i64,
+    /// Toners a single brand may have, unlimited when unset.
+    pub max_toners_per_brand: Option<i64>,
+    /// Overrides the `sslmode` of `DATABASE_URL` when set.
+    pub db_ssl_mode: Option<PgSslMode>,
+    pub db_ssl_root_cert: Option<String>,
+    /// Prefix a reverse proxy serves the API under, such as `/inventory`.
+    pub base_path: Option<String>,
+    /// Serves the routes under `base_path`, for proxies that forward the
+    /// prefix instead of stripping it.
+    pub base_path_nest: bool,
+    /// Expression brand and printer names must match as a whole.
+    pub name_pattern: Option<NamePattern>,
+    /// Mutations kept for `GET /admin/recent-ops`; zero disables it.
+    pub recent_ops_capacity: usize,
+    /// Requests served at once before shedding with `503`; zero disables it.
+    pub max_concurrent_requests: usize,
+    /// Refuses every mutation, for demo deployments.
+    pub read_only: bool,
+    /// Answers deletes of absent ids with `204` instead of `404`.
+    pub idempotent_deletes: bool,
+    /// Rejects client-supplied ids that are not version 4 UUIDs.
+    pub require_uuid_v4: bool,
+    /// Largest printer photo accepted, in bytes.
+    pub printer_photo_max_bytes: usize,
+    /// Largest request body accepted by single-item routes, in bytes.
+    pub max_body_bytes: usize,
+    /// Largest request body accepted by bulk and import routes, in bytes.
+    pub max_bulk_body_bytes: usize,
+    /// Database statements taking at least this long are logged at `warn`.
+    /// `0` disables the log.
+    pub slow_query_threshold_ms: u64,
+    /// `0` disables the periodic snapshots.
+    pub snapshot_interval_seconds: u64,
+}
 
 impl Config {
-    pub fn init() -> Result<(), ConfigError> {
+    pub fn init() -> Result<Config, ConfigError> {
         dotenvy::dotenv()?;
-        Ok(())
+
+        let config = Config {
+            default_page_size: env_or("DEFAULT_PAGE_SIZE", 20)?,
+            max_page_size: env_or("MAX_PAGE_SIZE", 100)?,
+            max_offset: env_or("MAX_OFFSET", 10_000)?,
+            printer_model_min_length: env_or("PRINTER_MODEL_MIN_LENGTH", 1)?,
+            printer_model_max_length: env_or("PRINTER_MODEL_MAX_LENGTH", 40)?,
+            toner_brand_validation: env_or("TONER_BRAND_VALIDATION", ValidationMode::Off)?,
+            strict_validation: env_or("STRICT_VALIDATION", false)?,
+            request_timeout_seconds: env_or("REQUEST_TIMEOUT_SECONDS", 30)?,
+            id_format: env_or("ID_FORMAT", IdFormat::Uuid)?,
+            printer_departments: env_list("PRINTER_DEPARTMENTS"),
+            db_connect_max_attempts: env_or("DB_CONNECT_MAX_ATTEMPTS", 5)?,
+            db_connect_retry_delay_ms: env_or("DB_CONNECT_RETRY_DELAY_MS", 1000)?,
+            printer_name_scope: env_or("PRINTER_NAME_SCOPE", PrinterNameScope::Global)?,
+            count_reconcile_interval_seconds: env_or("COUNT_RECONCILE_INTERVAL_SECONDS", 3600)?,
+            printer_qr_base_url: env::var("PRINTER_QR_BASE_URL").ok(),
+            api_keys: api_keys("API_KEYS")?,
+            search_max_results: env_or("SEARCH_MAX_RESULTS", 100)?,
+            reorder_lookback_days: env_or("REORDER_LOOKBACK_DAYS", 90)?,
+            reorder_coverage_days: env_or("REORDER_COVERAGE_DAYS", 30)?,
+            printer_draft_ttl_days: env_or("PRINTER_DRAFT_TTL_DAYS", 7)?,
+            printer_max_documents: env_or("PRINTER_MAX_DOCUMENTS", 20)?,
+            max_toners_per_brand: env_opt("MAX_TONERS_PER_BRAND")?,
+            db_ssl_mode: env_opt("DB_SSL_MODE")?,
+            db_ssl_root_cert: env::var("DB_SSL_ROOT_CERT").ok(),
+            base_path: env::var("BASE_PATH").ok().filter(|path| !path.is_empty()),
+            base_path_nest: env_or("BASE_PATH_NEST", false)?,
+            name_pattern: name_pattern("NAME_PATTERN")?,
+            recent_ops_capacity: env_or("RECENT_OPS_CAPACITY", 100)?,
+            max_concurrent_requests: env_or("MAX_CONCURRENT_REQUESTS", 0)?,
+            read_only: env_or("READ_ONLY", false)?,
+            idempotent_deletes: env_or("IDEMPOTENT_DELETES", false)?,
+            require_uuid_v4: env_or("REQUIRE_UUID_V4", false)?,
+            printer_photo_max_bytes: env_or("PRINTER_PHOTO_MAX_BYTES", 2 * 1024 * 1024)?,
+            max_body_bytes: env_or("MAX_BODY_BYTES", 64 * 1024)?,
+            max_bulk_body_bytes: env_or("MAX_BULK_BODY_BYTES", 10 * 1024 * 1024)?,
+            slow_query_threshold_ms: env_or("SLOW_QUERY_THRESHOLD_MS", 500)?,
+            snapshot_interval_seconds: env_or("SNAPSHOT_INTERVAL_SECONDS", 86400)?,
+        };
+
+        if config.max_page_size < 1 {
+            return Err(ConfigError::InvalidValue {
+                key: "MAX_PAGE_SIZE",
+                value: config.max_page_size.to_string(),
+            });
+        }
+
+        if config.default_page_size < 1 || config.default_page_size > config.max_page_size {
+            return Err(ConfigError::InvalidValue {
+                key: "DEFAULT_PAGE_SIZE",
+                value: config.default_page_size.to_string(),
+            });
+        }
+
+        if config.printer_model_max_length > PRINTER_MODEL_COLUMN_LENGTH
+            || config.printer_model_max_length < config.printer_model_min_length
+        {
+            return Err(ConfigError::InvalidValue {
+                key: "PRINTER_MODEL_MAX_LENGTH",
+                value: config.printer_model_max_length.to_string(),
+            });
+        }
+
+        if config.request_timeout_seconds < 1 {
+            return Err(ConfigError::InvalidValue {
+                key: "REQUEST_TIMEOUT_SECONDS",
+                value: config.request_timeout_seconds.to_string(),
+            });
+        }
+
+        if config.db_connect_max_attempts < 1 {
+            return Err(ConfigError::InvalidValue {
+                key: "DB_CONNECT_MAX_ATTEMPTS",
+                value: config.db_connect_max_attempts.to_string(),
+            });
+        }
+
+        if config.search_max_results < 1 {
+            return Err(ConfigError::InvalidValue {
+                key: "SEARCH_MAX_RESULTS",
+                value: config.search_max_results.to_string(),
+            });
+        }
+
+        if config.reorder_lookback_days < 1 {
+            return Err(ConfigError::InvalidValue {
+                key: "REORDER_LOOKBACK_DAYS",
+                value: config.reorder_lookback_days.to_string(),
+            });
+        }
+
+        if config.reorder_coverage_days < 0 {
+            return Err(ConfigError::InvalidValue {
+                key: "REORDER_COVERAGE_DAYS",
+                value: config.reorder_coverage_days.to_string(),
+            });
+        }
+
+        if config.printer_draft_ttl_days < 1 {
+            return Err(ConfigError::InvalidValue {
+                key: "PRINTER_DRAFT_TTL_DAYS",
+                value: config.printer_draft_ttl_days.to_string(),
+            });
+        }
+
+        // ULIDs carry no version, so they would all be rejected.
+        if config.require_uuid_v4 && config.id_format == IdFormat::Ulid {
+            return Err(ConfigError::InvalidValue {
+                key: "REQUIRE_UUID_V4",
+                value: String::from("true with ID_FORMAT=ulid"),
+            });
+        }
+
+        if config.printer_photo_max_bytes < 1 {
+            return Err(ConfigError::InvalidValue {
+                key: "PRINTER_PHOTO_MAX_BYTES",
+                value: config.printer_photo_max_bytes.to_string(),
+            });
+        }
+
+        if config.max_body_bytes < 1 {
+            return Err(ConfigError::InvalidValue {
+                key: "MAX_BODY_BYTES",
+                value: config.max_body_bytes.to_string(),
+            });
+        }
+
+        if config.max_bulk_body_bytes < 1 {
+            return Err(ConfigError::InvalidValue {
+                key: "MAX_BULK_BODY_BYTES",
+                value: config.max_bulk_body_bytes.to_string(),
+            });
+        }
+
+        if config.max_offset < 0 {
+            return Err(ConfigError::InvalidValue {
+                key: "MAX_OFFSET",
+                value: config.max_offset.to_string(),
+            });
+        }
+
+        if config.printer_max_documents < 1 {
+            return Err(ConfigError::InvalidValue {
+                key: "PRINTER_MAX_DOCUMENTS",
+                value: config.printer_max_documents.to_string(),
+            });
+        }
+
+        if let Some(max) = config.max_toners_per_brand.filter(|max| *max < 1) {
+            return Err(ConfigError::InvalidValue {
+                key: "MAX_TONERS_PER_BRAND",
+                value: max.to_string(),
+            });
+        }
+
+        if let Some(path) = &config.base_path {
+            if !base_path::is_valid(path) {
+                return Err(ConfigError::InvalidValue {
+                    key: "BASE_PATH",
+                    value: path.clone(),
+                });
+            }
+        }
+
+        if let Some(path) = &config.db_ssl_root_cert {
+            if !Path::new(path).is_file() {
+                return Err(ConfigError::InvalidValue {
+                    key: "DB_SSL_ROOT_CERT",
+                    value: path.clone(),
+                });
+            }
+        }
+
+        Ok(config)
     }
 }
+
+impl Config {
+    /// Mode in which a soft rule configured as `mode` runs: `warn` becomes
+    /// `strict` under `STRICT_VALIDATION`, while disabled rules stay off.
+    pub fn soft_mode(&self, mode: ValidationMode) -> ValidationMode {
+        match mode {
+            ValidationMode::Warn if self.strict_validation => ValidationMode::Strict,
+            mode => mode,
+        }
+    }
+}
+
+/// Reads `key` from the environment, falling back to `default` when unset.
+fn env_or<T: FromStr>(key: &'static str, default: T) -> Result<T, ConfigError> {
+    match env::var(key) {
+        Ok(value) => value
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue { key, value }),
+        Err(_) => Ok(default),
+    }
+}
+
+/// Reads `key` from the environment, `None` when unset.
+fn env_opt<T: FromStr>(key: &'static str) -> Result<Option<T>, ConfigError> {
+    match env::var(key) {
+        Ok(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| ConfigError::InvalidValue { key, value }),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Reads a comma-separated list from `key`, empty when unset.
+fn env_list(key: &'static str) -> Vec<String> {
+    env::var(key)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Compiles the expression in `key`, anchored so it has to match the whole
+/// name. `None` when unset or empty.
+fn name_pattern(key: &'static str) -> Result<Option<NamePattern>, ConfigError> {
+    match env::var(key) {
+        Ok(value) if !value.is_empty() => match Regex::new(&format!("^(?:{value})$")) {
+            Ok(regex) => Ok(Some(NamePattern {
+                source: value,
+                regex,
+            })),
+            Err(_) => Err(ConfigError::InvalidValue { key, value }),
+        },
+        _ => Ok(None),
+    }
+}
+
+/// Reads `key:scope` or `key:scope:name` entries from the comma-separated
+/// list in `key`.
+fn api_keys(key: &'static str) -> Result<Vec<ApiKey>, ConfigError> {
+    env_list(key)
+        .into_iter()
+        .map(|entry| {
+            let mut parts = entry.splitn(3, ':').map(str::trim);
+            let api_key = parts.next().filter(|api_key| !api_key.is_empty());
+            let scope = parts.next().and_then(|scope| scope.parse().ok());
+            let name = parts.next().filter(|name| !name.is_empty());
+
+            match (api_key, scope) {
+                (Some(api_key), Some(scope)) => Ok(ApiKey {
+                    digest: Sha256::digest(api_key.as_bytes()).into(),
+                    scope,
+                    id: name.map_or_else(|| fingerprint(api_key), String::from),
+                }),
+                _ => Err(ConfigError::InvalidValue { key, value: entry }),
+            }
+        })
+        .collect()
+}
+
+/// Short, non-reversible identifier of an API key.
+fn fingerprint(api_key: &str) -> String {
+    let digest = Sha256::digest(api_key.as_bytes());
+    let hex: String = digest[..6]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("key-{hex}")
+}
@@ -0,0 +1,46 @@
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::models::event::ChangeEvent;
+
+/// Channel the audit trigger notifies on.
+pub const CHANNEL: &str = "inventory_changes";
+
+/// Changes buffered for each client; a client falling further behind skips
+/// the oldest ones and is told how many it missed.
+const CLIENT_BUFFER: usize = 1024;
+
+/// Fans the change notifications out to the connected `GET /events` clients.
+pub struct Events {
+    sender: broadcast::Sender<ChangeEvent>,
+}
+
+impl Default for Events {
+    fn default() -> Self {
+        Events {
+            sender: broadcast::channel(CLIENT_BUFFER).0,
+        }
+    }
+}
+
+impl Events {
+    pub fn subscribe(&self) -> broadcast::Receiver<ChangeEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Forwards a notification of the audit trigger to the connected clients.
+    pub fn publish(&self, payload: &str) {
+        match serde_json::from_str::<ChangeEvent>(payload) {
+            // Sending only fails while no client is connected.
+            Ok(change) => {
+                let _ = self.sender.send(change);
+            }
+            Err(e) => warn!("Ignoring malformed change notification: {e}"),
+        }
+    }
+
+    /// Clients currently connected to the stream.
+    pub fn clients(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
@@ -0,0 +1,215 @@
+use std::convert::Infallible;
+
+use axum::{
+    async_trait,
+    extract::{
+        rejection::{JsonRejection, PathRejection},
+        FromRequest, FromRequestParts, Request,
+    },
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::json;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{config::Scope, ids};
+
+/// Drop-in replacement for axum's `Path` extractor.
+///
+/// Path parameters are ids, written either as UUIDs or as ULIDs, besides the
+/// odd name such as a tag's. A value that fails to parse is answered with a
+/// structured `400` instead of axum's plain-text rejection.
+pub struct Path<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    T: FromPath + Send,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let values =
+            match axum::extract::Path::<Vec<String>>::from_request_parts(parts, state).await {
+                Ok(axum::extract::Path(values)) => values,
+                Err(PathRejection::FailedToDeserializePathParams(e)) => {
+                    error!("Invalid path {}: {}", parts.uri.path(), e.body_text());
+                    return Err(invalid_path());
+                }
+                Err(rejection) => return Err(rejection.into_response()),
+            };
+        match T::from_path(&values) {
+            Some(value) => Ok(Path(value)),
+            None => {
+                error!("Invalid id in path {}", parts.uri.path());
+                Err(invalid_path())
+            }
+        }
+    }
+}
+
+fn invalid_path() -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        axum::Json(json!({ "error": "invalid uuid in path" })),
+    )
+        .into_response()
+}
+
+/// What a [`Path`] extracts: one parameter, or a pair of them.
+pub trait FromPath: Sized {
+    fn from_path(values: &[String]) -> Option<Self>;
+}
+
+/// A single path parameter.
+pub trait PathParam: Sized {
+    fn parse(value: &str) -> Option<Self>;
+}
+
+impl PathParam for Uuid {
+    fn parse(value: &str) -> Option<Self> {
+        ids::parse(value)
+    }
+}
+
+impl PathParam for String {
+    fn parse(value: &str) -> Option<Self> {
+        Some(value.to_owned())
+    }
+}
+
+impl<T: PathParam> FromPath for T {
+    fn from_path(values: &[String]) -> Option<Self> {
+        match values {
+            [value] => T::parse(value),
+            _ => None,
+        }
+    }
+}
+
+impl<A: PathParam, B: PathParam> FromPath for (A, B) {
+    fn from_path(values: &[String]) -> Option<Self> {
+        match values {
+            [first, second] => Some((A::parse(first)?, B::parse(second)?)),
+            _ => None,
+        }
+    }
+}
+
+/// Drop-in replacement for axum's `Json` extractor and response.
+///
+/// Request bodies deserialized with `deny_unknown_fields` are answered with a
+/// `400` naming the unknown field, so a typo like `nam` is not mistaken for a
+/// missing `name`, and values of the wrong type with a `400` naming the field
+/// and the type it takes. Other rejections are axum's.
+pub struct Json<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::Json::<T>::from_request(req, state).await {
+            Ok(axum::Json(value)) => Ok(Json(value)),
+            Err(JsonRejection::JsonDataError(e)) => {
+                match unknown_field(&e.body_text()) {
+                    Some(field) => {
+                        error!("Unknown field in request body: {field}");
+                        Err((
+                            StatusCode::BAD_REQUEST,
+                            axum::Json(json!({ "error": "unknown field", "field": field })),
+                        )
+                            .into_response())
+                    }
+                    None => match invalid_type(&e.body_text()) {
+                        Some((field, expected)) => {
+                            error!("Invalid type in request body: expected {expected} for field `{field}`");
+                            Err((
+                                StatusCode::BAD_REQUEST,
+                                axum::Json(json!({
+                                    "error": "invalid type",
+                                    "field": field,
+                                    "expected": expected,
+                                })),
+                            )
+                                .into_response())
+                        }
+                        None => Err(e.into_response()),
+                    },
+                }
+            }
+            Err(rejection) => Err(rejection.into_response()),
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+/// Extracts the field name from serde's "unknown field `x`, expected ..."
+/// message.
+fn unknown_field(message: &str) -> Option<&str> {
+    let (_, rest) = message.split_once("unknown field `")?;
+    rest.split_once('`').map(|(field, _)| field)
+}
+
+/// Extracts the field path and the expected type from serde's
+/// "x: invalid type: integer `1`, expected a string at line ..." message.
+/// `None` when the body itself has the wrong type.
+fn invalid_type(message: &str) -> Option<(&str, &str)> {
+    let (path, rest) = message.split_once(": invalid type: ")?;
+    let field = path.rsplit_once(": ").map_or(path, |(_, field)| field);
+    let (_, expected) = rest.split_once(", expected ")?;
+    let expected = expected
+        .rsplit_once(" at line ")
+        .map_or(expected, |(expected, _)| expected);
+    (field != ".").then_some((field, expected))
+}
+
+/// The API key that authenticated the request, stored in the request
+/// extensions by the auth middleware.
+#[derive(Clone)]
+pub struct Principal {
+    pub id: String,
+    pub scope: Scope,
+}
+
+/// Who is performing the request. Empty while authentication is disabled.
+pub struct Actor(pub Option<Principal>);
+
+impl Actor {
+    /// Value recorded in `created_by`/`updated_by` and the audit log.
+    pub fn id(&self) -> Option<&str> {
+        self.0.as_ref().map(|principal| principal.id.as_str())
+    }
+
+    /// Whether the request may see admin-only details. Without
+    /// authentication every client has full access.
+    pub fn is_admin(&self) -> bool {
+        self.0
+            .as_ref()
+            .is_none_or(|principal| principal.scope == Scope::Admin)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Actor
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Actor(parts.extensions.get::<Principal>().cloned()))
+    }
+}
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use tracing::{error, info};
+
+use crate::models::{
+    activity::{ActivityEntry, ActivityParams},
+    database::AppState,
+};
+
+/// Recently created or updated brands, printers, toners and drums, newest
+/// first. `since` keeps only changes after the given timestamp.
+pub async fn show_activity(
+    Query(params): Query<ActivityParams>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let limit = params.limit.unwrap_or(state.config.default_page_size);
+    if limit < 1 {
+        error!("Invalid activity limit: {limit}");
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json("Limit must be greater than zero."),
+        ));
+    }
+
+    let activity: Result<Vec<ActivityEntry>, sqlx::Error> = sqlx::query_as(
+        r#"
+        SELECT
+            kind, id, name,
+            CASE WHEN updated_at = created_at THEN 'created' ELSE 'updated' END AS action,
+            created_at, updated_at
+        FROM (
+            SELECT 'brand' AS kind, id, name, created_at, updated_at FROM brands
+            UNION ALL
+            SELECT 'printer', id, name, created_at, updated_at FROM printers
+            UNION ALL
+            SELECT 'toner', id, name, created_at, updated_at FROM toners
+            UNION ALL
+            SELECT 'drum', id, name, created_at, updated_at FROM drums
+        ) AS entities
+        WHERE $1::timestamptz IS NULL OR updated_at > $1
+        ORDER BY updated_at DESC, id
+        LIMIT $2;
+        "#,
+    )
+    .bind(params.since)
+    .bind(limit.min(state.config.max_page_size))
+    .fetch_all(&state.db)
+    .await;
+
+    match activity {
+        Ok(activity) => {
+            info!("Activity listed: {} entries", activity.len());
+            Ok(Json(activity))
+        }
+        Err(e) => {
+            error!("Error listing activity: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error listing activity."),
+            ))
+        }
+    }
+}
@@ -0,0 +1,705 @@
+use std::{collections::HashMap, env, sync::Arc};
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension,
+};
+use sqlx::{postgres::PgSslMode, PgPool, Postgres, Transaction};
+use tracing::{error, info};
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    audit, cache,
+    extractors::{Actor, Json, Path},
+    handlers::{printer, supplies::toner},
+    jobs::{self, AdminOperation, JobError},
+    middleware::deadline::Deadline,
+    models::{
+        brand::{MergeBrandsRequest, MergeBrandsResult},
+        config::{EffectiveApiKey, EffectiveConfig},
+        database::AppState,
+        error::ApiError,
+        integrity::IntegrityReport,
+        printer::{NearDuplicateGroup, NearDuplicateParams, PrinterName, SimilarPrinters},
+        status::TableStats,
+        supplies::toner::ReplaceTonerResult,
+    },
+    validations,
+};
+
+/// Runs the denormalized count reconciliation on demand. Answers `409` while
+/// another recomputation is running.
+pub async fn recompute_counts(
+    Extension(deadline): Extension<Deadline>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let mut tx = match state.begin(deadline).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!("Error recomputing counts: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error recomputing counts."),
+            ));
+        }
+    };
+
+    let fixed = match jobs::reconcile_brand_printer_counts(&mut tx).await {
+        Ok(fixed) => fixed,
+        Err(JobError::Busy) => {
+            error!("Counts are already being recomputed.");
+            return Err((
+                StatusCode::CONFLICT,
+                Json("Counts are already being recomputed."),
+            ));
+        }
+        Err(JobError::Database(e)) => {
+            error!("Error recomputing counts: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error recomputing counts."),
+            ));
+        }
+    };
+
+    match tx.commit().await {
+        Ok(_) => {
+            info!("Counts recomputed, {} rows fixed", fixed.len());
+            Ok(Json(fixed))
+        }
+        Err(e) => {
+            error!("Error recomputing counts: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error recomputing counts."),
+            ))
+        }
+    }
+}
+
+/// Merges the `from` brand into `into`: its printers and toners are repointed
+/// to `into` and `from` is deleted, all in one transaction.
+pub async fn merge_brands(
+    actor: Actor,
+    Extension(deadline): Extension<Deadline>,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<MergeBrandsRequest>,
+) -> impl IntoResponse {
+    // Same brand
+    if request.from == request.into {
+        error!("Cannot merge a brand into itself.");
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json("Cannot merge a brand into itself.".into()),
+        ));
+    }
+
+    let mut tx = match state.begin(deadline).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!("Error merging brands: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error merging brands.".into()),
+            ));
+        }
+    };
+
+    let result = match merge(&mut tx, &request, actor.id()).await {
+        Ok(Some(result)) => result,
+        Ok(None) => {
+            error!("Brand ID not found.");
+            return Err((StatusCode::NOT_FOUND, Json("Brand ID not found.".into())));
+        }
+        Err(JobError::Busy) => {
+            error!("Brands are already being merged.");
+            return Err((
+                StatusCode::CONFLICT,
+                Json("Brands are already being merged.".into()),
+            ));
+        }
+        // Both brands may have a toner of the same name and color, and with
+        // `PRINTER_NAME_SCOPE=brand` printers of the same name.
+        Err(JobError::Database(sqlx::Error::Database(e))) if e.is_unique_violation() => {
+            error!("Error merging brands: {e}");
+            let message = match e.constraint() {
+                Some("toners_brand_name_color_key") => {
+                    "Both brands have toners with the same name and color."
+                }
+                _ => "Both brands have printers with the same name.",
+            };
+            return Err((StatusCode::CONFLICT, Json(message.into())));
+        }
+        Err(JobError::Database(e)) => {
+            error!("Error merging brands: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error merging brands.".into()),
+            ));
+        }
+    };
+
+    // Merged brand over its toner limit
+    if let Some(limit) = state
+        .config
+        .max_toners_per_brand
+        .filter(|_| result.toners > 0)
+    {
+        match toner::brand_toner_count(&mut tx, request.into, None).await {
+            Ok(count) if count > limit => {
+                error!("Brand {} would have {} toners.", &request.into, count);
+                return Err((
+                    StatusCode::CONFLICT,
+                    Json(ApiError::brand_toner_limit(
+                        "Merged brand would have more than the maximum number of toners.",
+                        limit,
+                        count,
+                    )),
+                ));
+            }
+            Ok(_) => {}
+            Err(e) => {
+                error!("Error merging brands: {e}");
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json("Error merging brands.".into()),
+                ));
+            }
+        }
+    }
+
+    match tx.commit().await {
+        Ok(_) => {
+            state.cache.invalidate(cache::BRANDS);
+            info!(
+                "Brand {} merged into {}! Printers: {}, toners: {}",
+                &request.from, &request.into, result.printers, result.toners
+            );
+            Ok(Json(result))
+        }
+        Err(e) => {
+            error!("Error merging brands: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error merging brands.".into()),
+            ))
+        }
+    }
+}
+
+/// Runs the merge inside `tx`, or returns `None` when either brand is missing.
+async fn merge(
+    tx: &mut Transaction<'static, Postgres>,
+    request: &MergeBrandsRequest,
+    actor: Option<&str>,
+) -> Result<Option<MergeBrandsResult>, JobError> {
+    if !AdminOperation::MergeBrands.try_lock(tx).await? {
+        return Err(JobError::Busy);
+    }
+
+    // Locking both rows keeps them from being deleted halfway through.
+    let found: Vec<(Uuid,)> =
+        sqlx::query_as(r#"SELECT id FROM brands WHERE id = ANY($1) FOR UPDATE;"#)
+            .bind([request.from, request.into])
+            .fetch_all(&mut **tx)
+            .await?;
+    if found.len() < 2 {
+        return Ok(None);
+    }
+
+    let printers =
+        sqlx::query(r#"UPDATE printers SET brand = $2, updated_by = $3 WHERE brand = $1;"#)
+            .bind(request.from)
+            .bind(request.into)
+            .bind(actor)
+            .execute(&mut **tx)
+            .await?;
+    let toners = sqlx::query(r#"UPDATE toners SET brand = $2, updated_by = $3 WHERE brand = $1;"#)
+        .bind(request.from)
+        .bind(request.into)
+        .bind(actor)
+        .execute(&mut **tx)
+        .await?;
+
+    sqlx::query(&audit::delete_statement("brands"))
+        .bind(request.from)
+        .bind(actor)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(Some(MergeBrandsResult {
+        printers: printers.rows_affected(),
+        toners: toners.rows_affected(),
+    }))
+}
+
+/// Moves every printer using the discontinued toner `from` to `to`, in one
+/// transaction. Each moved printer's audit entry records the old and new
+/// toner. A replacement of another brand than some of the printers is held to
+/// `TONER_BRAND_VALIDATION`, and `409` is answered while another replacement
+/// runs.
+pub async fn replace_toner(
+    actor: Actor,
+    Path((from, to)): Path<(Uuid, Uuid)>,
+    Extension(deadline): Extension<Deadline>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    // Same toner
+    if from == to {
+        error!("Cannot replace a toner with itself.");
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json("Cannot replace a toner with itself."),
+        ));
+    }
+
+    let mut tx = match state.begin(deadline).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!("Error replacing toner: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error replacing toner."),
+            ));
+        }
+    };
+
+    let checked = async {
+        // Locking both rows keeps them from being deleted halfway through.
+        let found: Vec<(Uuid, Option<Uuid>)> =
+            sqlx::query_as(r#"SELECT id, brand FROM toners WHERE id = ANY($1) FOR UPDATE;"#)
+                .bind([from, to])
+                .fetch_all(&mut *tx)
+                .await?;
+        let (Some((_, to_brand)), 2) = (found.iter().find(|(id, _)| *id == to), found.len()) else {
+            return Ok(None);
+        };
+
+        let brands: Vec<(Uuid,)> =
+            sqlx::query_as(r#"SELECT DISTINCT brand FROM printers WHERE toner = $1;"#)
+                .bind(from)
+                .fetch_all(&mut *tx)
+                .await?;
+        Ok::<_, sqlx::Error>(Some(
+            brands
+                .iter()
+                .any(|(brand,)| printer::toner_brand_mismatch(*brand, *to_brand)),
+        ))
+    }
+    .await;
+
+    let mismatched = match checked {
+        Ok(Some(mismatched)) => mismatched,
+        Ok(None) => {
+            error!("Toner ID not found.");
+            return Err((StatusCode::NOT_FOUND, Json("Toner ID not found.")));
+        }
+        Err(e) => {
+            error!("Error replacing toner: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error replacing toner."),
+            ));
+        }
+    };
+
+    // Toner brand mismatch
+    let warnings: Vec<String> = if mismatched {
+        match validations::soft_violation(
+            state.config.soft_mode(state.config.toner_brand_validation),
+            printer::TONER_BRAND_MISMATCH,
+        ) {
+            Ok(warning) => warning.into_iter().map(String::from).collect(),
+            Err((status, message)) => return Err((status, Json(message))),
+        }
+    } else {
+        Vec::new()
+    };
+
+    // Taken once the request is known to go ahead, so a refused one does not
+    // hold it while its transaction rolls back.
+    let replaced = async {
+        if !AdminOperation::ReplaceToner.try_lock(&mut tx).await? {
+            return Err(JobError::Busy);
+        }
+
+        let changes = serde_json::json!({ "toner": { "old": from, "new": to } });
+        let printers = sqlx::query(
+            r#"
+            WITH changes AS (SELECT set_config('app.changes', $4, true))
+            UPDATE printers SET toner = $2, updated_by = $3
+            WHERE toner = $1 AND EXISTS (SELECT 1 FROM changes);
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(actor.id())
+        .bind(changes.to_string())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(printers.rows_affected())
+    }
+    .await;
+
+    match replaced {
+        Ok(printers) => {
+            info!(
+                "Toner {} replaced with {}! Printers: {}",
+                &from, &to, printers
+            );
+            Ok(Json(ReplaceTonerResult {
+                from,
+                to,
+                printers,
+                warnings,
+            }))
+        }
+        Err(JobError::Busy) => {
+            error!("Toners are already being replaced.");
+            Err((
+                StatusCode::CONFLICT,
+                Json("Toners are already being replaced."),
+            ))
+        }
+        Err(JobError::Database(e)) => {
+            error!("Error replacing toner: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error replacing toner."),
+            ))
+        }
+    }
+}
+
+/// Reports printers referring to brands, toners or drums that no longer
+/// exist, as left behind by manual edits with the foreign keys bypassed.
+pub async fn check_integrity(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match integrity_report(&state.db).await {
+        Ok(report) => {
+            info!(
+                "Integrity checked! Dangling references: {}",
+                report.printers_missing_brand.len()
+                    + report.printers_missing_toner.len()
+                    + report.printers_missing_drum.len()
+            );
+            Ok(Json(report))
+        }
+        Err(e) => {
+            error!("Error checking integrity: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error checking integrity."),
+            ))
+        }
+    }
+}
+
+async fn integrity_report(db: &PgPool) -> Result<IntegrityReport, sqlx::Error> {
+    Ok(IntegrityReport {
+        printers_missing_brand: dangling_printers(db, "brand", "brands").await?,
+        printers_missing_toner: dangling_printers(db, "toner", "toners").await?,
+        printers_missing_drum: dangling_printers(db, "drum", "drums").await?,
+    })
+}
+
+/// Printers whose `column` has no matching row in `table`.
+async fn dangling_printers(
+    db: &PgPool,
+    column: &'static str,
+    table: &'static str,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let ids: Vec<(Uuid,)> = sqlx::query_as(&format!(
+        r#"
+        SELECT printers.id FROM printers
+        LEFT JOIN {table} ON {table}.id = printers.{column}
+        WHERE {table}.id IS NULL
+        ORDER BY printers.id;
+        "#
+    ))
+    .fetch_all(db)
+    .await?;
+
+    Ok(ids.into_iter().map(|(id,)| id).collect())
+}
+
+/// The last mutations handled by this instance, most recent first, for
+/// debugging without access to the logs.
+pub async fn show_recent_ops(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let operations = state.recent_ops.snapshot();
+    info!("Recent operations listed: {}", operations.len());
+    Json(operations)
+}
+
+/// Row counts and on-disk sizes of the catalog tables, counted on each call.
+pub async fn show_table_stats(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match sqlx::query_as::<_, TableStats>(
+        r#"
+        SELECT 'brands' AS "table", COUNT(*) AS rows,
+            pg_total_relation_size('brands') AS total_bytes
+        FROM brands
+        UNION ALL
+        SELECT 'printers', COUNT(*), pg_total_relation_size('printers') FROM printers
+        UNION ALL
+        SELECT 'toners', COUNT(*), pg_total_relation_size('toners') FROM toners
+        UNION ALL
+        SELECT 'drums', COUNT(*), pg_total_relation_size('drums') FROM drums;
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(stats) => {
+            info!("Table stats listed");
+            Ok(Json(stats))
+        }
+        Err(e) => {
+            error!("Error listing table stats: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error listing table stats."),
+            ))
+        }
+    }
+}
+
+/// Configuration this instance runs with, for debugging a deployment. The
+/// database password and the API keys themselves are never included.
+pub async fn show_config(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let config = &state.config;
+    let effective = EffectiveConfig {
+        bind_address: env::var("HOST").ok(),
+        database_url: env::var("DATABASE_URL")
+            .ok()
+            .map(|url| redact_database_url(&url)),
+        db_max_connections: state.db.options().get_max_connections(),
+        db_ssl_mode: config.db_ssl_mode.map(ssl_mode_name),
+        db_ssl_root_cert: config.db_ssl_root_cert.clone(),
+        db_connect_max_attempts: config.db_connect_max_attempts,
+        db_connect_retry_delay_ms: config.db_connect_retry_delay_ms,
+        slow_query_threshold_ms: config.slow_query_threshold_ms,
+        default_page_size: config.default_page_size,
+        max_page_size: config.max_page_size,
+        max_offset: config.max_offset,
+        search_max_results: config.search_max_results,
+        request_timeout_seconds: config.request_timeout_seconds,
+        max_concurrent_requests: config.max_concurrent_requests,
+        max_body_bytes: config.max_body_bytes,
+        max_bulk_body_bytes: config.max_bulk_body_bytes,
+        read_only: config.read_only,
+        idempotent_deletes: config.idempotent_deletes,
+        base_path: config.base_path.clone(),
+        base_path_nest: config.base_path_nest,
+        id_format: config.id_format,
+        require_uuid_v4: config.require_uuid_v4,
+        toner_brand_validation: config.toner_brand_validation,
+        strict_validation: config.strict_validation,
+        name_pattern: config
+            .name_pattern
+            .as_ref()
+            .map(|pattern| pattern.source.clone()),
+        printer_name_scope: config.printer_name_scope,
+        printer_model_min_length: config.printer_model_min_length,
+        printer_model_max_length: config.printer_model_max_length,
+        printer_departments: config.printer_departments.clone(),
+        printer_max_documents: config.printer_max_documents,
+        printer_photo_max_bytes: config.printer_photo_max_bytes,
+        printer_qr_base_url: config.printer_qr_base_url.clone(),
+        printer_draft_ttl_days: config.printer_draft_ttl_days,
+        max_toners_per_brand: config.max_toners_per_brand,
+        reorder_lookback_days: config.reorder_lookback_days,
+        reorder_coverage_days: config.reorder_coverage_days,
+        recent_ops_capacity: config.recent_ops_capacity,
+        count_reconcile_interval_seconds: config.count_reconcile_interval_seconds,
+        snapshot_interval_seconds: config.snapshot_interval_seconds,
+        api_keys: config
+            .api_keys
+            .iter()
+            .map(|key| EffectiveApiKey {
+                id: key.id.clone(),
+                scope: key.scope,
+            })
+            .collect(),
+    };
+
+    info!("Effective configuration retrieved");
+    Json(effective)
+}
+
+/// Placeholder for the secrets left out of `GET /admin/config`.
+const REDACTED: &str = "REDACTED";
+
+/// `url` with its password, in the userinfo or as a `password` parameter,
+/// replaced by `REDACTED`. Unparseable URLs are redacted whole, since they
+/// may still hold a password.
+fn redact_database_url(url: &str) -> String {
+    let Ok(mut url) = Url::parse(url) else {
+        return REDACTED.to_string();
+    };
+
+    if url.password().is_some() {
+        let _ = url.set_password(Some(REDACTED));
+    }
+    if url.query_pairs().any(|(key, _)| key == "password") {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(key, value)| match key.as_ref() {
+                "password" => (key.into_owned(), REDACTED.to_string()),
+                _ => (key.into_owned(), value.into_owned()),
+            })
+            .collect();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+
+    url.to_string()
+}
+
+fn ssl_mode_name(mode: PgSslMode) -> &'static str {
+    match mode {
+        PgSslMode::Disable => "disable",
+        PgSslMode::Allow => "allow",
+        PgSslMode::Prefer => "prefer",
+        PgSslMode::Require => "require",
+        PgSslMode::VerifyCa => "verify-ca",
+        PgSslMode::VerifyFull => "verify-full",
+    }
+}
+
+/// Similarity at or above which `GET /admin/near-duplicate-printers` pairs two
+/// names when no `threshold` is given.
+const DEFAULT_NAME_SIMILARITY: f32 = 0.6;
+
+/// Groups of printers with near-duplicate names, such as "HP LaserJet 400"
+/// and "HP Laserjet400" after bulk imports, for operators to reconcile.
+///
+/// Names are compared by trigram similarity, ignoring case and anything but
+/// letters and digits, through an index on that key. Printers end up in one
+/// group when linked by a chain of similar pairs; groups with the closest
+/// match come first.
+pub async fn show_near_duplicate_printers(
+    Query(params): Query<NearDuplicateParams>,
+    Extension(deadline): Extension<Deadline>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let threshold = params.threshold.unwrap_or(DEFAULT_NAME_SIMILARITY);
+
+    // Threshold out of range
+    if !(threshold > 0.0 && threshold <= 1.0) {
+        error!("Invalid similarity threshold: {threshold}");
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json("`threshold` must be above 0 and at most 1."),
+        ));
+    }
+
+    let pairs = async {
+        let mut tx = state.begin(deadline).await?;
+        // `%` matches at the session's threshold, which lets it use the index.
+        sqlx::query(r#"SELECT set_config('pg_trgm.similarity_threshold', $1, true);"#)
+            .bind(threshold.to_string())
+            .execute(&mut *tx)
+            .await?;
+        let pairs: Vec<(Uuid, String, Uuid, String, f32)> = sqlx::query_as(
+            r#"
+            SELECT printer.id, printer.name, other.id, other.name,
+                similarity(
+                    regexp_replace(lower(printer.name), '[^[:alnum:]]', '', 'g'),
+                    regexp_replace(lower(other.name), '[^[:alnum:]]', '', 'g')
+                )
+            FROM printers AS printer
+            JOIN printers AS other
+                ON printer.id < other.id
+                AND regexp_replace(lower(printer.name), '[^[:alnum:]]', '', 'g')
+                    % regexp_replace(lower(other.name), '[^[:alnum:]]', '', 'g');
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(pairs)
+    }
+    .await;
+
+    match pairs {
+        Ok(pairs) => {
+            let groups = group_near_duplicates(pairs);
+            info!("Near-duplicate printer groups listed: {}", groups.len());
+            Ok(Json(groups))
+        }
+        Err(e) => {
+            error!("Error listing near-duplicate printers: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error listing near-duplicate printers."),
+            ))
+        }
+    }
+}
+
+/// Joins similar pairs into groups of connected printers.
+fn group_near_duplicates(pairs: Vec<(Uuid, String, Uuid, String, f32)>) -> Vec<NearDuplicateGroup> {
+    let mut groups: Vec<Option<NearDuplicateGroup>> = Vec::new();
+    let mut group_of: HashMap<Uuid, usize> = HashMap::new();
+
+    for (printer, printer_name, other, other_name, similarity) in pairs {
+        let index = match (
+            group_of.get(&printer).copied(),
+            group_of.get(&other).copied(),
+        ) {
+            (Some(index), Some(other_index)) if index != other_index => {
+                let merged = groups[other_index].take().unwrap();
+                for member in &merged.printers {
+                    group_of.insert(member.id, index);
+                }
+                let group = groups[index].as_mut().unwrap();
+                group.printers.extend(merged.printers);
+                group.pairs.extend(merged.pairs);
+                index
+            }
+            (Some(index), _) | (None, Some(index)) => index,
+            (None, None) => {
+                groups.push(Some(NearDuplicateGroup {
+                    printers: Vec::new(),
+                    pairs: Vec::new(),
+                }));
+                groups.len() - 1
+            }
+        };
+
+        let group = groups[index].as_mut().unwrap();
+        for (id, name) in [(printer, printer_name), (other, other_name)] {
+            if group_of.insert(id, index).is_none() {
+                group.printers.push(PrinterName { id, name });
+            }
+        }
+        group.pairs.push(SimilarPrinters {
+            printer,
+            other,
+            similarity,
+        });
+    }
+
+    let mut groups: Vec<NearDuplicateGroup> = groups.into_iter().flatten().collect();
+    for group in &mut groups {
+        group
+            .printers
+            .sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        group.pairs.sort_by(|a, b| {
+            b.similarity
+                .total_cmp(&a.similarity)
+                .then(a.printer.cmp(&b.printer))
+                .then(a.other.cmp(&b.other))
+        });
+    }
+    groups.sort_by(|a, b| {
+        b.pairs[0]
+            .similarity
+            .total_cmp(&a.pairs[0].similarity)
+            .then(a.printers[0].name.cmp(&b.printers[0].name))
+    });
+    groups
+}
@@ -0,0 +1,530 @@
+use std::sync::Arc;
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Extension,
+};
+use futures_util::{stream, TryStreamExt};
+use serde::Serialize;
+use sqlx::{
+    postgres::{PgArguments, PgRow},
+    query::Query as SqlQuery,
+    Acquire, FromRow, PgConnection, Postgres, Transaction,
+};
+use tokio::sync::mpsc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    cache,
+    extractors::{Actor, Json},
+    handlers::supplies::toner,
+    jobs::AdminOperation,
+    middleware::deadline::Deadline,
+    models::{
+        backup::{Backup, ImportConflict, ImportCounts, ImportParams, ImportReport},
+        brand::Brand,
+        database::AppState,
+        printer::Printer,
+        supplies::{drum::Drum, toner::Toner},
+    },
+    queries::{self, Table},
+};
+
+type Chunk = Result<Bytes, sqlx::Error>;
+
+/// Streams every brand, toner, drum and printer as a single JSON document
+/// keyed by type, in the shape `POST /admin/import` accepts.
+///
+/// All rows are read from one snapshot, and written out as they are fetched so
+/// the catalog is never buffered whole. The export is bounded by the request
+/// deadline; failing or running out of time halfway through still ends the
+/// document, with an `error` member that `POST /admin/import` refuses.
+pub async fn export_catalog(
+    Extension(deadline): Extension<Deadline>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let mut tx = match state.begin(deadline).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!("Error exporting catalog: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error exporting catalog."),
+            ));
+        }
+    };
+    if let Err(e) = sqlx::query(r#"SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY;"#)
+        .execute(&mut *tx)
+        .await
+    {
+        error!("Error exporting catalog: {e}");
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json("Error exporting catalog."),
+        ));
+    }
+
+    let (sender, receiver) = mpsc::channel::<Chunk>(32);
+    tokio::spawn(async move {
+        let mut progress = Progress::Start;
+        let message = match tokio::time::timeout(
+            deadline.remaining(),
+            write_catalog(&mut tx, &sender, &mut progress),
+        )
+        .await
+        {
+            Ok(Ok(_)) => {
+                info!("Catalog exported");
+                return;
+            }
+            Ok(Err(e)) => {
+                error!("Error exporting catalog: {e}");
+                "Error exporting catalog."
+            }
+            Err(_) => {
+                error!("Catalog export timed out.");
+                "Catalog export timed out."
+            }
+        };
+
+        drop(tx);
+        let separator = match progress {
+            Progress::Start => "",
+            Progress::InSection => "],",
+            Progress::AfterSection => ",",
+        };
+        send(
+            &sender,
+            format!(r#"{separator}"error":{}}}"#, serde_json::json!(message)),
+        )
+        .await;
+    });
+
+    let body = stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    });
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/json")],
+        Body::from_stream(body),
+    ))
+}
+
+/// How far the document has been written, to know how to close it when the
+/// export stops halfway through.
+enum Progress {
+    /// Only the opening brace.
+    Start,
+    /// Inside a section's array.
+    InSection,
+    /// Right after a section's array.
+    AfterSection,
+}
+
+async fn write_catalog(
+    conn: &mut PgConnection,
+    sender: &mpsc::Sender<Chunk>,
+    progress: &mut Progress,
+) -> Result<(), sqlx::Error> {
+    send(sender, "{").await;
+    write_section::<Brand>(
+        conn,
+        sender,
+        progress,
+        "brands",
+        "SELECT * FROM brands ORDER BY id;",
+    )
+    .await?;
+    write_section::<Toner>(
+        conn,
+        sender,
+        progress,
+        "toners",
+        "SELECT * FROM toners ORDER BY id;",
+    )
+    .await?;
+    write_section::<Drum>(
+        conn,
+        sender,
+        progress,
+        "drums",
+        "SELECT * FROM drums ORDER BY id;",
+    )
+    .await?;
+    write_section::<Printer>(
+        conn,
+        sender,
+        progress,
+        "printers",
+        "SELECT * FROM printers ORDER BY id;",
+    )
+    .await?;
+    send(sender, "}").await;
+    Ok(())
+}
+
+/// Writes `"key": [...]` with one element per row of `query`, after a comma
+/// unless it is the first section. Stops early once the client has gone away.
+async fn write_section<T>(
+    conn: &mut PgConnection,
+    sender: &mpsc::Sender<Chunk>,
+    progress: &mut Progress,
+    key: &str,
+    query: &str,
+) -> Result<(), sqlx::Error>
+where
+    T: for<'r> FromRow<'r, PgRow> + Serialize + Send + Unpin,
+{
+    if sender.is_closed() {
+        return Ok(());
+    }
+
+    let separator = match progress {
+        Progress::Start => "",
+        _ => ",",
+    };
+    send(sender, format!(r#"{separator}"{key}":["#)).await;
+    *progress = Progress::InSection;
+
+    let mut rows = sqlx::query_as::<_, T>(query).fetch(&mut *conn);
+    let mut first = true;
+    while let Some(row) = rows.try_next().await? {
+        let separator = if first { "" } else { "," };
+        first = false;
+        if !send(sender, format!("{separator}{}", serde_json::json!(row))).await {
+            return Ok(());
+        }
+    }
+    send(sender, "]").await;
+    *progress = Progress::AfterSection;
+    Ok(())
+}
+
+/// Whether the chunk reached the client.
+async fn send(sender: &mpsc::Sender<Chunk>, chunk: impl Into<Bytes>) -> bool {
+    sender.send(Ok(chunk.into())).await.is_ok()
+}
+
+/// Restores a backup in dependency order, brands, toners and drums before
+/// the printers referring to them, in one transaction.
+///
+/// Rows whose id exists are skipped, or replaced with `?overwrite=true`. Rows
+/// the database refuses, such as a name taken under another id, are rolled
+/// back on their own and reported as conflicts without failing the import.
+pub async fn import_catalog(
+    actor: Actor,
+    Extension(deadline): Extension<Deadline>,
+    Query(params): Query<ImportParams>,
+    State(state): State<Arc<AppState>>,
+    Json(backup): Json<Backup>,
+) -> impl IntoResponse {
+    // Export stopped halfway through
+    if let Some(message) = &backup.error {
+        error!("Backup is incomplete: {message}");
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json("Backup is incomplete; export the catalog again."),
+        ));
+    }
+
+    let mut tx = match state.begin(deadline).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!("Error importing catalog: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error importing catalog."),
+            ));
+        }
+    };
+
+    // Another import in progress
+    match AdminOperation::ImportCatalog.try_lock(&mut tx).await {
+        Ok(true) => {}
+        Ok(false) => {
+            error!("A catalog import is already running.");
+            return Err((
+                StatusCode::CONFLICT,
+                Json("A catalog import is already running."),
+            ));
+        }
+        Err(e) => {
+            error!("Error importing catalog: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error importing catalog."),
+            ));
+        }
+    }
+
+    let report = match restore(
+        &mut tx,
+        &backup,
+        params.overwrite,
+        state.config.max_toners_per_brand,
+        actor.id(),
+    )
+    .await
+    {
+        Ok(report) => report,
+        Err(e) => {
+            error!("Error importing catalog: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error importing catalog."),
+            ));
+        }
+    };
+
+    match tx.commit().await {
+        Ok(_) => {
+            state.cache.invalidate(cache::BRANDS);
+            info!("Catalog imported! Conflicts: {}", report.conflicts.len());
+            Ok(Json(report))
+        }
+        Err(e) => {
+            error!("Error importing catalog: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error importing catalog."),
+            ))
+        }
+    }
+}
+
+/// Outcome of importing a single row.
+enum Imported {
+    Written,
+    Skipped,
+    Refused(String),
+}
+
+async fn restore(
+    tx: &mut Transaction<'static, Postgres>,
+    backup: &Backup,
+    overwrite: bool,
+    max_toners_per_brand: Option<i64>,
+    actor: Option<&str>,
+) -> Result<ImportReport, sqlx::Error> {
+    let mut report = ImportReport::default();
+
+    // `printer_count` is left to the printer triggers.
+    let statement = upsert(
+        r#"
+        INSERT INTO brands
+            (id, name, logo_url, country_code, website_url, support_email, support_phone,
+             created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $6, $7, $8, $5, $5)
+        "#,
+        r#"
+        name = EXCLUDED.name, logo_url = EXCLUDED.logo_url,
+        country_code = EXCLUDED.country_code, website_url = EXCLUDED.website_url,
+        support_email = EXCLUDED.support_email, support_phone = EXCLUDED.support_phone,
+        updated_by = EXCLUDED.updated_by
+        "#,
+        overwrite,
+    );
+    for brand in &backup.brands {
+        let query = sqlx::query(&statement)
+            .bind(brand.id)
+            .bind(&brand.name)
+            .bind(&brand.logo_url)
+            .bind(&brand.country_code)
+            .bind(actor)
+            .bind(&brand.website_url)
+            .bind(&brand.support_email)
+            .bind(&brand.support_phone);
+        let outcome = import_row(tx, query).await?;
+        record(
+            &mut report.brands,
+            &mut report.conflicts,
+            "brand",
+            brand.id,
+            outcome,
+        );
+    }
+
+    let statement = upsert(
+        r#"
+        INSERT INTO toners
+            (id, name, stock, brand, min_stock, color, page_yield, created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $8)
+        "#,
+        r#"
+        name = EXCLUDED.name, stock = EXCLUDED.stock, brand = EXCLUDED.brand,
+        min_stock = EXCLUDED.min_stock, color = EXCLUDED.color,
+        page_yield = EXCLUDED.page_yield, updated_by = EXCLUDED.updated_by
+        "#,
+        overwrite,
+    );
+    for toner in &backup.toners {
+        let query = sqlx::query(&statement)
+            .bind(toner.id)
+            .bind(&toner.name)
+            .bind(toner.stock)
+            .bind(toner.brand)
+            .bind(toner.min_stock)
+            .bind(&toner.color)
+            .bind(toner.page_yield)
+            .bind(actor);
+        let outcome = match (toner.brand, max_toners_per_brand) {
+            (Some(brand), Some(limit))
+                if brand_full(tx, brand, toner.id, limit, overwrite).await? =>
+            {
+                Imported::Refused(String::from(
+                    "Toner brand already has the maximum number of toners.",
+                ))
+            }
+            _ => import_row(tx, query).await?,
+        };
+        record(
+            &mut report.toners,
+            &mut report.conflicts,
+            "toner",
+            toner.id,
+            outcome,
+        );
+    }
+
+    let statement = upsert(
+        r#"
+        INSERT INTO drums (id, name, stock, created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $4)
+        "#,
+        r#"name = EXCLUDED.name, stock = EXCLUDED.stock, updated_by = EXCLUDED.updated_by"#,
+        overwrite,
+    );
+    for drum in &backup.drums {
+        let query = sqlx::query(&statement)
+            .bind(drum.id)
+            .bind(&drum.name)
+            .bind(drum.stock)
+            .bind(actor);
+        let outcome = import_row(tx, query).await?;
+        record(
+            &mut report.drums,
+            &mut report.conflicts,
+            "drum",
+            drum.id,
+            outcome,
+        );
+    }
+
+    let statement = upsert(
+        r#"
+        INSERT INTO printers
+            (id, name, model, brand, toner, drum, owner_department, page_count, status,
+             decommissioned_at, decommission_reason, acquisition_type, lease_end_date,
+             connection_type, created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $15)
+        "#,
+        r#"
+        name = EXCLUDED.name, model = EXCLUDED.model, brand = EXCLUDED.brand,
+        toner = EXCLUDED.toner, drum = EXCLUDED.drum, owner_department = EXCLUDED.owner_department,
+        page_count = EXCLUDED.page_count, status = EXCLUDED.status,
+        decommissioned_at = EXCLUDED.decommissioned_at,
+        decommission_reason = EXCLUDED.decommission_reason,
+        acquisition_type = EXCLUDED.acquisition_type, lease_end_date = EXCLUDED.lease_end_date,
+        connection_type = EXCLUDED.connection_type,
+        updated_by = EXCLUDED.updated_by
+        "#,
+        overwrite,
+    );
+    for printer in &backup.printers {
+        let query = sqlx::query(&statement)
+            .bind(printer.id)
+            .bind(&printer.name)
+            .bind(&printer.model)
+            .bind(printer.brand)
+            .bind(printer.toner)
+            .bind(printer.drum)
+            .bind(&printer.owner_department)
+            .bind(printer.page_count)
+            .bind(printer.status)
+            .bind(printer.decommissioned_at)
+            .bind(&printer.decommission_reason)
+            .bind(printer.acquisition_type)
+            .bind(printer.lease_end_date)
+            .bind(printer.connection_type)
+            .bind(actor);
+        let outcome = import_row(tx, query).await?;
+        record(
+            &mut report.printers,
+            &mut report.conflicts,
+            "printer",
+            printer.id,
+            outcome,
+        );
+    }
+
+    Ok(report)
+}
+
+/// Appends the `ON CONFLICT (id)` clause matching the overwrite mode.
+fn upsert(insert: &str, update: &str, overwrite: bool) -> String {
+    if overwrite {
+        format!("{insert} ON CONFLICT (id) DO UPDATE SET {update};")
+    } else {
+        format!("{insert} ON CONFLICT (id) DO NOTHING;")
+    }
+}
+
+/// Whether writing the toner `id` would take `brand` past `MAX_TONERS_PER_BRAND`.
+/// Existing toners are only written with `overwrite`, so otherwise they take
+/// no place.
+async fn brand_full(
+    tx: &mut Transaction<'static, Postgres>,
+    brand: Uuid,
+    id: Uuid,
+    limit: i64,
+    overwrite: bool,
+) -> Result<bool, sqlx::Error> {
+    if !overwrite && queries::exists(&mut **tx, Table::Toners, id).await? {
+        return Ok(false);
+    }
+
+    let count = toner::moved_toner_count(tx, brand, id).await?;
+    Ok(count.is_some_and(|count| count >= limit))
+}
+
+/// Runs one row's statement inside a savepoint, so a refused row does not
+/// abort the rest of the import.
+async fn import_row(
+    tx: &mut Transaction<'static, Postgres>,
+    query: SqlQuery<'_, Postgres, PgArguments>,
+) -> Result<Imported, sqlx::Error> {
+    let mut savepoint = tx.begin().await?;
+    match query.execute(&mut *savepoint).await {
+        Ok(result) => {
+            savepoint.commit().await?;
+            if result.rows_affected() > 0 {
+                Ok(Imported::Written)
+            } else {
+                Ok(Imported::Skipped)
+            }
+        }
+        Err(sqlx::Error::Database(e)) => {
+            savepoint.rollback().await?;
+            Ok(Imported::Refused(e.message().to_string()))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn record(
+    counts: &mut ImportCounts,
+    conflicts: &mut Vec<ImportConflict>,
+    kind: &'static str,
+    id: Uuid,
+    outcome: Imported,
+) {
+    match outcome {
+        Imported::Written => counts.imported += 1,
+        Imported::Skipped => counts.skipped += 1,
+        Imported::Refused(reason) => conflicts.push(ImportConflict { kind, id, reason }),
+    }
+}
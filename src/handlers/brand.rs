@@ -1,29 +1,68 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::IntoResponse,
-    Json,
+    extract::{OriginalUri, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Extension,
 };
 use tracing::{error, info};
 use uuid::Uuid;
 
-use crate::models::{
-    brand::{Brand, CreateBrandRequest, UpdateBrandRequest},
-    database::AppState,
-    DeleteRequest,
+use crate::{
+    audit, cache,
+    extractors::{Actor, Json, Path},
+    handlers::{deleted, location},
+    middleware::deadline::Deadline,
+    models::{
+        audit::Audited,
+        brand::{
+            self, Brand, BrandCountryCount, BrandDetail, BrandDetailTotals, CreateBrandRequest,
+            NameAvailability, NameAvailabilityParams, UpdateBrandRequest, UpsertBrandRequest,
+            BRAND_FIELDS,
+        },
+        database::AppState,
+        error::ApiError,
+        fields::FieldsParams,
+        jsonapi::{self, Document},
+        pagination::{Page, PaginationParams},
+        printer::Printer,
+        supplies::{drum::Drum, toner::Toner},
+        DeleteRequest,
+    },
+    queries::{self, Table},
+    validations::{
+        self, Named, MAX_EMAIL_LENGTH, MAX_PHONE_LENGTH, MAX_URL_LENGTH, NAME_MAX_LENGTH,
+        NAME_MIN_LENGTH,
+    },
 };
 
+/// Brand names are compared trimmed, lowercased and without diacritics, so
+/// "Épson" and "epson " count as the same brand. The expression is the one
+/// `brands_normalized_name_key` indexes, which makes it usable as an upsert
+/// conflict target.
+const NORMALIZED_NAME: &str = "normalize_name(name)";
+/// The same normalization, applied to the `$1` parameter.
+const NORMALIZED_INPUT: &str = "normalize_name($1)";
+
 pub async fn count_brands(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let brand_count: Result<(i32,), sqlx::Error> =
-        sqlx::query_as(r#"SELECT COUNT(*)::int FROM brands;"#)
-            .fetch_one(&state.db)
-            .await;
+    if let Some(count) = state.cache.get(cache::BRANDS, "count") {
+        info!("Brand count served from cache");
+        return Ok(Json(count));
+    }
+
+    let generation = state.cache.generation();
+    let brand_count: Result<(i64,), sqlx::Error> = sqlx::query_as(queries::COUNT_BRANDS)
+        .fetch_one(&state.db)
+        .await;
 
     match brand_count {
         Ok((count,)) => {
             info!("Successfully retrieved brand count: {}", count);
+            let count = serde_json::json!(count);
+            state
+                .cache
+                .insert(cache::BRANDS, "count", count.clone(), generation);
             Ok(Json(count))
         }
         Err(e) => {
@@ -36,64 +75,373 @@ pub async fn count_brands(State(state): State<Arc<AppState>>) -> impl IntoRespon
     }
 }
 
+/// Brand count per country, largest first, with brands without a country
+/// grouped last.
+pub async fn count_brands_by_country(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let counts: Result<Vec<BrandCountryCount>, sqlx::Error> = sqlx::query_as(
+        r#"
+        SELECT country_code, COUNT(*) AS count
+        FROM brands
+        GROUP BY country_code
+        ORDER BY count DESC, country_code ASC NULLS LAST;
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    match counts {
+        Ok(counts) => {
+            info!(
+                "Brand count by country retrieved: {} countries",
+                counts.len()
+            );
+            Ok(Json(counts))
+        }
+        Err(e) => {
+            error!("Error retrieving brand count by country: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error retrieving brand count by country."),
+            ))
+        }
+    }
+}
+
 pub async fn search_brand(
     Path(id): Path<Uuid>,
+    Query(fields): Query<FieldsParams>,
+    actor: Actor,
+    headers: HeaderMap,
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
-    match sqlx::query_as::<_, Brand>(r#"SELECT * FROM brands WHERE id = $1;"#)
+    let fields = match fields.resolve(BRAND_FIELDS) {
+        Ok(fields) => fields,
+        Err(message) => {
+            error!("Invalid brand fields: {message}");
+            return (StatusCode::BAD_REQUEST, Json(message).into_response());
+        }
+    };
+
+    let json_api = jsonapi::requested(&headers);
+
+    let brand = sqlx::query(queries::BRAND_BY_ID)
         .bind(id)
         .fetch_optional(&state.db)
         .await
-    {
+        .and_then(|row| {
+            row.map(|row| Audited::<Brand>::from_row(&row, actor.is_admin()))
+                .transpose()
+        });
+
+    match brand {
         Ok(Some(brand)) => {
             info!("Brand found: {id}");
-            (StatusCode::OK, Json(Some(brand)))
+            let mut body = if json_api {
+                serde_json::json!(Document::single(&brand))
+            } else {
+                serde_json::json!(brand)
+            };
+            if let Some(fields) = &fields {
+                match body.get_mut("data") {
+                    Some(resource) => fields.retain(resource),
+                    None => fields.retain(&mut body),
+                }
+            }
+            (StatusCode::OK, jsonapi::respond(json_api, body))
         }
         Ok(None) => {
             error!("No brand found.");
-            (StatusCode::NOT_FOUND, Json(None))
+            (StatusCode::NOT_FOUND, Json(None::<Brand>).into_response())
         }
         Err(e) => {
             error!("Error retrieving brand: {e}");
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(None))
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(None::<Brand>).into_response(),
+            )
         }
     }
 }
 
-pub async fn show_brands(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let brands: Result<Vec<Brand>, sqlx::Error> = sqlx::query_as(r#"SELECT * FROM brands;"#)
-        .fetch_all(&state.db)
-        .await;
-    match brands {
+/// Reads the brand and its printers, toners and drums with one query each,
+/// whatever the number of related rows, in a single snapshot. Each list holds
+/// at most `MAX_PAGE_SIZE` rows, with the full counts under `totals`.
+pub async fn show_brand_detail(
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    Extension(deadline): Extension<Deadline>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let limit = state.config.max_page_size;
+    let detail = async {
+        let mut tx = state.begin(deadline).await?;
+        sqlx::query(r#"SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY;"#)
+            .execute(&mut *tx)
+            .await?;
+
+        let Some(row) = sqlx::query(queries::BRAND_BY_ID)
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let brand = Audited::<Brand>::from_row(&row, actor.is_admin())?;
+
+        let printers = sqlx::query_as::<_, Printer>(
+            r#"SELECT * FROM printers WHERE brand = $1 ORDER BY id LIMIT $2;"#,
+        )
+        .bind(id)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+        let toners = sqlx::query_as::<_, Toner>(
+            r#"
+            SELECT * FROM toners
+            WHERE brand = $1 OR id IN (SELECT toner FROM printers WHERE brand = $1)
+            ORDER BY id
+            LIMIT $2;
+            "#,
+        )
+        .bind(id)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+        let drums = sqlx::query_as::<_, Drum>(
+            r#"
+            SELECT * FROM drums
+            WHERE id IN (SELECT drum FROM printers WHERE brand = $1)
+            ORDER BY id
+            LIMIT $2;
+            "#,
+        )
+        .bind(id)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+        let totals = sqlx::query_as::<_, BrandDetailTotals>(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM printers WHERE brand = $1) AS printers,
+                (SELECT COUNT(*) FROM toners
+                 WHERE brand = $1 OR id IN (SELECT toner FROM printers WHERE brand = $1)) AS toners,
+                (SELECT COUNT(*) FROM drums
+                 WHERE id IN (SELECT drum FROM printers WHERE brand = $1)) AS drums;
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(Some(BrandDetail {
+            brand,
+            printers,
+            toners,
+            drums,
+            totals,
+        }))
+    }
+    .await;
+
+    match detail {
+        Ok(Some(detail)) => {
+            info!("Brand detail found: {id}");
+            Ok(Json(detail))
+        }
+        Ok(None) => {
+            error!("Brand ID not found.");
+            Err((StatusCode::NOT_FOUND, Json("Brand ID not found.")))
+        }
+        Err(e) => {
+            error!("Error retrieving brand detail: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error retrieving brand detail."),
+            ))
+        }
+    }
+}
+
+pub async fn show_brands(
+    Query(pagination): Query<PaginationParams>,
+    Query(fields): Query<FieldsParams>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let (limit, offset) = match pagination.resolve(&state.config) {
+        Ok(window) => window,
+        Err(message) => {
+            error!("Invalid pagination: {message}");
+            return Err((StatusCode::BAD_REQUEST, Json(message.into())));
+        }
+    };
+
+    let fields = match fields.resolve(BRAND_FIELDS) {
+        Ok(fields) => fields,
+        Err(message) => {
+            error!("Invalid brand fields: {message}");
+            return Err((StatusCode::BAD_REQUEST, Json(message)));
+        }
+    };
+
+    // Only the window is cached; links, fields and the JSON:API shape are
+    // built per request, so other query parameters do not fragment the cache.
+    let cache_key = format!("list:{limit}:{offset}");
+    let cached = state
+        .cache
+        .get(cache::BRANDS, &cache_key)
+        .and_then(|window| serde_json::from_value::<(Vec<Brand>, i64)>(window).ok());
+    let window = match cached {
+        Some(window) => {
+            info!("Brands served from cache");
+            Ok(window)
+        }
+        None => {
+            let generation = state.cache.generation();
+            let total: Result<(i64,), sqlx::Error> = sqlx::query_as(queries::COUNT_BRANDS)
+                .fetch_one(&state.db)
+                .await;
+            let brands: Result<Vec<Brand>, sqlx::Error> = sqlx::query_as(queries::LIST_BRANDS)
+                .bind(limit)
+                .bind(offset)
+                .fetch_all(&state.db)
+                .await;
+            match (brands, total) {
+                (Ok(brands), Ok((total,))) => {
+                    info!("Brands listed successfully");
+                    let window = (brands, total);
+                    state.cache.insert(
+                        cache::BRANDS,
+                        &cache_key,
+                        serde_json::json!(window),
+                        generation,
+                    );
+                    Ok(window)
+                }
+                (Err(e), _) | (_, Err(e)) => Err(e),
+            }
+        }
+    };
+
+    let json_api = jsonapi::requested(&headers);
+    match window {
+        Ok((brands, total)) => {
+            let page = Page::new(brands, total, limit, offset, &uri);
+            let mut page = if json_api {
+                serde_json::json!(Document::page(page))
+            } else {
+                serde_json::json!(page)
+            };
+            if let (Some(fields), Some(brands)) = (&fields, page["data"].as_array_mut()) {
+                brands.iter_mut().for_each(|brand| fields.retain(brand));
+            }
+            Ok(jsonapi::respond(json_api, page))
+        }
+        Err(e) => {
+            error!("Error listing brands: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error listing brands.".into()),
+            ))
+        }
+    }
+}
+
+/// Brands that no printer refers to, candidates for catalog cleanup.
+pub async fn show_unused_brands(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match sqlx::query_as::<_, Brand>(
+        r#"
+        SELECT brands.* FROM brands
+        LEFT JOIN printers ON printers.brand = brands.id
+        WHERE printers.id IS NULL
+        ORDER BY brands.name;
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await
+    {
         Ok(brands) => {
-            info!("Brands listed successfully");
+            info!("Unused brands listed: {}", brands.len());
             Ok(Json(brands))
         }
         Err(e) => {
-            error!("Error listing brands: {e}");
+            error!("Error listing unused brands: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error listing unused brands."),
+            ))
+        }
+    }
+}
+
+/// Tells whether `name` is free, using the same normalized comparison as
+/// create, so the answer matches what a create would do.
+pub async fn check_brand_name(
+    Query(params): Query<NameAvailabilityParams>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match sqlx::query_as::<_, NameAvailability>(&format!(
+        r#"
+        SELECT
+            {NORMALIZED_INPUT} AS normalized,
+            NOT EXISTS (
+                SELECT 1 FROM brands WHERE {NORMALIZED_NAME} = {NORMALIZED_INPUT}
+            ) AS available;
+        "#
+    ))
+    .bind(&params.name)
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(availability) => {
+            info!(
+                "Brand name '{}' available: {}",
+                &params.name, availability.available
+            );
+            Ok(Json(availability))
+        }
+        Err(e) => {
+            error!("Error checking brand name availability: {e}");
             Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json("Error listing brands."),
+                Json("Error checking brand name availability."),
             ))
         }
     }
 }
 
 pub async fn create_brand(
+    actor: Actor,
     State(state): State<Arc<AppState>>,
     Json(request): Json<CreateBrandRequest>,
 ) -> impl IntoResponse {
-    let new_brand = Brand::new(&request.name);
+    let new_brand = Brand {
+        website_url: request.website_url,
+        support_email: request.support_email,
+        support_phone: request.support_phone,
+        ..Brand::new(
+            request.name.trim(),
+            request.logo_url.as_deref(),
+            request.country_code.as_deref(),
+        )
+    };
 
     // Check duplicate
-    match sqlx::query(r#"SELECT id FROM brands WHERE name = $1;"#)
-        .bind(&new_brand.name)
-        .fetch_optional(&state.db)
-        .await
+    match sqlx::query(&format!(
+        r#"SELECT id FROM brands WHERE {NORMALIZED_NAME} = {NORMALIZED_INPUT};"#
+    ))
+    .bind(&new_brand.name)
+    .fetch_optional(&state.db)
+    .await
     {
         Ok(Some(_)) => {
             error!("Brand '{}' already exists.", &new_brand.name);
-            (StatusCode::CONFLICT, Err(Json("Brand already exists.")))
+            (
+                StatusCode::CONFLICT,
+                Err(Json(ApiError::duplicate_name("Brand already exists."))),
+            )
         }
         Ok(None) => {
             // Name is empty
@@ -101,99 +449,239 @@ pub async fn create_brand(
                 error!("Brand name cannot be empty.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Brand name cannot be empty.")),
+                    Err(Json(ApiError::name_empty("Brand name cannot be empty."))),
                 );
             }
 
             // Name too short
-            if new_brand.name.len() < 4 {
+            if new_brand.name.chars().count() < NAME_MIN_LENGTH {
                 error!("Brand name is too short.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Brand name is too short.")),
+                    Err(Json(ApiError::name_too_short(
+                        "Brand name is too short.",
+                        NAME_MIN_LENGTH,
+                        new_brand.name.chars().count(),
+                    ))),
                 );
             }
 
             // Name too long
-            if new_brand.name.len() > 20 {
+            if new_brand.name.chars().count() > NAME_MAX_LENGTH {
                 error!("Brand name is too long.");
-                return (StatusCode::BAD_REQUEST, Err(Json("Drum name is too long.")));
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Err(Json(ApiError::name_too_long(
+                        "Brand name is too long.",
+                        NAME_MAX_LENGTH,
+                        new_brand.name.chars().count(),
+                    ))),
+                );
+            }
+
+            // Name has characters that are not allowed
+            if let Err(e) =
+                validations::check_name_pattern(&state.config, Named::Brand, &new_brand.name)
+            {
+                error!("{}", e.message());
+                return (StatusCode::BAD_REQUEST, Err(Json(e)));
+            }
+
+            // Invalid logo URL
+            if let Some(logo_url) = &new_brand.logo_url {
+                if logo_url.len() > MAX_URL_LENGTH {
+                    error!("Brand logo URL is too long.");
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Err(Json("Brand logo URL is too long.".into())),
+                    );
+                }
+
+                if !validations::is_http_url(logo_url) {
+                    error!("Brand logo URL is invalid: {}", logo_url);
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Err(Json("Brand logo URL must be a valid http(s) URL.".into())),
+                    );
+                }
+            }
+
+            // Unknown country code
+            if let Err(message) = check_country_code(new_brand.country_code.as_deref()) {
+                error!("{message}");
+                return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+            }
+
+            // Invalid website or support contact
+            if let Err(message) = check_contact(
+                new_brand.website_url.as_deref(),
+                new_brand.support_email.as_deref(),
+                new_brand.support_phone.as_deref(),
+            ) {
+                error!("{message}");
+                return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
             }
 
             match sqlx::query(
                 r#"
-                INSERT INTO brands (id, name)
-                VALUES ($1, $2)
+                INSERT INTO brands
+                    (id, name, logo_url, country_code, website_url, support_email, support_phone,
+                     created_by, updated_by)
+                VALUES ($1, $2, $3, $4, $6, $7, $8, $5, $5)
                 "#,
             )
             .bind(new_brand.id)
             .bind(&new_brand.name)
+            .bind(&new_brand.logo_url)
+            .bind(&new_brand.country_code)
+            .bind(actor.id())
+            .bind(&new_brand.website_url)
+            .bind(&new_brand.support_email)
+            .bind(&new_brand.support_phone)
             .execute(&state.db)
             .await
             {
                 Ok(_) => {
+                    state.cache.invalidate(cache::BRANDS);
                     info!("Brand created! ID: {}", &new_brand.id);
-                    (StatusCode::CREATED, Ok(Json(new_brand.id)))
+                    (
+                        StatusCode::CREATED,
+                        Ok((
+                            location(format!("/api/v1/brand/{}", new_brand.id)),
+                            Json(new_brand.id),
+                        )),
+                    )
+                }
+                // Another request created the brand since the check above.
+                Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                    error!("Brand '{}' already exists: {}", &new_brand.name, e);
+                    (
+                        StatusCode::CONFLICT,
+                        Err(Json(ApiError::duplicate_name("Brand already exists."))),
+                    )
                 }
                 Err(e) => {
                     error!("Error creating brand: {}", e);
                     (
                         StatusCode::INTERNAL_SERVER_ERROR,
-                        Err(Json("Error creating brand.")),
+                        Err(Json("Error creating brand.".into())),
                     )
                 }
             }
         }
         Err(_) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Err(Json("Error creating brand.")),
+            Err(Json("Error creating brand.".into())),
         ),
     }
 }
 
 pub async fn update_brand(
+    actor: Actor,
     State(state): State<Arc<AppState>>,
     Json(request): Json<UpdateBrandRequest>,
 ) -> impl IntoResponse {
     let brand_id = request.id;
-    let new_name = request.name;
+    let new_name = request.name.trim().to_string();
+    let new_logo_url = request.logo_url;
+    let new_country_code = request
+        .country_code
+        .as_deref()
+        .map(brand::normalize_country_code);
+    let new_website_url = request.website_url;
+    let new_support_email = request.support_email;
+    let new_support_phone = request.support_phone;
+
+    // Invalid id
+    if let Err(message) = validations::check_ids([brand_id], state.config.require_uuid_v4) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+    }
 
     // ID not found
-    match sqlx::query(r#"SELECT id FROM brands WHERE id = $1;"#)
-        .bind(brand_id)
-        .fetch_optional(&state.db)
-        .await
-    {
-        Ok(Some(_)) => {
+    match queries::exists(&state.db, Table::Brands, brand_id).await {
+        Ok(true) => {
             // Name is empty
             if new_name.is_empty() {
                 error!("Brand name cannot be empty.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Brand name cannot be empty.")),
+                    Err(Json(ApiError::name_empty("Brand name cannot be empty."))),
                 );
             }
 
             // Name too short
-            if new_name.len() < 4 {
+            if new_name.chars().count() < NAME_MIN_LENGTH {
                 error!("Brand name is too short.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Brand name is too short.")),
+                    Err(Json(ApiError::name_too_short(
+                        "Brand name is too short.",
+                        NAME_MIN_LENGTH,
+                        new_name.chars().count(),
+                    ))),
                 );
             }
 
             // Name too long
-            if new_name.len() > 20 {
+            if new_name.chars().count() > NAME_MAX_LENGTH {
                 error!("Brand name is too long.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Brand name is too long.")),
+                    Err(Json(ApiError::name_too_long(
+                        "Brand name is too long.",
+                        NAME_MAX_LENGTH,
+                        new_name.chars().count(),
+                    ))),
                 );
             }
 
+            // Name has characters that are not allowed
+            if let Err(e) = validations::check_name_pattern(&state.config, Named::Brand, &new_name)
+            {
+                error!("{}", e.message());
+                return (StatusCode::BAD_REQUEST, Err(Json(e)));
+            }
+
+            // Invalid logo URL
+            if let Some(logo_url) = &new_logo_url {
+                if logo_url.len() > MAX_URL_LENGTH {
+                    error!("Brand logo URL is too long.");
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Err(Json("Brand logo URL is too long.".into())),
+                    );
+                }
+
+                if !validations::is_http_url(logo_url) {
+                    error!("Brand logo URL is invalid: {}", logo_url);
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Err(Json("Brand logo URL must be a valid http(s) URL.".into())),
+                    );
+                }
+            }
+
+            // Unknown country code
+            if let Err(message) = check_country_code(new_country_code.as_deref()) {
+                error!("{message}");
+                return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+            }
+
+            // Invalid website or support contact
+            if let Err(message) = check_contact(
+                new_website_url.as_deref(),
+                new_support_email.as_deref(),
+                new_support_phone.as_deref(),
+            ) {
+                error!("{message}");
+                return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+            }
+
             // Check duplicate
-            match sqlx::query(r#"SELECT id FROM brands WHERE name = $1 AND id != $2;"#)
+            match sqlx::query(&format!(
+                r#"SELECT id FROM brands WHERE {NORMALIZED_NAME} = {NORMALIZED_INPUT} AND id != $2;"#
+            ))
                 .bind(&new_name)
                 .bind(brand_id)
                 .fetch_optional(&state.db)
@@ -201,24 +689,55 @@ pub async fn update_brand(
             {
                 Ok(Some(_)) => {
                     error!("Brand name already exists.");
-                    (StatusCode::CONFLICT, Err(Json("Brand already exists.")))
+                    (StatusCode::CONFLICT, Err(Json(ApiError::duplicate_name("Brand already exists."))))
                 }
                 Ok(None) => {
-                    match sqlx::query(r#"UPDATE brands SET name = $1 WHERE id = $2;"#)
-                        .bind(&new_name)
-                        .bind(brand_id)
-                        .execute(&state.db)
-                        .await
+                    // Submitting the stored values writes nothing, leaving
+                    // `updated_at` and the audit log untouched.
+                    match sqlx::query(
+                        r#"
+                        UPDATE brands
+                        SET name = $1, logo_url = $2, country_code = $5, website_url = $6,
+                            support_email = $7, support_phone = $8, updated_by = $4
+                        WHERE id = $3
+                            AND (name, logo_url, country_code, website_url, support_email,
+                                support_phone)
+                            IS DISTINCT FROM ($1, $2, $5, $6, $7, $8);
+                        "#,
+                    )
+                    .bind(&new_name)
+                    .bind(&new_logo_url)
+                    .bind(brand_id)
+                    .bind(actor.id())
+                    .bind(&new_country_code)
+                    .bind(&new_website_url)
+                    .bind(&new_support_email)
+                    .bind(&new_support_phone)
+                    .execute(&state.db)
+                    .await
                     {
+                        Ok(result) if result.rows_affected() == 0 => {
+                            info!("Brand {} unchanged", &brand_id);
+                            (StatusCode::OK, Ok(Json(brand_id)))
+                        }
                         Ok(_) => {
+                            state.cache.invalidate(cache::BRANDS);
                             info!("Brand updated! ID: {}", &brand_id);
                             (StatusCode::OK, Ok(Json(brand_id)))
                         }
+                        // Another request took the name since the check above.
+                        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                            error!("Brand name already exists: {}", e);
+                            (
+                                StatusCode::CONFLICT,
+                                Err(Json(ApiError::duplicate_name("Brand already exists."))),
+                            )
+                        }
                         Err(e) => {
                             error!("Error updating brand: {}", e);
                             (
                                 StatusCode::INTERNAL_SERVER_ERROR,
-                                Err(Json("Error updating drum.")),
+                                Err(Json("Error updating drum.".into())),
                             )
                         }
                     }
@@ -227,63 +746,283 @@ pub async fn update_brand(
                     error!("Error checking for duplicate brand name: {}", e);
                     (
                         StatusCode::INTERNAL_SERVER_ERROR,
-                        Err(Json("Error checking for duplicated brand name.")),
+                        Err(Json("Error checking for duplicated brand name.".into())),
                     )
                 }
             }
         }
-        Ok(None) => {
+        Ok(false) => {
             error!("Brand ID not found.");
-            (StatusCode::NOT_FOUND, Err(Json("Brand ID not found.")))
+            (
+                StatusCode::NOT_FOUND,
+                Err(Json("Brand ID not found.".into())),
+            )
         }
         Err(e) => {
             error!("Error fetching brand by ID: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Err(Json("Error fetching brand by ID")),
+                Err(Json("Error fetching brand by ID".into())),
             )
         }
     }
 }
 
-pub async fn delete_brand(
+/// Creates the brand if no brand with the same name exists, otherwise updates it.
+///
+/// The decision is made by Postgres in a single statement, so concurrent syncs
+/// cannot race each other into duplicates. `xmax = 0` is only true for freshly
+/// inserted rows, which tells us which status code to answer with.
+pub async fn upsert_brand(
+    actor: Actor,
     State(state): State<Arc<AppState>>,
-    Json(request): Json<DeleteRequest>,
+    Json(request): Json<UpsertBrandRequest>,
 ) -> impl IntoResponse {
-    match sqlx::query(r#"SELECT id FROM brands WHERE id = $1;"#)
-        .bind(request.id)
-        .fetch_optional(&state.db)
-        .await
+    let new_brand = Brand {
+        website_url: request.website_url,
+        support_email: request.support_email,
+        support_phone: request.support_phone,
+        ..Brand::new(
+            request.name.trim(),
+            request.logo_url.as_deref(),
+            request.country_code.as_deref(),
+        )
+    };
+
+    // Name is empty
+    if new_brand.name.is_empty() {
+        error!("Brand name cannot be empty.");
+        return (
+            StatusCode::BAD_REQUEST,
+            Err(Json(ApiError::name_empty("Brand name cannot be empty."))),
+        );
+    }
+
+    // Name too short
+    if new_brand.name.chars().count() < NAME_MIN_LENGTH {
+        error!("Brand name is too short.");
+        return (
+            StatusCode::BAD_REQUEST,
+            Err(Json(ApiError::name_too_short(
+                "Brand name is too short.",
+                NAME_MIN_LENGTH,
+                new_brand.name.chars().count(),
+            ))),
+        );
+    }
+
+    // Name too long
+    if new_brand.name.chars().count() > NAME_MAX_LENGTH {
+        error!("Brand name is too long.");
+        return (
+            StatusCode::BAD_REQUEST,
+            Err(Json(ApiError::name_too_long(
+                "Brand name is too long.",
+                NAME_MAX_LENGTH,
+                new_brand.name.chars().count(),
+            ))),
+        );
+    }
+
+    // Name has characters that are not allowed
+    if let Err(e) = validations::check_name_pattern(&state.config, Named::Brand, &new_brand.name) {
+        error!("{}", e.message());
+        return (StatusCode::BAD_REQUEST, Err(Json(e)));
+    }
+
+    // Invalid logo URL
+    if let Some(logo_url) = &new_brand.logo_url {
+        if logo_url.len() > MAX_URL_LENGTH {
+            error!("Brand logo URL is too long.");
+            return (
+                StatusCode::BAD_REQUEST,
+                Err(Json("Brand logo URL is too long.".into())),
+            );
+        }
+
+        if !validations::is_http_url(logo_url) {
+            error!("Brand logo URL is invalid: {}", logo_url);
+            return (
+                StatusCode::BAD_REQUEST,
+                Err(Json("Brand logo URL must be a valid http(s) URL.".into())),
+            );
+        }
+    }
+
+    // Unknown country code
+    if let Err(message) = check_country_code(new_brand.country_code.as_deref()) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+    }
+
+    // Invalid website or support contact
+    if let Err(message) = check_contact(
+        new_brand.website_url.as_deref(),
+        new_brand.support_email.as_deref(),
+        new_brand.support_phone.as_deref(),
+    ) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+    }
+
+    // "Epson" updates a stored "epson ", as create would call them the same.
+    match sqlx::query_as::<_, (Uuid, bool)>(&format!(
+        r#"
+        INSERT INTO brands
+            (id, name, logo_url, country_code, website_url, support_email, support_phone,
+             created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $6, $7, $8, $5, $5)
+        ON CONFLICT (({NORMALIZED_NAME})) DO UPDATE
+        SET logo_url = EXCLUDED.logo_url, country_code = EXCLUDED.country_code,
+            website_url = EXCLUDED.website_url, support_email = EXCLUDED.support_email,
+            support_phone = EXCLUDED.support_phone, updated_by = EXCLUDED.updated_by
+        RETURNING id, (xmax = 0) AS inserted;
+        "#
+    ))
+    .bind(new_brand.id)
+    .bind(&new_brand.name)
+    .bind(&new_brand.logo_url)
+    .bind(&new_brand.country_code)
+    .bind(actor.id())
+    .bind(&new_brand.website_url)
+    .bind(&new_brand.support_email)
+    .bind(&new_brand.support_phone)
+    .fetch_one(&state.db)
+    .await
     {
-        Ok(Some(_)) => {
-            match sqlx::query(r#"DELETE FROM brands WHERE id = $1;"#)
+        Ok((id, true)) => {
+            state.cache.invalidate(cache::BRANDS);
+            info!("Brand created! ID: {}", &id);
+            (
+                StatusCode::CREATED,
+                Ok((location(format!("/api/v1/brand/{id}")), Json(id))),
+            )
+        }
+        Ok((id, false)) => {
+            state.cache.invalidate(cache::BRANDS);
+            info!("Brand updated! ID: {}", &id);
+            (
+                StatusCode::OK,
+                Ok((location(format!("/api/v1/brand/{id}")), Json(id))),
+            )
+        }
+        // Another request took the name in a way the conflict target does not
+        // resolve, such as on `brands_name_key`.
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            error!("Brand '{}' already exists: {}", &new_brand.name, e);
+            (
+                StatusCode::CONFLICT,
+                Err(Json(ApiError::duplicate_name("Brand already exists."))),
+            )
+        }
+        Err(e) => {
+            error!("Error upserting brand: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error upserting brand.".into())),
+            )
+        }
+    }
+}
+
+pub async fn delete_brand(
+    actor: Actor,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<DeleteRequest>,
+) -> Response {
+    // Invalid id
+    if let Err(message) = validations::check_ids([request.id], state.config.require_uuid_v4) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Json(message)).into_response();
+    }
+
+    match queries::exists(&state.db, Table::Brands, request.id).await {
+        Ok(true) => {
+            match sqlx::query(&audit::delete_statement("brands"))
                 .bind(request.id)
+                .bind(actor.id())
                 .execute(&state.db)
                 .await
             {
                 Ok(_) => {
+                    state.cache.invalidate(cache::BRANDS);
                     info!("Brand deleted! ID: {}", &request.id);
-                    (StatusCode::OK, Ok(Json("Brand deleted!")))
+                    deleted(&state.config, "Brand deleted!")
                 }
                 Err(e) => {
                     error!("Error deleting brand: {}", e);
                     (
                         StatusCode::INTERNAL_SERVER_ERROR,
-                        Ok(Json("Error deleting brand.")),
+                        Json("Error deleting brand."),
                     )
+                        .into_response()
                 }
             }
         }
-        Ok(None) => {
+        // Already deleted, as when a client retries a delete that succeeded
+        Ok(false) if state.config.idempotent_deletes => {
+            info!("Brand {} already absent.", &request.id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => {
             error!("Brand ID not found.");
-            (StatusCode::NOT_FOUND, Err(Json("Brand ID not found")))
+            (StatusCode::NOT_FOUND, Json("Brand ID not found")).into_response()
         }
         Err(e) => {
             error!("Error deleting brand: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Err(Json("Error deleting brand.")),
+                Json("Error deleting brand."),
             )
+                .into_response()
         }
     }
 }
+
+/// The country code is optional, but must be an ISO 3166-1 alpha-2 code when
+/// given.
+fn check_country_code(country_code: Option<&str>) -> Result<(), &'static str> {
+    match country_code {
+        Some(country_code) if !validations::is_country_code(country_code) => {
+            Err("Brand country code must be an ISO 3166-1 alpha-2 code.")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Website and support contacts are optional, but must be well-formed when
+/// given.
+fn check_contact(
+    website_url: Option<&str>,
+    support_email: Option<&str>,
+    support_phone: Option<&str>,
+) -> Result<(), &'static str> {
+    if let Some(website_url) = website_url {
+        if website_url.len() > MAX_URL_LENGTH {
+            return Err("Brand website URL is too long.");
+        }
+        if !validations::is_http_url(website_url) {
+            return Err("Brand website URL must be a valid http(s) URL.");
+        }
+    }
+
+    if let Some(support_email) = support_email {
+        if support_email.len() > MAX_EMAIL_LENGTH {
+            return Err("Brand support email is too long.");
+        }
+        if !validations::is_email(support_email) {
+            return Err("Brand support email must be a valid email address.");
+        }
+    }
+
+    if let Some(support_phone) = support_phone {
+        if support_phone.len() > MAX_PHONE_LENGTH {
+            return Err("Brand support phone is too long.");
+        }
+        if !validations::is_phone(support_phone) {
+            return Err("Brand support phone must have 7 to 15 digits.");
+        }
+    }
+
+    Ok(())
+}
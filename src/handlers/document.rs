@@ -0,0 +1,232 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Extension};
+use sqlx::PgExecutor;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    extractors::{Json, Path},
+    ids,
+    middleware::deadline::Deadline,
+    models::{
+        database::AppState,
+        document::{DocumentRequest, PrinterDocument},
+    },
+    queries::{self, Table},
+    validations::{self, MAX_URL_LENGTH},
+};
+
+/// Width of the `printer_documents.label` column.
+const LABEL_MAX_LENGTH: usize = 100;
+
+pub async fn show_printer_documents(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match queries::exists(&state.db, Table::Printers, id).await {
+        Ok(true) => match printer_documents(&state.db, id).await {
+            Ok(documents) => {
+                info!("Documents of printer {} listed", &id);
+                (StatusCode::OK, Ok(Json(documents)))
+            }
+            Err(e) => {
+                error!("Error listing printer documents: {e}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Err(Json("Error listing printer documents.")),
+                )
+            }
+        },
+        Ok(false) => {
+            error!("Printer ID not found.");
+            (StatusCode::NOT_FOUND, Err(Json("Printer ID not found.")))
+        }
+        Err(e) => {
+            error!("Error listing printer documents: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error listing printer documents.")),
+            )
+        }
+    }
+}
+
+/// Attaches a document link to a printer, up to `PRINTER_MAX_DOCUMENTS` of
+/// them.
+pub async fn add_printer_document(
+    Path(id): Path<Uuid>,
+    Extension(deadline): Extension<Deadline>,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<DocumentRequest>,
+) -> impl IntoResponse {
+    let label = request.label.trim();
+    let url = request.url.trim();
+
+    // Label is empty
+    if label.is_empty() {
+        error!("Document label cannot be empty.");
+        return (
+            StatusCode::BAD_REQUEST,
+            Err(Json("Document label cannot be empty.")),
+        );
+    }
+
+    // Label too long
+    if label.chars().count() > LABEL_MAX_LENGTH {
+        error!("Document label is too long.");
+        return (
+            StatusCode::BAD_REQUEST,
+            Err(Json("Document label is too long.")),
+        );
+    }
+
+    // URL too long
+    if url.len() > MAX_URL_LENGTH {
+        error!("Document URL is too long.");
+        return (
+            StatusCode::BAD_REQUEST,
+            Err(Json("Document URL is too long.")),
+        );
+    }
+
+    // Invalid URL
+    if !validations::is_http_url(url) {
+        error!("Document URL is invalid: {}", url);
+        return (
+            StatusCode::BAD_REQUEST,
+            Err(Json("Document URL must be a valid http(s) URL.")),
+        );
+    }
+
+    let mut tx = match state.begin(deadline).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!("Error adding printer document: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error adding printer document.")),
+            );
+        }
+    };
+
+    // The lock keeps concurrent additions from both passing the count check.
+    let count: Result<Option<(i64,)>, sqlx::Error> = sqlx::query_as(
+        r#"
+        SELECT (SELECT COUNT(*) FROM printer_documents WHERE printer_id = printers.id)
+        FROM printers
+        WHERE id = $1
+        FOR UPDATE;
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await;
+
+    match count {
+        Ok(Some((count,))) if count >= state.config.printer_max_documents => {
+            error!("Printer {} already has {} documents.", &id, count);
+            return (
+                StatusCode::CONFLICT,
+                Err(Json("Printer already has the maximum number of documents.")),
+            );
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            error!("Printer ID not found.");
+            return (StatusCode::NOT_FOUND, Err(Json("Printer ID not found.")));
+        }
+        Err(e) => {
+            error!("Error adding printer document: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error adding printer document.")),
+            );
+        }
+    }
+
+    let document: Result<PrinterDocument, sqlx::Error> = sqlx::query_as(
+        r#"
+        INSERT INTO printer_documents (id, printer_id, label, url)
+        VALUES ($1, $2, $3, $4)
+        RETURNING *;
+        "#,
+    )
+    .bind(ids::new_id())
+    .bind(id)
+    .bind(label)
+    .bind(url)
+    .fetch_one(&mut *tx)
+    .await;
+
+    match document {
+        Ok(document) => match tx.commit().await {
+            Ok(_) => {
+                info!("Document added to printer {}! ID: {}", &id, &document.id);
+                (StatusCode::CREATED, Ok(Json(document)))
+            }
+            Err(e) => {
+                error!("Error adding printer document: {e}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Err(Json("Error adding printer document.")),
+                )
+            }
+        },
+        Err(e) => {
+            error!("Error adding printer document: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error adding printer document.")),
+            )
+        }
+    }
+}
+
+pub async fn remove_printer_document(
+    Path((id, document)): Path<(Uuid, Uuid)>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match sqlx::query(r#"DELETE FROM printer_documents WHERE id = $1 AND printer_id = $2;"#)
+        .bind(document)
+        .bind(id)
+        .execute(&state.db)
+        .await
+    {
+        Ok(result) if result.rows_affected() > 0 => {
+            info!("Document {} removed from printer {}", &document, &id);
+            (StatusCode::OK, Ok(Json(document)))
+        }
+        Ok(_) => {
+            error!("Printer {} has no document {}.", &id, &document);
+            (
+                StatusCode::NOT_FOUND,
+                Err(Json("Printer does not have this document.")),
+            )
+        }
+        Err(e) => {
+            error!("Error removing printer document: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error removing printer document.")),
+            )
+        }
+    }
+}
+
+/// Documents of `printer`, oldest first.
+pub async fn printer_documents<'e>(
+    executor: impl PgExecutor<'e>,
+    printer: Uuid,
+) -> Result<Vec<PrinterDocument>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT * FROM printer_documents
+        WHERE printer_id = $1
+        ORDER BY created_at ASC, id ASC;
+        "#,
+    )
+    .bind(printer)
+    .fetch_all(executor)
+    .await
+}
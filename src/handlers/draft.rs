@@ -0,0 +1,272 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    extractors::{Actor, Json, Path},
+    handlers::{location, printer},
+    ids,
+    models::{
+        database::AppState,
+        draft::{PrinterDraft, PrinterDraftRequest},
+        printer::CreatePrinterRequest,
+    },
+};
+
+/// Columns returned for a draft; `$1` is the configured lifetime in days.
+const DRAFT_COLUMNS: &str = r#"
+    id, name, model, brand, toner, drum, owner_department,
+    updated_at + make_interval(days => $1) AS expires_at
+"#;
+
+/// Drafts saved within the configured lifetime; `$1` is that lifetime in days.
+const LIVE_DRAFT: &str = "updated_at > NOW() - make_interval(days => $1)";
+
+/// Starts a printer draft. Expired drafts are purged on the way.
+pub async fn create_printer_draft(
+    actor: Actor,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<PrinterDraftRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = sqlx::query(&format!(
+        r#"DELETE FROM printer_drafts WHERE NOT ({LIVE_DRAFT});"#
+    ))
+    .bind(state.config.printer_draft_ttl_days)
+    .execute(&state.db)
+    .await
+    {
+        warn!("Error purging expired printer drafts: {e}");
+    }
+
+    match sqlx::query_as::<_, PrinterDraft>(&format!(
+        r#"
+        INSERT INTO printer_drafts
+            (id, name, model, brand, toner, drum, owner_department, created_by)
+        VALUES ($2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING {DRAFT_COLUMNS};
+        "#
+    ))
+    .bind(state.config.printer_draft_ttl_days)
+    .bind(ids::new_id())
+    .bind(&request.name)
+    .bind(&request.model)
+    .bind(&request.brand)
+    .bind(&request.toner)
+    .bind(&request.drum)
+    .bind(&request.owner_department)
+    .bind(actor.id())
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(draft) => {
+            info!("Printer draft created! ID: {}", &draft.id);
+            (
+                StatusCode::CREATED,
+                Ok((
+                    location(format!("/api/v1/printers/drafts/{}", draft.id)),
+                    Json(draft),
+                )),
+            )
+        }
+        Err(e) => {
+            error!("Error creating printer draft: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error creating printer draft.")),
+            )
+        }
+    }
+}
+
+pub async fn show_printer_draft(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match sqlx::query_as::<_, PrinterDraft>(&format!(
+        r#"SELECT {DRAFT_COLUMNS} FROM printer_drafts WHERE id = $2 AND {LIVE_DRAFT};"#
+    ))
+    .bind(state.config.printer_draft_ttl_days)
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    {
+        Ok(Some(draft)) => {
+            info!("Printer draft found: {id}");
+            (StatusCode::OK, Ok(Json(draft)))
+        }
+        Ok(None) => {
+            error!("Printer draft not found.");
+            (StatusCode::NOT_FOUND, Err(Json("Printer draft not found.")))
+        }
+        Err(e) => {
+            error!("Error retrieving printer draft: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error retrieving printer draft.")),
+            )
+        }
+    }
+}
+
+/// Replaces the fields of a draft, as autosaving forms do on every change.
+pub async fn update_printer_draft(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<PrinterDraftRequest>,
+) -> impl IntoResponse {
+    match sqlx::query_as::<_, PrinterDraft>(&format!(
+        r#"
+        UPDATE printer_drafts
+        SET name = $3, model = $4, brand = $5, toner = $6, drum = $7,
+            owner_department = $8, updated_at = NOW()
+        WHERE id = $2 AND {LIVE_DRAFT}
+        RETURNING {DRAFT_COLUMNS};
+        "#
+    ))
+    .bind(state.config.printer_draft_ttl_days)
+    .bind(id)
+    .bind(&request.name)
+    .bind(&request.model)
+    .bind(&request.brand)
+    .bind(&request.toner)
+    .bind(&request.drum)
+    .bind(&request.owner_department)
+    .fetch_optional(&state.db)
+    .await
+    {
+        Ok(Some(draft)) => {
+            info!("Printer draft saved! ID: {id}");
+            (StatusCode::OK, Ok(Json(draft)))
+        }
+        Ok(None) => {
+            error!("Printer draft not found.");
+            (StatusCode::NOT_FOUND, Err(Json("Printer draft not found.")))
+        }
+        Err(e) => {
+            error!("Error saving printer draft: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error saving printer draft.")),
+            )
+        }
+    }
+}
+
+/// Promotes a draft to a printer through the same validation as
+/// `POST /printers`, answering as that endpoint does. The draft is removed
+/// once the printer is created.
+pub async fn finalize_printer_draft(
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    State(state): State<Arc<AppState>>,
+) -> Response {
+    let mut tx = match state.db.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!("Error finalizing printer draft: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error finalizing printer draft."),
+            )
+                .into_response();
+        }
+    };
+
+    // Locked so that a concurrent finalize waits and then finds it gone.
+    let draft = match sqlx::query_as::<_, PrinterDraft>(&format!(
+        r#"SELECT {DRAFT_COLUMNS} FROM printer_drafts WHERE id = $2 AND {LIVE_DRAFT} FOR UPDATE;"#
+    ))
+    .bind(state.config.printer_draft_ttl_days)
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await
+    {
+        Ok(Some(draft)) => draft,
+        Ok(None) => {
+            error!("Printer draft not found.");
+            return (StatusCode::NOT_FOUND, Json("Printer draft not found.")).into_response();
+        }
+        Err(e) => {
+            error!("Error finalizing printer draft: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error finalizing printer draft."),
+            )
+                .into_response();
+        }
+    };
+
+    // Missing fields
+    let missing: Vec<&str> = [
+        ("name", &draft.name),
+        ("model", &draft.model),
+        ("brand", &draft.brand),
+        ("toner", &draft.toner),
+        ("drum", &draft.drum),
+    ]
+    .into_iter()
+    .filter(|(_, value)| value.is_none())
+    .map(|(field, _)| field)
+    .collect();
+    if !missing.is_empty() {
+        let message = format!("Draft is missing: {}.", missing.join(", "));
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Json(message)).into_response();
+    }
+
+    let request = CreatePrinterRequest {
+        name: draft.name.unwrap_or_default(),
+        model: draft.model.unwrap_or_default(),
+        brand: draft.brand.unwrap_or_default(),
+        toner: draft.toner.unwrap_or_default(),
+        drum: draft.drum.unwrap_or_default(),
+        owner_department: draft.owner_department,
+        status: None,
+        acquisition_type: None,
+        lease_end_date: None,
+        connection_type: None,
+    };
+
+    // The printer and the removal of its draft are committed together.
+    let response = printer::insert_printer(&mut *tx, actor, &state, request)
+        .await
+        .into_response();
+    if response.status() != StatusCode::CREATED {
+        return response;
+    }
+
+    let removed = sqlx::query(r#"DELETE FROM printer_drafts WHERE id = $1;"#)
+        .bind(id)
+        .execute(&mut *tx)
+        .await;
+    match removed {
+        Ok(_) => match tx.commit().await {
+            Ok(_) => {
+                info!("Printer draft finalized! ID: {id}");
+                response
+            }
+            Err(e) => {
+                error!("Error finalizing printer draft: {e}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json("Error finalizing printer draft."),
+                )
+                    .into_response()
+            }
+        },
+        Err(e) => {
+            error!("Error removing finalized printer draft: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error finalizing printer draft."),
+            )
+                .into_response()
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::{convert::Infallible, sync::Arc};
+
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::{stream, Stream};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use tracing::info;
+
+use crate::{
+    middleware::id_format::{self, IdFormat},
+    models::{database::AppState, event::ChangeEvent},
+};
+
+/// Streams every change to brands, printers, toners and drums as a `change`
+/// server-sent event, for dashboards that update live. A client too slow to
+/// keep up gets a `lagged` event with the number of changes it missed, after
+/// which it should refetch what it shows. Ids are written as asked for with
+/// the `ids` parameter of `Accept`, like in the other responses.
+pub async fn stream_events(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let format = IdFormat::requested(&headers).unwrap_or(IdFormat::Hyphenated);
+    let subscription = Subscription(state.events.subscribe());
+    info!("Event stream opened: {} clients", state.events.clients());
+
+    let events = stream::unfold(subscription, move |mut subscription| async move {
+        let event = match subscription.0.recv().await {
+            // Serializing the change cannot fail.
+            Ok(change) => {
+                let mut data = serde_json::to_value(change).unwrap_or_default();
+                id_format::rewrite(&mut data, format);
+                Event::default().event("change").data(data.to_string())
+            }
+            Err(RecvError::Lagged(missed)) => {
+                Event::default().event("lagged").data(missed.to_string())
+            }
+            Err(RecvError::Closed) => return None,
+        };
+        Some((Ok(event), subscription))
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+/// Receiver of one client, dropped along with the stream when the client
+/// disconnects.
+struct Subscription(Receiver<ChangeEvent>);
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        info!("Event stream closed");
+    }
+}
@@ -0,0 +1,68 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use sqlx::PgPool;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    extractors::Json,
+    models::{
+        database::AppState,
+        exists::{ExistsRequest, ExistsResponse},
+    },
+};
+
+/// Checks several brand, toner and drum ids at once, with one set-based
+/// query per table.
+pub async fn check_exists(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ExistsRequest>,
+) -> impl IntoResponse {
+    let brands = existing(&state.db, "brands", &request.brands).await;
+    let toners = existing(&state.db, "toners", &request.toners).await;
+    let drums = existing(&state.db, "drums", &request.drums).await;
+
+    match (brands, toners, drums) {
+        (Ok(brands), Ok(toners), Ok(drums)) => {
+            info!(
+                "Existence checked for {} ids",
+                brands.len() + toners.len() + drums.len()
+            );
+            Ok(Json(ExistsResponse {
+                brands,
+                toners,
+                drums,
+            }))
+        }
+        (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+            error!("Error checking id existence: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error checking id existence."),
+            ))
+        }
+    }
+}
+
+async fn existing(
+    db: &PgPool,
+    table: &'static str,
+    ids: &[Uuid],
+) -> Result<BTreeMap<Uuid, bool>, sqlx::Error> {
+    if ids.is_empty() {
+        return Ok(BTreeMap::new());
+    }
+
+    let found: Vec<(Uuid,)> =
+        sqlx::query_as(&format!(r#"SELECT id FROM {table} WHERE id = ANY($1);"#))
+            .bind(ids)
+            .fetch_all(db)
+            .await?;
+
+    let mut result: BTreeMap<Uuid, bool> = ids.iter().map(|id| (*id, false)).collect();
+    for (id,) in found {
+        result.insert(id, true);
+    }
+    Ok(result)
+}
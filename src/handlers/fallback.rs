@@ -0,0 +1,18 @@
+use axum::{
+    http::{Method, StatusCode, Uri},
+    response::IntoResponse,
+    Json,
+};
+use tracing::error;
+
+/// Method-router fallback for known paths hit with an unsupported method.
+///
+/// Axum still fills in the `Allow` header for method-router fallbacks, so
+/// clients get both the list of permitted methods and a readable body.
+pub async fn method_not_allowed(method: Method, uri: Uri) -> impl IntoResponse {
+    error!("Method {} not allowed on {}.", method, uri.path());
+    (
+        StatusCode::METHOD_NOT_ALLOWED,
+        Json("Method not allowed for this endpoint. See the `Allow` header for the supported methods."),
+    )
+}
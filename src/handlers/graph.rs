@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use sqlx::PgPool;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    extractors::{Json, Path},
+    models::{
+        database::AppState,
+        graph::{GraphEdge, GraphNode, NodeKind, PrinterGraph, Relation},
+    },
+};
+
+/// Printer with its brand, toner and drum, the toners interchangeable with
+/// its toner and its tags, as a graph for visualization.
+pub async fn show_printer_graph(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match printer_graph(&state.db, id).await {
+        Ok(Some(graph)) => {
+            info!(
+                "Graph of printer {} retrieved: {} nodes",
+                &id,
+                graph.nodes.len()
+            );
+            Ok(Json(graph))
+        }
+        Ok(None) => {
+            error!("Printer ID not found.");
+            Err((StatusCode::NOT_FOUND, Json("Printer ID not found.")))
+        }
+        Err(e) => {
+            error!("Error retrieving printer graph: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error retrieving printer graph."),
+            ))
+        }
+    }
+}
+
+type PrinterRelations = (
+    String,
+    Option<Uuid>,
+    Option<String>,
+    Option<Uuid>,
+    Option<String>,
+    Option<Uuid>,
+    Option<String>,
+);
+
+/// Two queries: the printer joined to its brand, toner and drum, then its
+/// compatible toners and tags together. References to missing rows are left
+/// out of the graph.
+async fn printer_graph(db: &PgPool, id: Uuid) -> Result<Option<PrinterGraph>, sqlx::Error> {
+    let printer: Option<PrinterRelations> = sqlx::query_as(
+        r#"
+        SELECT printers.name, brands.id, brands.name, toners.id, toners.name, drums.id, drums.name
+        FROM printers
+        LEFT JOIN brands ON brands.id = printers.brand
+        LEFT JOIN toners ON toners.id = printers.toner
+        LEFT JOIN drums ON drums.id = printers.drum
+        WHERE printers.id = $1;
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(db)
+    .await?;
+
+    let Some((name, brand, brand_name, toner, toner_name, drum, drum_name)) = printer else {
+        return Ok(None);
+    };
+
+    let mut graph = PrinterGraph {
+        root: id,
+        nodes: vec![GraphNode {
+            id,
+            kind: NodeKind::Printer,
+            label: name,
+        }],
+        edges: Vec::new(),
+    };
+
+    for (node, label, kind, relation) in [
+        (brand, brand_name, NodeKind::Brand, Relation::Brand),
+        (toner, toner_name, NodeKind::Toner, Relation::Toner),
+        (drum, drum_name, NodeKind::Drum, Relation::Drum),
+    ] {
+        if let (Some(node), Some(label)) = (node, label) {
+            graph.nodes.push(GraphNode {
+                id: node,
+                kind,
+                label,
+            });
+            graph.edges.push(GraphEdge {
+                source: id,
+                target: node,
+                relation,
+            });
+        }
+    }
+
+    let related: Vec<(bool, Uuid, String)> = sqlx::query_as(
+        r#"
+        SELECT TRUE AS compatible, toners.id, toners.name
+        FROM toner_equivalents
+        JOIN toners ON toners.id IN (toner_equivalents.toner_id, toner_equivalents.equivalent_id)
+        WHERE $2 IN (toner_equivalents.toner_id, toner_equivalents.equivalent_id)
+            AND toners.id != $2
+        UNION ALL
+        SELECT FALSE, tags.id, tags.name
+        FROM printer_tags
+        JOIN tags ON tags.id = printer_tags.tag_id
+        WHERE printer_tags.printer_id = $1
+        ORDER BY compatible DESC, name ASC, id ASC;
+        "#,
+    )
+    .bind(id)
+    .bind(toner)
+    .fetch_all(db)
+    .await?;
+
+    for (compatible, node, label) in related {
+        let (kind, source, relation) = match (compatible, toner) {
+            (true, Some(toner)) => (NodeKind::Toner, toner, Relation::Compatible),
+            (true, None) => continue,
+            (false, _) => (NodeKind::Tag, id, Relation::Tag),
+        };
+        graph.nodes.push(GraphNode {
+            id: node,
+            kind,
+            label,
+        });
+        graph.edges.push(GraphEdge {
+            source,
+            target: node,
+            relation,
+        });
+    }
+
+    Ok(Some(graph))
+}
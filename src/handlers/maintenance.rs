@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{OriginalUri, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    extractors::{Json, Path},
+    models::{
+        database::AppState,
+        maintenance::{CreateMaintenanceScheduleRequest, DueMaintenance, MaintenanceSchedule},
+        pagination::{Page, PaginationParams},
+    },
+    queries::{self, Table},
+    validations,
+};
+
+pub async fn show_maintenance_schedules(
+    Query(pagination): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let (limit, offset) = match pagination.resolve(&state.config) {
+        Ok(window) => window,
+        Err(message) => {
+            error!("Invalid pagination: {message}");
+            return Err((StatusCode::BAD_REQUEST, Json(message)));
+        }
+    };
+
+    let total: Result<(i64,), sqlx::Error> =
+        sqlx::query_as(r#"SELECT COUNT(*) FROM maintenance_schedules;"#)
+            .fetch_one(&state.db)
+            .await;
+    let schedules: Result<Vec<MaintenanceSchedule>, sqlx::Error> =
+        sqlx::query_as(r#"SELECT * FROM maintenance_schedules ORDER BY id LIMIT $1 OFFSET $2;"#)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&state.db)
+            .await;
+    match (schedules, total) {
+        (Ok(schedules), Ok((total,))) => {
+            info!("Maintenance schedules listed successfully");
+            Ok(Json(Page::new(schedules, total, limit, offset, &uri)))
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            error!("Error listing maintenance schedules: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error listing maintenance schedules."),
+            ))
+        }
+    }
+}
+
+/// Schedules whose `last_done_at + interval_days` has passed, or that were
+/// never done, oldest due date first.
+pub async fn show_due_maintenance(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let due: Result<Vec<DueMaintenance>, sqlx::Error> = sqlx::query_as(
+        r#"
+        SELECT
+            maintenance_schedules.id AS schedule_id,
+            printers.id AS printer_id,
+            printers.name AS printer_name,
+            maintenance_schedules.interval_days,
+            maintenance_schedules.last_done_at,
+            maintenance_schedules.last_done_at
+                + maintenance_schedules.interval_days * INTERVAL '1 day' AS due_at
+        FROM maintenance_schedules
+        JOIN printers ON printers.id = maintenance_schedules.printer_id
+        WHERE maintenance_schedules.last_done_at IS NULL
+            OR maintenance_schedules.last_done_at
+                + maintenance_schedules.interval_days * INTERVAL '1 day' <= NOW()
+        ORDER BY due_at ASC NULLS FIRST, printers.name ASC;
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    match due {
+        Ok(due) => {
+            info!("Due maintenance listed: {}", due.len());
+            Ok(Json(due))
+        }
+        Err(e) => {
+            error!("Error listing due maintenance: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error listing due maintenance."),
+            ))
+        }
+    }
+}
+
+pub async fn create_maintenance_schedule(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<CreateMaintenanceScheduleRequest>,
+) -> impl IntoResponse {
+    let new_schedule = MaintenanceSchedule::new(
+        request.printer_id,
+        request.interval_days,
+        request.last_done_at,
+    );
+
+    // Invalid id
+    if let Err(message) =
+        validations::check_ids([new_schedule.printer_id], state.config.require_uuid_v4)
+    {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Err(Json(message)));
+    }
+
+    // Interval not positive
+    if new_schedule.interval_days < 1 {
+        error!("Maintenance interval must be greater than zero.");
+        return (
+            StatusCode::BAD_REQUEST,
+            Err(Json("Maintenance interval must be greater than zero.")),
+        );
+    }
+
+    // Last maintenance in the future
+    if let Some(last_done_at) = new_schedule.last_done_at {
+        if validations::is_in_future(last_done_at) {
+            error!("Maintenance timestamp is in the future: {}", last_done_at);
+            return (
+                StatusCode::BAD_REQUEST,
+                Err(Json("Maintenance timestamp cannot be in the future.")),
+            );
+        }
+    }
+
+    // Printer not found
+    match queries::exists(&state.db, Table::Printers, new_schedule.printer_id).await {
+        Ok(true) => {
+            match sqlx::query(
+                r#"
+                INSERT INTO maintenance_schedules (id, printer_id, interval_days, last_done_at)
+                VALUES ($1, $2, $3, $4);
+                "#,
+            )
+            .bind(new_schedule.id)
+            .bind(new_schedule.printer_id)
+            .bind(new_schedule.interval_days)
+            .bind(new_schedule.last_done_at)
+            .execute(&state.db)
+            .await
+            {
+                Ok(_) => {
+                    info!("Maintenance schedule created! ID: {}", &new_schedule.id);
+                    (StatusCode::CREATED, Ok(Json(new_schedule.id)))
+                }
+                Err(e) => {
+                    error!("Error creating maintenance schedule: {}", e);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Err(Json("Error creating maintenance schedule.")),
+                    )
+                }
+            }
+        }
+        Ok(false) => {
+            error!("Printer ID not found.");
+            (StatusCode::NOT_FOUND, Err(Json("Printer ID not found.")))
+        }
+        Err(e) => {
+            error!("Error fetching printer by ID: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error fetching printer by ID")),
+            )
+        }
+    }
+}
+
+/// Records that the printer's maintenance was done now, restarting the
+/// interval of every schedule it has.
+pub async fn mark_maintenance_done(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match sqlx::query(
+        r#"UPDATE maintenance_schedules SET last_done_at = NOW() WHERE printer_id = $1;"#,
+    )
+    .bind(id)
+    .execute(&state.db)
+    .await
+    {
+        Ok(result) if result.rows_affected() > 0 => {
+            info!("Maintenance done for printer {}", &id);
+            (StatusCode::OK, Ok(Json(result.rows_affected())))
+        }
+        Ok(_) => {
+            error!("No maintenance schedule found for printer {}.", &id);
+            (
+                StatusCode::NOT_FOUND,
+                Err(Json("No maintenance schedule found for this printer.")),
+            )
+        }
+        Err(e) => {
+            error!("Error marking maintenance done: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error marking maintenance done.")),
+            )
+        }
+    }
+}
@@ -1,5 +1,44 @@
+pub mod activity;
+pub mod admin;
+pub mod backup;
 pub mod brand;
+pub mod document;
+pub mod draft;
+pub mod events;
+pub mod exists;
+pub mod fallback;
+pub mod graph;
+pub mod maintenance;
 pub mod movement;
+pub mod photo;
 pub mod printer;
+pub mod schema;
+pub mod snapshot;
 pub mod status;
 pub mod supplies;
+pub mod tag;
+
+use axum::{
+    http::{header, HeaderName, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::{base_path, config::Config};
+
+/// `Location` header of a `201 Created` response, pointing at the route of
+/// the new resource, such as `/api/v1/brand/{id}`.
+pub fn location(route: String) -> [(HeaderName, String); 1] {
+    [(header::LOCATION, base_path::route(&route))]
+}
+
+/// Answer to a delete that removed the row: `204 No Content` under
+/// `IDEMPOTENT_DELETES`, the same as a retry gets, and `200` with `message`
+/// otherwise.
+pub fn deleted(config: &Config, message: &'static str) -> Response {
+    if config.idempotent_deletes {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        (StatusCode::OK, Json(message)).into_response()
+    }
+}
@@ -1,29 +1,33 @@
-use chrono::Utc;
 use reqwest::StatusCode;
 use sqlx::Row;
 use std::sync::Arc;
 use tracing::{error, info};
 
 use axum::{
-    extract::{Path, State},
-    response::IntoResponse,
-    Json,
+    extract::{OriginalUri, Query, State},
+    response::{IntoResponse, Response},
 };
 use uuid::Uuid;
 
-use crate::models::{
-    database::AppState,
-    movement::{
-        CreateDrumMovementRequest, CreateTonerMovementRequest, Movement, UpdateMovementRequest,
+use crate::{
+    extractors::{Actor, Json, Path},
+    handlers::{deleted, location},
+    models::{
+        database::AppState,
+        movement::{
+            CreateDrumMovementRequest, CreateTonerMovementRequest, Movement, UpdateMovementRequest,
+        },
+        pagination::{Page, PaginationParams},
+        DeleteRequest,
     },
-    DeleteRequest,
+    queries::{self, Table},
+    validations,
 };
 
 pub async fn count_all_movements(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let movement_count: Result<(i32,), sqlx::Error> =
-        sqlx::query_as(r#"SELECT COUNT(*)::int FROM movements;"#)
-            .fetch_one(&state.db)
-            .await;
+    let movement_count: Result<(i64,), sqlx::Error> = sqlx::query_as(queries::COUNT_MOVEMENTS)
+        .fetch_one(&state.db)
+        .await;
 
     match movement_count {
         Ok((count,)) => {
@@ -41,8 +45,8 @@ pub async fn count_all_movements(State(state): State<Arc<AppState>>) -> impl Int
 }
 
 pub async fn count_toner_movements(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let movement_count: Result<(i32,), sqlx::Error> =
-        sqlx::query_as(r#"SELECT COUNT(*)::int FROM movements WHERE toner_id IS NOT NULL;"#)
+    let movement_count: Result<(i64,), sqlx::Error> =
+        sqlx::query_as(queries::COUNT_TONER_MOVEMENTS)
             .fetch_one(&state.db)
             .await;
 
@@ -62,10 +66,9 @@ pub async fn count_toner_movements(State(state): State<Arc<AppState>>) -> impl I
 }
 
 pub async fn count_drum_movements(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let movement_count: Result<(i32,), sqlx::Error> =
-        sqlx::query_as(r#"SELECT COUNT(*)::int FROM movements WHERE drum_id IS NOT NULL;"#)
-            .fetch_one(&state.db)
-            .await;
+    let movement_count: Result<(i64,), sqlx::Error> = sqlx::query_as(queries::COUNT_DRUM_MOVEMENTS)
+        .fetch_one(&state.db)
+        .await;
 
     match movement_count {
         Ok((count,)) => {
@@ -106,17 +109,36 @@ pub async fn search_movement(
     }
 }
 
-pub async fn show_all_movements(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let movements: Result<Vec<Movement>, sqlx::Error> =
-        sqlx::query_as(r#"SELECT * FROM movements"#)
-            .fetch_all(&state.db)
-            .await;
-    match movements {
-        Ok(movements) => {
+pub async fn show_all_movements(
+    Query(pagination): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let (limit, offset) = match pagination.resolve(&state.config) {
+        Ok(window) => window,
+        Err(message) => {
+            error!("Invalid pagination: {message}");
+            return Err((StatusCode::BAD_REQUEST, Json(message)));
+        }
+    };
+
+    let total: Result<(i64,), sqlx::Error> = sqlx::query_as(queries::COUNT_MOVEMENTS)
+        .fetch_one(&state.db)
+        .await;
+    let movements: Result<Vec<Movement>, sqlx::Error> = sqlx::query_as(queries::LIST_MOVEMENTS)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&state.db)
+        .await;
+    match (movements, total) {
+        (Ok(movements), Ok((total,))) => {
             info!("Movements listed successfully");
-            Ok((StatusCode::OK, Json(movements)))
+            Ok((
+                StatusCode::OK,
+                Json(Page::new(movements, total, limit, offset, &uri)),
+            ))
         }
-        Err(e) => {
+        (Err(e), _) | (_, Err(e)) => {
             error!("Error listing movements: {e}");
             Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -126,17 +148,37 @@ pub async fn show_all_movements(State(state): State<Arc<AppState>>) -> impl Into
     }
 }
 
-pub async fn show_toner_movements(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+pub async fn show_toner_movements(
+    Query(pagination): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let (limit, offset) = match pagination.resolve(&state.config) {
+        Ok(window) => window,
+        Err(message) => {
+            error!("Invalid pagination: {message}");
+            return Err((StatusCode::BAD_REQUEST, Json(message)));
+        }
+    };
+
+    let total: Result<(i64,), sqlx::Error> = sqlx::query_as(queries::COUNT_TONER_MOVEMENTS)
+        .fetch_one(&state.db)
+        .await;
     let toner_movements: Result<Vec<Movement>, sqlx::Error> =
-        sqlx::query_as(r#"SELECT * FROM movements WHERE toner_id IS NOT NULL;"#)
+        sqlx::query_as(queries::LIST_TONER_MOVEMENTS)
+            .bind(limit)
+            .bind(offset)
             .fetch_all(&state.db)
             .await;
-    match toner_movements {
-        Ok(movements) => {
+    match (toner_movements, total) {
+        (Ok(movements), Ok((total,))) => {
             info!("Toner movements listed successfully");
-            Ok((StatusCode::OK, Json(movements)))
+            Ok((
+                StatusCode::OK,
+                Json(Page::new(movements, total, limit, offset, &uri)),
+            ))
         }
-        Err(e) => {
+        (Err(e), _) | (_, Err(e)) => {
             error!("Error listing toner movements: {e}");
             Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -146,30 +188,68 @@ pub async fn show_toner_movements(State(state): State<Arc<AppState>>) -> impl In
     }
 }
 
-pub async fn show_drum_movements(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+pub async fn show_drum_movements(
+    Query(pagination): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let (limit, offset) = match pagination.resolve(&state.config) {
+        Ok(window) => window,
+        Err(message) => {
+            error!("Invalid pagination: {message}");
+            return Err((StatusCode::BAD_REQUEST, Json(message)));
+        }
+    };
+
+    let total: Result<(i64,), sqlx::Error> = sqlx::query_as(queries::COUNT_DRUM_MOVEMENTS)
+        .fetch_one(&state.db)
+        .await;
     let drum_movements: Result<Vec<Movement>, sqlx::Error> =
-        sqlx::query_as(r#"SELECT * FROM movements WHERE drum_id IS NOT NULL;"#)
+        sqlx::query_as(queries::LIST_DRUM_MOVEMENTS)
+            .bind(limit)
+            .bind(offset)
             .fetch_all(&state.db)
             .await;
-    match drum_movements {
-        Ok(movements) => {
+    match (drum_movements, total) {
+        (Ok(movements), Ok((total,))) => {
             info!("Drum movements listed successfully");
-            Ok((StatusCode::OK, Json(movements)))
+            Ok((
+                StatusCode::OK,
+                Json(Page::new(movements, total, limit, offset, &uri)),
+            ))
         }
-        Err(e) => {
+        (Err(e), _) | (_, Err(e)) => {
             error!("Error listing drum movements: {e}");
             Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json("Error listing toner movements."),
+                Json("Error listing drum movements."),
             ))
         }
     }
 }
 
 pub async fn create_toner_movement(
+    actor: Actor,
     State(state): State<Arc<AppState>>,
     Json(request): Json<CreateTonerMovementRequest>,
 ) -> impl IntoResponse {
+    // Invalid id
+    if let Err(message) = validations::check_ids(request.toner_id, state.config.require_uuid_v4) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Err(Json(message)));
+    }
+
+    // Timestamp in the future
+    if let Some(created_at) = request.created_at {
+        if validations::is_in_future(created_at) {
+            error!("Movement timestamp is in the future: {}", created_at);
+            return (
+                StatusCode::BAD_REQUEST,
+                Err(Json("Movement timestamp cannot be in the future.")),
+            );
+        }
+    }
+
     match request.toner_id {
         Some(toner_id) => {
             let printer_id: Uuid = match sqlx::query(r#"SELECT id FROM printers WHERE toner = $1;"#)
@@ -189,7 +269,11 @@ pub async fn create_toner_movement(
                 }
             };
 
-            let new_movement = Movement::new(printer_id, Some(toner_id), None, request.quantity);
+            let mut new_movement =
+                Movement::new(printer_id, Some(toner_id), None, request.quantity);
+            if let Some(created_at) = request.created_at {
+                new_movement.created_at = created_at;
+            }
 
             // Empty quantity
             if new_movement.quantity == 0 {
@@ -206,24 +290,33 @@ pub async fn create_toner_movement(
                 VALUES ($1, $2, $3, $4, $5, $6);
                 "#,
             )
-            .bind(Uuid::new_v4())
+            .bind(new_movement.id)
             .bind(printer_id)
             .bind(new_movement.toner_id)
             .bind(new_movement.drum_id)
             .bind(new_movement.quantity)
-            .bind(Utc::now())
+            .bind(new_movement.created_at)
             .execute(&state.db)
             .await
             {
                 Ok(_) => {
-                    sqlx::query(r#"UPDATE toners SET stock = stock + $1 WHERE id = $2;"#)
-                        .bind(request.quantity)
-                        .bind(toner_id)
-                        .execute(&state.db)
-                        .await
-                        .unwrap();
-
-                    (StatusCode::CREATED, Ok(Json(new_movement.id)))
+                    sqlx::query(
+                        r#"UPDATE toners SET stock = stock + $1, updated_by = $3 WHERE id = $2;"#,
+                    )
+                    .bind(request.quantity)
+                    .bind(toner_id)
+                    .bind(actor.id())
+                    .execute(&state.db)
+                    .await
+                    .unwrap();
+
+                    (
+                        StatusCode::CREATED,
+                        Ok((
+                            location(format!("/api/v1/movements/{}", new_movement.id)),
+                            Json(new_movement.id),
+                        )),
+                    )
                 }
                 Err(e) => {
                     error!("Error creating toner movement: {}", e);
@@ -242,9 +335,27 @@ pub async fn create_toner_movement(
 }
 
 pub async fn create_drum_movement(
+    actor: Actor,
     State(state): State<Arc<AppState>>,
     Json(request): Json<CreateDrumMovementRequest>,
 ) -> impl IntoResponse {
+    // Invalid id
+    if let Err(message) = validations::check_ids(request.drum_id, state.config.require_uuid_v4) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Err(Json(message)));
+    }
+
+    // Timestamp in the future
+    if let Some(created_at) = request.created_at {
+        if validations::is_in_future(created_at) {
+            error!("Movement timestamp is in the future: {}", created_at);
+            return (
+                StatusCode::BAD_REQUEST,
+                Err(Json("Movement timestamp cannot be in the future.")),
+            );
+        }
+    }
+
     match request.drum_id {
         Some(drum_id) => {
             let printer_id: Uuid = match sqlx::query(r#"SELECT id FROM printers WHERE drum = $1;"#)
@@ -264,7 +375,10 @@ pub async fn create_drum_movement(
                 }
             };
 
-            let new_movement = Movement::new(printer_id, None, Some(drum_id), request.quantity);
+            let mut new_movement = Movement::new(printer_id, None, Some(drum_id), request.quantity);
+            if let Some(created_at) = request.created_at {
+                new_movement.created_at = created_at;
+            }
 
             // Empty quantity
             if new_movement.quantity == 0 {
@@ -281,24 +395,33 @@ pub async fn create_drum_movement(
                 VALUES ($1, $2, $3, $4, $5, $6);
                 "#,
             )
-            .bind(Uuid::new_v4())
+            .bind(new_movement.id)
             .bind(printer_id)
             .bind(new_movement.toner_id)
             .bind(new_movement.drum_id)
             .bind(new_movement.quantity)
-            .bind(Utc::now())
+            .bind(new_movement.created_at)
             .execute(&state.db)
             .await
             {
                 Ok(_) => {
-                    sqlx::query(r#"UPDATE drums SET stock = stock + $1 WHERE id = $2;"#)
-                        .bind(request.quantity)
-                        .bind(drum_id)
-                        .execute(&state.db)
-                        .await
-                        .unwrap();
-
-                    (StatusCode::CREATED, Ok(Json(new_movement.id)))
+                    sqlx::query(
+                        r#"UPDATE drums SET stock = stock + $1, updated_by = $3 WHERE id = $2;"#,
+                    )
+                    .bind(request.quantity)
+                    .bind(drum_id)
+                    .bind(actor.id())
+                    .execute(&state.db)
+                    .await
+                    .unwrap();
+
+                    (
+                        StatusCode::CREATED,
+                        Ok((
+                            location(format!("/api/v1/movements/{}", new_movement.id)),
+                            Json(new_movement.id),
+                        )),
+                    )
                 }
                 Err(e) => {
                     error!("Error creating drum movement: {}", e);
@@ -326,6 +449,18 @@ pub async fn update_movement(
     let new_drum_id = request.drum_id;
     let new_quantity = request.quantity;
 
+    // Invalid id
+    if let Err(message) = validations::check_ids(
+        [movement_id, new_printer_id]
+            .into_iter()
+            .chain(new_toner_id)
+            .chain(new_drum_id),
+        state.config.require_uuid_v4,
+    ) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Err(Json(message)));
+    }
+
     // One of the two must exist
     if new_toner_id.is_none() && new_drum_id.is_none() {
         error!("Either toner_id or drum_id must be provided.");
@@ -336,12 +471,8 @@ pub async fn update_movement(
     }
 
     // Not found
-    match sqlx::query(r#"SELECT id FROM movements WHERE id = $1;"#)
-        .bind(movement_id)
-        .fetch_optional(&state.db)
-        .await
-    {
-        Ok(Some(_)) => {
+    match queries::exists(&state.db, Table::Movements, movement_id).await {
+        Ok(true) => {
             match sqlx::query(
                 r#"UPDATE movements 
                     SET printer_id = $1,
@@ -371,7 +502,7 @@ pub async fn update_movement(
                 }
             }
         }
-        Ok(None) => {
+        Ok(false) => {
             error!("Movement ID not found.");
             (StatusCode::NOT_FOUND, Err(Json("Movement ID not found.")))
         }
@@ -388,13 +519,15 @@ pub async fn update_movement(
 pub async fn delete_movement(
     State(state): State<Arc<AppState>>,
     Json(request): Json<DeleteRequest>,
-) -> impl IntoResponse {
-    match sqlx::query(r#"SELECT id FROM movements WHERE id = $1;"#)
-        .bind(request.id)
-        .fetch_optional(&state.db)
-        .await
-    {
-        Ok(Some(_)) => {
+) -> Response {
+    // Invalid id
+    if let Err(message) = validations::check_ids([request.id], state.config.require_uuid_v4) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Json(message)).into_response();
+    }
+
+    match queries::exists(&state.db, Table::Movements, request.id).await {
+        Ok(true) => {
             match sqlx::query(r#"DELETE FROM movements WHERE id = $1;"#)
                 .bind(request.id)
                 .execute(&state.db)
@@ -402,27 +535,34 @@ pub async fn delete_movement(
             {
                 Ok(_) => {
                     info!("Movement deleted! ID: {}", &request.id);
-                    (StatusCode::OK, Ok(Json("Movement deleted!")))
+                    deleted(&state.config, "Movement deleted!")
                 }
                 Err(e) => {
                     error!("Error deleting movement: {}", e);
                     (
                         StatusCode::INTERNAL_SERVER_ERROR,
-                        Ok(Json("Error deleting movement.")),
+                        Json("Error deleting movement."),
                     )
+                        .into_response()
                 }
             }
         }
-        Ok(None) => {
+        // Already deleted, as when a client retries a delete that succeeded
+        Ok(false) if state.config.idempotent_deletes => {
+            info!("Movement {} already absent.", &request.id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => {
             error!("Movement ID not found.");
-            (StatusCode::NOT_FOUND, Err(Json("Movement ID not found")))
+            (StatusCode::NOT_FOUND, Json("Movement ID not found")).into_response()
         }
         Err(e) => {
             error!("Error deleting movement: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Err(Json("Error deleting movement.")),
+                Json("Error deleting movement."),
             )
+                .into_response()
         }
     }
 }
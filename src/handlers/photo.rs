@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{multipart::MultipartRejection, Multipart, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    extractors::{Json, Path},
+    models::{database::AppState, photo::PrinterPhoto},
+    queries::{self, Table},
+};
+
+/// Multipart field holding the photo.
+const PHOTO_FIELD: &str = "photo";
+
+/// Image type of `data` judged by its leading bytes, so a photo is never
+/// served under a type it does not have.
+fn sniff_image_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\xFF\xD8\xFF") {
+        Some("image/jpeg")
+    } else if data.starts_with(b"\x89PNG\r\n\x1A\n") {
+        Some("image/png")
+    } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+/// Stores the photo of a printer sent as the `photo` field of a
+/// `multipart/form-data` body, replacing any previous one.
+pub async fn upload_printer_photo(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> impl IntoResponse {
+    let Ok(mut multipart) = multipart else {
+        error!("Printer photo upload requires a multipart body.");
+        return Err((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Json("Request body must be sent as `multipart/form-data`."),
+        ));
+    };
+
+    match queries::exists(&state.db, Table::Printers, id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            error!("Printer ID not found.");
+            return Err((StatusCode::NOT_FOUND, Json("Printer ID not found.")));
+        }
+        Err(e) => {
+            error!("Error uploading printer photo: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error uploading printer photo."),
+            ));
+        }
+    }
+
+    let (content_type, data) = loop {
+        match multipart.next_field().await {
+            Ok(Some(field)) if field.name() == Some(PHOTO_FIELD) => {
+                let content_type = field.content_type().map(str::to_ascii_lowercase);
+                match field.bytes().await {
+                    Ok(data) => break (content_type, data),
+                    Err(e) => {
+                        error!("Invalid printer photo upload: {e}");
+                        return Err((e.status(), Json("Invalid multipart body.")));
+                    }
+                }
+            }
+            Ok(Some(_)) => continue,
+            // Missing photo
+            Ok(None) => {
+                error!("Printer photo upload has no `photo` field.");
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json("Multipart body must have a `photo` field."),
+                ));
+            }
+            Err(e) => {
+                error!("Invalid printer photo upload: {e}");
+                return Err((e.status(), Json("Invalid multipart body.")));
+            }
+        }
+    };
+
+    // Photo too large
+    if data.len() > state.config.printer_photo_max_bytes {
+        error!("Printer photo is too large: {} bytes.", data.len());
+        return Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json("Printer photo is too large."),
+        ));
+    }
+
+    // Not a supported image
+    let Some(content_type) =
+        sniff_image_type(&data).filter(|sniffed| content_type.as_deref() == Some(*sniffed))
+    else {
+        error!("Unsupported printer photo type: {:?}", content_type);
+        return Err((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Json("Printer photo must be a JPEG, PNG or WebP image."),
+        ));
+    };
+
+    match sqlx::query_as::<_, PrinterPhoto>(
+        r#"
+        INSERT INTO printer_photos (printer_id, content_type, data)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (printer_id) DO UPDATE
+        SET content_type = EXCLUDED.content_type, data = EXCLUDED.data, uploaded_at = NOW()
+        RETURNING printer_id, content_type, length(data) AS size, uploaded_at;
+        "#,
+    )
+    .bind(id)
+    .bind(content_type)
+    .bind(data.as_ref())
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(photo) => {
+            info!("Photo of printer {} uploaded: {} bytes", &id, photo.size);
+            Ok(Json(photo))
+        }
+        Err(e) => {
+            error!("Error uploading printer photo: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error uploading printer photo."),
+            ))
+        }
+    }
+}
+
+/// Serves the photo of a printer with the type it was uploaded as.
+pub async fn show_printer_photo(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Response {
+    let photo: Result<Option<(String, Vec<u8>)>, sqlx::Error> =
+        sqlx::query_as(r#"SELECT content_type, data FROM printer_photos WHERE printer_id = $1;"#)
+            .bind(id)
+            .fetch_optional(&state.db)
+            .await;
+
+    match photo {
+        Ok(Some((content_type, data))) => {
+            info!("Photo of printer {} served", &id);
+            ([(header::CONTENT_TYPE, content_type)], data).into_response()
+        }
+        Ok(None) => {
+            error!("Printer {} has no photo.", &id);
+            (StatusCode::NOT_FOUND, Json("Printer has no photo.")).into_response()
+        }
+        Err(e) => {
+            error!("Error retrieving printer photo: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error retrieving printer photo."),
+            )
+                .into_response()
+        }
+    }
+}
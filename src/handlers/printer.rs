@@ -1,25 +1,53 @@
-use std::{str::FromStr, sync::Arc};
+use std::{collections::HashSet, sync::Arc};
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::IntoResponse,
-    Json,
+    extract::{OriginalUri, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Extension,
 };
+use chrono::NaiveDate;
+use qrcode::{render::svg, QrCode};
+use sqlx::{postgres::PgRow, Acquire, PgExecutor, PgPool, Postgres, Transaction};
 use tracing::{error, info};
 use uuid::Uuid;
 
-use crate::models::{
-    database::AppState,
-    printer::{CreatePrinterRequest, Printer, UpdatePrinterRequest},
-    DeleteRequest,
+use crate::{
+    audit,
+    config::{Config, PrinterNameScope, ValidationMode},
+    extractors::{Actor, Json, Path},
+    handlers::{deleted, document, location},
+    ids,
+    middleware::{content_type, deadline::Deadline},
+    models::{
+        audit::Audited,
+        database::AppState,
+        error::ApiError,
+        jsonapi::{self, Document},
+        movement::Movement,
+        pagination::{Page, PaginationParams},
+        printer::{
+            AcquisitionType, BrandPrinterCount, BrandSwapRequest, BrandSwapResponse,
+            BulkStatusRequest, BulkStatusResponse, ClonePrinterRequest, CompatibilityReport,
+            CompatibilityRequest, ConnectionPrinterCount, CreatePrinterRequest,
+            DecommissionRequest, DepartmentPrinterCount, ExpiringLease, ExpiringLeaseParams,
+            ImportReport, ImportRowResult, ImportRowStatus, Printer, PrinterCsvRow, PrinterDetail,
+            PrinterFilter, PrinterModelCount, PrinterModelFilter, PrinterStatus, PrinterWithToner,
+            ReferenceCheck, ReferenceStatus, ReportPagesParams, ReportPagesRequest,
+            TonerGroupFilter, TonerPrinters, TonerSummary, UpdatePrinterRequest,
+        },
+        search::{SearchParams, SearchResults},
+        tag::TagMatch,
+        DeleteRequest, MutationResponse,
+    },
+    queries::{self, Table},
+    validations::{self, Named, NAME_MAX_LENGTH, NAME_MIN_LENGTH},
 };
 
 pub async fn count_printers(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let printer_count: Result<(i32,), sqlx::Error> =
-        sqlx::query_as(r#"SELECT COUNT(*)::int FROM printers;"#)
-            .fetch_one(&state.db)
-            .await;
+    let printer_count: Result<(i64,), sqlx::Error> = sqlx::query_as(queries::COUNT_PRINTERS)
+        .fetch_one(&state.db)
+        .await;
 
     match printer_count {
         Ok((count,)) => {
@@ -36,40 +64,402 @@ pub async fn count_printers(State(state): State<Arc<AppState>>) -> impl IntoResp
     }
 }
 
+/// Printer count per brand, most-used brands first. Ties are broken by brand
+/// name so the order is stable between calls.
+pub async fn count_printers_by_brand(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let counts: Result<Vec<BrandPrinterCount>, sqlx::Error> = sqlx::query_as(
+        r#"
+        SELECT brands.id AS brand, brands.name AS brand_name, COUNT(*) AS count
+        FROM printers
+        JOIN brands ON brands.id = printers.brand
+        GROUP BY brands.id, brands.name
+        ORDER BY count DESC, brand_name ASC;
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    match counts {
+        Ok(counts) => {
+            info!("Printer count by brand retrieved: {} brands", counts.len());
+            Ok(Json(counts))
+        }
+        Err(e) => {
+            error!("Error retrieving printer count by brand: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error retrieving printer count by brand."),
+            ))
+        }
+    }
+}
+
+/// Printer count per owner department, largest first, with printers that have
+/// no department grouped last.
+pub async fn count_printers_by_department(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let counts: Result<Vec<DepartmentPrinterCount>, sqlx::Error> = sqlx::query_as(
+        r#"
+        SELECT owner_department AS department, COUNT(*) AS count
+        FROM printers
+        GROUP BY owner_department
+        ORDER BY count DESC, department ASC NULLS LAST;
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    match counts {
+        Ok(counts) => {
+            info!(
+                "Printer count by department retrieved: {} departments",
+                counts.len()
+            );
+            Ok(Json(counts))
+        }
+        Err(e) => {
+            error!("Error retrieving printer count by department: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error retrieving printer count by department."),
+            ))
+        }
+    }
+}
+
+/// Printer count per connection type, with printers of unknown connection
+/// counted under `null`.
+pub async fn count_printers_by_connection(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let counts: Result<Vec<ConnectionPrinterCount>, sqlx::Error> = sqlx::query_as(
+        r#"
+        SELECT connection_type, COUNT(*) AS count
+        FROM printers
+        GROUP BY connection_type
+        ORDER BY count DESC, connection_type ASC NULLS LAST;
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    match counts {
+        Ok(counts) => {
+            info!(
+                "Printer count by connection type retrieved: {} types",
+                counts.len()
+            );
+            Ok(Json(counts))
+        }
+        Err(e) => {
+            error!("Error retrieving printer count by connection type: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error retrieving printer count by connection type."),
+            ))
+        }
+    }
+}
+
+/// Printers whose name or model contain every word of `q`, case-insensitively,
+/// at most `SEARCH_MAX_RESULTS` of them. The match runs on the indexed
+/// `search_vector`, ranked so name matches come before model matches; words
+/// match whole, and query punctuation is not an operator.
+pub async fn search_printers(
+    Query(params): Query<SearchParams>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    // Query is empty
+    if params.q.trim().is_empty() {
+        error!("Search query cannot be empty.");
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json("Search query cannot be empty."),
+        ));
+    }
+
+    let cap = state.config.search_max_results;
+    let printers: Result<Vec<Printer>, sqlx::Error> = sqlx::query_as(
+        r#"
+        SELECT * FROM printers
+        WHERE search_vector @@ plainto_tsquery('simple', $1)
+        ORDER BY ts_rank(search_vector, plainto_tsquery('simple', $1)) DESC, name ASC, id
+        LIMIT $2;
+        "#,
+    )
+    .bind(params.q.trim())
+    .bind(cap + 1)
+    .fetch_all(&state.db)
+    .await;
+
+    match printers {
+        Ok(printers) => {
+            let results = SearchResults::capped(printers, cap);
+            info!(
+                "Printer search matched {} printers (truncated: {})",
+                results.data.len(),
+                results.truncated
+            );
+            Ok(Json(results))
+        }
+        Err(e) => {
+            error!("Error searching printers: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error searching printers."),
+            ))
+        }
+    }
+}
+
+/// Distinct printer models in use with the number of printers of each,
+/// optionally limited to one brand.
+pub async fn show_printer_models(
+    Query(filter): Query<PrinterModelFilter>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let models: Result<Vec<PrinterModelCount>, sqlx::Error> = sqlx::query_as(
+        r#"
+        SELECT model, COUNT(*) AS count
+        FROM printers
+        WHERE ($1::uuid IS NULL OR brand = $1)
+        GROUP BY model
+        ORDER BY model ASC;
+        "#,
+    )
+    .bind(filter.brand)
+    .fetch_all(&state.db)
+    .await;
+
+    match models {
+        Ok(models) => {
+            info!("Printer models retrieved: {} models", models.len());
+            Ok(Json(models))
+        }
+        Err(e) => {
+            error!("Error retrieving printer models: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error retrieving printer models."),
+            ))
+        }
+    }
+}
+
+/// Leased printers still in service whose lease ends within `within_days`
+/// days (30 by default), soonest first. Leases that already ended are listed
+/// too, as those printers are overdue for return.
+pub async fn show_expiring_leases(
+    Query(params): Query<ExpiringLeaseParams>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let within_days = params.within_days.unwrap_or(DEFAULT_LEASE_WINDOW_DAYS);
+
+    // Negative window
+    if within_days < 0 {
+        error!("Invalid lease window: {within_days}");
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json("`within_days` cannot be negative."),
+        ));
+    }
+
+    let leases: Result<Vec<ExpiringLease>, sqlx::Error> = sqlx::query_as(
+        r#"
+        SELECT
+            id, name, model, owner_department, lease_end_date,
+            lease_end_date - CURRENT_DATE AS days_left
+        FROM printers
+        WHERE acquisition_type = 'leased'
+            AND status <> 'retired'
+            AND lease_end_date <= CURRENT_DATE + $1
+        ORDER BY lease_end_date ASC, id ASC;
+        "#,
+    )
+    .bind(within_days)
+    .fetch_all(&state.db)
+    .await;
+
+    match leases {
+        Ok(leases) => {
+            info!("Expiring leases listed: {} printers", leases.len());
+            Ok(Json(leases))
+        }
+        Err(e) => {
+            error!("Error listing expiring leases: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error listing expiring leases."),
+            ))
+        }
+    }
+}
+
+/// Printers grouped by the toner they use, for planning batch swaps when a
+/// toner is phased out. Read with a single join, ordered by toner so each
+/// group is a run of consecutive rows.
+pub async fn show_printers_grouped_by_toner(
+    Query(filter): Query<TonerGroupFilter>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let rows: Result<Vec<PrinterWithToner>, sqlx::Error> = sqlx::query_as(
+        r#"
+        SELECT printers.*, toners.name AS toner_name
+        FROM printers
+        JOIN toners ON toners.id = printers.toner
+        WHERE ($1::uuid IS NULL OR printers.toner = $1)
+        ORDER BY toners.name, toners.id, printers.id;
+        "#,
+    )
+    .bind(filter.toner)
+    .fetch_all(&state.db)
+    .await;
+
+    match rows {
+        Ok(rows) => {
+            let mut groups: Vec<TonerPrinters> = Vec::new();
+            for row in rows {
+                match groups.last_mut() {
+                    Some(group) if group.toner.id == row.printer.toner => {
+                        group.printers.push(row.printer)
+                    }
+                    _ => groups.push(TonerPrinters {
+                        toner: TonerSummary {
+                            id: row.printer.toner,
+                            name: row.toner_name,
+                        },
+                        printers: vec![row.printer],
+                    }),
+                }
+            }
+            info!("Printers grouped by toner: {} toners", groups.len());
+            Ok(Json(groups))
+        }
+        Err(e) => {
+            error!("Error grouping printers by toner: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error grouping printers by toner."),
+            ))
+        }
+    }
+}
+
 pub async fn search_printer(
     Path(id): Path<Uuid>,
+    actor: Actor,
+    headers: HeaderMap,
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
-    match sqlx::query_as::<_, Printer>(r#"SELECT * FROM printers WHERE id = $1;"#)
+    let json_api = jsonapi::requested(&headers);
+
+    let printer = sqlx::query(queries::PRINTER_BY_ID)
         .bind(id)
         .fetch_optional(&state.db)
         .await
-    {
-        Ok(Some(printer)) => {
+        .and_then(|row| {
+            row.map(|row| Audited::<Printer>::from_row(&row, actor.is_admin()))
+                .transpose()
+        });
+
+    // The plain view lists the printer's documents along with it.
+    let body = match printer {
+        Ok(Some(printer)) if json_api => Ok(Some(serde_json::json!(Document::single(&printer)))),
+        Ok(Some(printer)) => document::printer_documents(&state.db, id)
+            .await
+            .map(|documents| Some(serde_json::json!(PrinterDetail { printer, documents }))),
+        Ok(None) => Ok(None),
+        Err(e) => Err(e),
+    };
+
+    match body {
+        Ok(Some(body)) => {
             info!("Printer found: {id}");
-            (StatusCode::OK, Json(Some(printer)))
+            (StatusCode::OK, jsonapi::respond(json_api, body))
         }
         Ok(None) => {
             error!("No printer found.");
-            (StatusCode::NOT_FOUND, Json(None))
+            (StatusCode::NOT_FOUND, Json(None::<Printer>).into_response())
         }
         Err(e) => {
             error!("Error retrieving printer: {e}");
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(None))
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(None::<Printer>).into_response(),
+            )
         }
     }
 }
 
-pub async fn show_printers(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let printers: Result<Vec<Printer>, sqlx::Error> = sqlx::query_as(r#"SELECT * FROM printers;"#)
-        .fetch_all(&state.db)
-        .await;
-    match printers {
-        Ok(printers) => {
+/// Conditions of the printer list: `$1` is the department, `$2` the tags,
+/// `$3` whether a printer needs all of them rather than any and `$4` the
+/// connection type.
+const PRINTER_FILTER: &str = r#"
+    ($1::varchar IS NULL OR owner_department = $1)
+    AND ($4::varchar IS NULL OR connection_type = $4)
+    AND (
+        cardinality($2::varchar[]) = 0
+        OR (
+            SELECT COUNT(*) FROM printer_tags
+            JOIN tags ON tags.id = printer_tags.tag_id
+            WHERE printer_tags.printer_id = printers.id AND tags.name = ANY($2)
+        ) >= CASE WHEN $3 THEN cardinality($2::varchar[]) ELSE 1 END
+    )
+"#;
+
+pub async fn show_printers(
+    Query(pagination): Query<PaginationParams>,
+    Query(filter): Query<PrinterFilter>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let json_api = jsonapi::requested(&headers);
+    let (limit, offset) = match pagination.resolve(&state.config) {
+        Ok(window) => window,
+        Err(message) => {
+            error!("Invalid pagination: {message}");
+            return Err((StatusCode::BAD_REQUEST, Json(message)));
+        }
+    };
+
+    let tags = filter.tags();
+    let all_tags = matches!(filter.tag_match, TagMatch::All);
+
+    let total: Result<(i64,), sqlx::Error> = sqlx::query_as(&format!(
+        r#"SELECT COUNT(*) FROM printers WHERE {PRINTER_FILTER};"#
+    ))
+    .bind(&filter.department)
+    .bind(&tags)
+    .bind(all_tags)
+    .bind(filter.connection_type)
+    .fetch_one(&state.db)
+    .await;
+    let printers: Result<Vec<Printer>, sqlx::Error> = sqlx::query_as(&format!(
+        r#"
+        SELECT * FROM printers
+        WHERE {PRINTER_FILTER}
+        ORDER BY id
+        LIMIT $5 OFFSET $6;
+        "#
+    ))
+    .bind(&filter.department)
+    .bind(&tags)
+    .bind(all_tags)
+    .bind(filter.connection_type)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await;
+    match (printers, total) {
+        (Ok(printers), Ok((total,))) => {
             info!("Printers listed successfully");
-            Ok(Json(printers))
+            let page = Page::new(printers, total, limit, offset, &uri);
+            let page = if json_api {
+                serde_json::json!(Document::page(page))
+            } else {
+                serde_json::json!(page)
+            };
+            Ok(jsonapi::respond(json_api, page))
         }
-        Err(e) => {
+        (Err(e), _) | (_, Err(e)) => {
             error!("Error listing printers: {e}");
             Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -80,59 +470,199 @@ pub async fn show_printers(State(state): State<Arc<AppState>>) -> impl IntoRespo
 }
 
 pub async fn create_printer(
+    actor: Actor,
     State(state): State<Arc<AppState>>,
     Json(request): Json<CreatePrinterRequest>,
 ) -> impl IntoResponse {
-    let new_printer = Printer::new(
-        &request.name,
-        &request.model,
-        Uuid::from_str(&request.brand).unwrap(),
-        Uuid::from_str(&request.toner).unwrap(),
-        Uuid::from_str(&request.drum).unwrap(),
-    );
+    insert_printer(&state.db, actor, &state, request)
+        .await
+        .into_response()
+}
+
+/// Validates and inserts a printer, writing through `executor` so that the
+/// insert can share a transaction with other changes, as when a draft is
+/// finalized.
+pub async fn insert_printer<'e>(
+    executor: impl PgExecutor<'e>,
+    actor: Actor,
+    state: &AppState,
+    request: CreatePrinterRequest,
+) -> impl IntoResponse {
+    // Malformed id
+    let Some([brand, toner, drum]) =
+        ids::parse_all([&request.brand, &request.toner, &request.drum])
+    else {
+        error!("Printer references a malformed id.");
+        return (StatusCode::BAD_REQUEST, Err(Json("Invalid id.".into())));
+    };
+
+    let new_printer = Printer {
+        acquisition_type: request.acquisition_type.unwrap_or_default(),
+        lease_end_date: request.lease_end_date,
+        connection_type: request.connection_type,
+        ..Printer::new(
+            &request.name,
+            request.model.trim(),
+            brand,
+            toner,
+            drum,
+            request.owner_department.as_deref(),
+            request.status,
+        )
+    };
+
+    // Invalid id
+    if let Err(message) = validations::check_ids(
+        [new_printer.brand, new_printer.toner, new_printer.drum],
+        state.config.require_uuid_v4,
+    ) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+    }
 
     // Check duplicate
-    match sqlx::query(r#"SELECT id FROM printers WHERE name = $1;"#)
-        .bind(&new_printer.name)
-        .fetch_optional(&state.db)
-        .await
+    match name_taken(
+        &state.db,
+        state.config.printer_name_scope,
+        &new_printer.name,
+        new_printer.brand,
+        None,
+    )
+    .await
     {
-        Ok(Some(_)) => {
+        Ok(true) => {
             error!("Printer '{}' already exists.", &new_printer.name);
-            (StatusCode::CONFLICT, Err(Json("Printer already exists.")))
+            (
+                StatusCode::CONFLICT,
+                Err(Json(ApiError::duplicate_name(conflict_message(
+                    state.config.printer_name_scope,
+                )))),
+            )
         }
-        Ok(None) => {
+        Ok(false) => {
             // Name is empty
             if new_printer.name.is_empty() {
                 error!("Printer name cannot be empty.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Printer name cannot be empty.")),
+                    Err(Json(ApiError::name_empty("Printer name cannot be empty."))),
                 );
             }
 
             // Name too short
-            if new_printer.name.len() < 4 {
+            if new_printer.name.chars().count() < NAME_MIN_LENGTH {
                 error!("Printer name is too short.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Printer name is too short.")),
+                    Err(Json(ApiError::name_too_short(
+                        "Printer name is too short.",
+                        NAME_MIN_LENGTH,
+                        new_printer.name.chars().count(),
+                    ))),
                 );
             }
 
             // Name too long
-            if new_printer.name.len() > 20 {
+            if new_printer.name.chars().count() > NAME_MAX_LENGTH {
                 error!("Printer name is too long.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Printer name is too long.")),
+                    Err(Json(ApiError::name_too_long(
+                        "Printer name is too long.",
+                        NAME_MAX_LENGTH,
+                        new_printer.name.chars().count(),
+                    ))),
+                );
+            }
+
+            // Name has characters that are not allowed
+            if let Err(e) =
+                validations::check_name_pattern(&state.config, Named::Printer, &new_printer.name)
+            {
+                error!("{}", e.message());
+                return (StatusCode::BAD_REQUEST, Err(Json(e)));
+            }
+
+            // Model is empty
+            if new_printer.model.is_empty() {
+                error!("Printer model cannot be empty.");
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Err(Json("Printer model cannot be empty.".into())),
+                );
+            }
+
+            // Model too short
+            if new_printer.model.chars().count() < state.config.printer_model_min_length {
+                error!("Printer model is too short.");
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Err(Json("Printer model is too short.".into())),
+                );
+            }
+
+            // Model too long
+            if new_printer.model.chars().count() > state.config.printer_model_max_length {
+                error!("Printer model is too long.");
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Err(Json("Printer model is too long.".into())),
+                );
+            }
+
+            // Department not allowed
+            if let Err(message) =
+                validate_department(&state.config, new_printer.owner_department.as_deref())
+            {
+                error!("{message}");
+                return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+            }
+
+            // Lease end date missing or not allowed
+            if let Err(message) =
+                validate_acquisition(new_printer.acquisition_type, new_printer.lease_end_date)
+            {
+                error!("{message}");
+                return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+            }
+
+            // Created retired
+            if new_printer.status == PrinterStatus::Retired {
+                error!("Printers cannot be created retired.");
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Err(Json(
+                        "Printers are retired through the decommission endpoint.".into(),
+                    )),
                 );
             }
 
+            // Name same as model
+            let mut warnings: Vec<String> =
+                match check_name_model(&state.config, &new_printer.name, &new_printer.model) {
+                    Ok(warning) => warning.into_iter().map(String::from).collect(),
+                    Err((status, message)) => return (status, Err(Json(message.into()))),
+                };
+
+            // Toner brand mismatch
+            match check_toner_brand(
+                &state.db,
+                state.config.soft_mode(state.config.toner_brand_validation),
+                new_printer.brand,
+                new_printer.toner,
+            )
+            .await
+            {
+                Ok(warning) => warnings.extend(warning.map(String::from)),
+                Err((status, message)) => return (status, Err(Json(message.into()))),
+            };
+
             match sqlx::query(
                 r#"
-                INSERT INTO printers (id, name, model, brand, toner, drum)
-                VALUES ($1, $2, $3, $4, $5, $6);
+                INSERT INTO printers
+                    (id, name, model, brand, toner, drum, owner_department, status,
+                    acquisition_type, lease_end_date, connection_type, created_by, updated_by)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $12);
                 "#,
             )
             .bind(new_printer.id)
@@ -141,109 +671,360 @@ pub async fn create_printer(
             .bind(new_printer.brand)
             .bind(new_printer.toner)
             .bind(new_printer.drum)
-            .execute(&state.db)
+            .bind(new_printer.owner_department)
+            .bind(new_printer.status)
+            .bind(new_printer.acquisition_type)
+            .bind(new_printer.lease_end_date)
+            .bind(new_printer.connection_type)
+            .bind(actor.id())
+            .execute(executor)
             .await
             {
                 Ok(_) => {
                     info!("Printer created! ID: {}", &new_printer.id);
-                    (StatusCode::CREATED, Ok(Json(new_printer.id)))
+                    (
+                        StatusCode::CREATED,
+                        Ok((
+                            location(format!("/api/v1/printer/{}", new_printer.id)),
+                            Json(MutationResponse {
+                                id: new_printer.id,
+                                warnings,
+                            }),
+                        )),
+                    )
+                }
+                // Another request took the name since the check above.
+                Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                    error!("Printer already exists: {}", e);
+                    (
+                        StatusCode::CONFLICT,
+                        Err(Json(ApiError::duplicate_name(conflict_message(
+                            state.config.printer_name_scope,
+                        )))),
+                    )
                 }
                 Err(e) => {
                     error!("Error creating printer: {}", e);
                     (
                         StatusCode::INTERNAL_SERVER_ERROR,
-                        Err(Json("Error creating printer.")),
+                        Err(Json("Error creating printer.".into())),
                     )
                 }
             }
         }
         Err(_) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Err(Json("Error creating printer.")),
+            Err(Json("Error creating printer.".into())),
         ),
     }
 }
 
+/// Checks the brand, toner and drum picked for a printer before it is
+/// created, without creating anything. A toner of another brand is reported
+/// along with its alternatives of the printer's brand, whatever
+/// `TONER_BRAND_VALIDATION` is set to.
+pub async fn validate_printer_compatibility(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<CompatibilityRequest>,
+) -> impl IntoResponse {
+    // Invalid id
+    if let Err(message) = validations::check_ids(
+        [request.brand, request.toner, request.drum],
+        state.config.require_uuid_v4,
+    ) {
+        error!("{message}");
+        return Err((StatusCode::BAD_REQUEST, Json(message)));
+    }
+
+    match compatibility_report(&state.db, &request).await {
+        Ok(report) => {
+            info!("Printer compatibility checked: {}", report.compatible);
+            Ok(Json(report))
+        }
+        Err(e) => {
+            error!("Error checking printer compatibility: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error checking printer compatibility."),
+            ))
+        }
+    }
+}
+
+async fn compatibility_report(
+    db: &PgPool,
+    request: &CompatibilityRequest,
+) -> Result<CompatibilityReport, sqlx::Error> {
+    let (brand_found, toner_found, toner_brand, drum_found): (bool, bool, Option<Uuid>, bool) =
+        sqlx::query_as(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM brands WHERE id = $1),
+                EXISTS (SELECT 1 FROM toners WHERE id = $2),
+                (SELECT brand FROM toners WHERE id = $2),
+                EXISTS (SELECT 1 FROM drums WHERE id = $3);
+            "#,
+        )
+        .bind(request.brand)
+        .bind(request.toner)
+        .bind(request.drum)
+        .fetch_one(db)
+        .await?;
+
+    let found = |found: bool, message: &'static str| {
+        if found {
+            ReferenceCheck::new(ReferenceStatus::Ok, None)
+        } else {
+            ReferenceCheck::new(ReferenceStatus::NotFound, Some(message))
+        }
+    };
+    let brand = found(brand_found, "Brand ID not found.");
+    let drum = found(drum_found, "Drum ID not found.");
+    let toner = if brand_found && toner_brand_mismatch(request.brand, toner_brand) {
+        let alternatives = sqlx::query_scalar(
+            r#"
+            SELECT toners.id FROM toner_equivalents
+            JOIN toners
+                ON toners.id IN (toner_equivalents.toner_id, toner_equivalents.equivalent_id)
+            WHERE $1 IN (toner_equivalents.toner_id, toner_equivalents.equivalent_id)
+                AND toners.id != $1 AND toners.brand = $2
+            ORDER BY toners.name, toners.id;
+            "#,
+        )
+        .bind(request.toner)
+        .bind(request.brand)
+        .fetch_all(db)
+        .await?;
+
+        ReferenceCheck {
+            alternatives,
+            ..ReferenceCheck::new(ReferenceStatus::BrandMismatch, Some(TONER_BRAND_MISMATCH))
+        }
+    } else {
+        found(toner_found, "Toner ID not found.")
+    };
+
+    Ok(CompatibilityReport {
+        compatible: [&brand, &toner, &drum]
+            .iter()
+            .all(|check| check.status == ReferenceStatus::Ok),
+        brand,
+        toner,
+        drum,
+    })
+}
+
 pub async fn update_printer(
+    actor: Actor,
     State(state): State<Arc<AppState>>,
     Json(request): Json<UpdatePrinterRequest>,
 ) -> impl IntoResponse {
+    // Malformed id
+    let Some([new_brand, new_toner, new_drum]) =
+        ids::parse_all([&request.brand, &request.toner, &request.drum])
+    else {
+        error!("Printer references a malformed id.");
+        return (StatusCode::BAD_REQUEST, Err(Json("Invalid id.".into())));
+    };
+
     let printer_id = request.id;
     let new_name = request.name;
-    let new_model = request.model;
-    let new_brand = Uuid::from_str(request.brand.as_str()).unwrap();
-    let new_toner = Uuid::from_str(request.toner.as_str()).unwrap();
-    let new_drum = Uuid::from_str(request.drum.as_str()).unwrap();
+    let new_model = request.model.trim().to_string();
+    let new_department = request.owner_department;
+    let new_acquisition = request.acquisition_type;
+    let new_lease_end_date = request.lease_end_date;
+    let new_connection = request.connection_type;
+
+    // Invalid id
+    if let Err(message) = validations::check_ids(
+        [printer_id, new_brand, new_toner, new_drum],
+        state.config.require_uuid_v4,
+    ) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+    }
 
     // ID not found
-    match sqlx::query(r#"SELECT id FROM printers WHERE id = $1;"#)
-        .bind(printer_id)
-        .fetch_optional(&state.db)
-        .await
-    {
-        Ok(Some(_)) => {
+    match queries::exists(&state.db, Table::Printers, printer_id).await {
+        Ok(true) => {
             // Name is empty
             if new_name.is_empty() {
                 error!("Printer name cannot be empty.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Printer name cannot be empty.")),
+                    Err(Json(ApiError::name_empty("Printer name cannot be empty."))),
                 );
             }
 
             // Name too short
-            if new_name.len() < 4 {
+            if new_name.chars().count() < NAME_MIN_LENGTH {
                 error!("Printer name is too short.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Printer name is too short.")),
+                    Err(Json(ApiError::name_too_short(
+                        "Printer name is too short.",
+                        NAME_MIN_LENGTH,
+                        new_name.chars().count(),
+                    ))),
                 );
             }
 
             // Name too long
-            if new_name.len() > 20 {
+            if new_name.chars().count() > NAME_MAX_LENGTH {
                 error!("Printer name is too long.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Printer name is too long.")),
+                    Err(Json(ApiError::name_too_long(
+                        "Printer name is too long.",
+                        NAME_MAX_LENGTH,
+                        new_name.chars().count(),
+                    ))),
+                );
+            }
+
+            // Name has characters that are not allowed
+            if let Err(e) =
+                validations::check_name_pattern(&state.config, Named::Printer, &new_name)
+            {
+                error!("{}", e.message());
+                return (StatusCode::BAD_REQUEST, Err(Json(e)));
+            }
+
+            // Model is empty
+            if new_model.is_empty() {
+                error!("Printer model cannot be empty.");
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Err(Json("Printer model cannot be empty.".into())),
+                );
+            }
+
+            // Model too short
+            if new_model.chars().count() < state.config.printer_model_min_length {
+                error!("Printer model is too short.");
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Err(Json("Printer model is too short.".into())),
                 );
             }
 
+            // Model too long
+            if new_model.chars().count() > state.config.printer_model_max_length {
+                error!("Printer model is too long.");
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Err(Json("Printer model is too long.".into())),
+                );
+            }
+
+            // Department not allowed
+            if let Err(message) = validate_department(&state.config, new_department.as_deref()) {
+                error!("{message}");
+                return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+            }
+
+            // Lease end date missing or not allowed
+            let acquisition = match new_acquisition {
+                Some(acquisition_type) => {
+                    validate_acquisition(acquisition_type, new_lease_end_date)
+                }
+                None if new_lease_end_date.is_some() => {
+                    Err("Lease end date must be sent with `acquisition_type`.")
+                }
+                None => Ok(()),
+            };
+            if let Err(message) = acquisition {
+                error!("{message}");
+                return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+            }
+
+            // Name same as model
+            let mut warnings: Vec<String> =
+                match check_name_model(&state.config, &new_name, &new_model) {
+                    Ok(warning) => warning.into_iter().map(String::from).collect(),
+                    Err((status, message)) => return (status, Err(Json(message.into()))),
+                };
+
+            // Toner brand mismatch
+            match check_toner_brand(
+                &state.db,
+                state.config.soft_mode(state.config.toner_brand_validation),
+                new_brand,
+                new_toner,
+            )
+            .await
+            {
+                Ok(warning) => warnings.extend(warning.map(String::from)),
+                Err((status, message)) => return (status, Err(Json(message.into()))),
+            };
+
             // Check duplicate
-            match sqlx::query(r#"SELECT id FROM printers WHERE name = $1 AND id != $2;"#)
-                .bind(&new_name)
-                .bind(printer_id)
-                .fetch_optional(&state.db)
-                .await
+            match name_taken(
+                &state.db,
+                state.config.printer_name_scope,
+                &new_name,
+                new_brand,
+                Some(printer_id),
+            )
+            .await
             {
-                Ok(Some(_)) => {
+                Ok(true) => {
                     error!("Printer name already exists.");
-                    (StatusCode::CONFLICT, Err(Json("Printer already exists.")))
+                    (
+                        StatusCode::CONFLICT,
+                        Err(Json(ApiError::duplicate_name(conflict_message(
+                            state.config.printer_name_scope,
+                        )))),
+                    )
                 }
-                Ok(None) => {
+                Ok(false) => {
                     match sqlx::query(
                         r#"UPDATE printers 
-                    SET name = $1, model = $2, brand = $3, toner = $4, drum = $5 
-                    WHERE id = $6;"#,
+                    SET name = $1, model = $2, brand = $3, toner = $4, drum = $5,
+                        owner_department = $6, updated_by = $8,
+                        acquisition_type = COALESCE($9, acquisition_type),
+                        lease_end_date = CASE WHEN $9 IS NULL THEN lease_end_date ELSE $10 END,
+                        connection_type = COALESCE($11, connection_type)
+                    WHERE id = $7;"#,
                     )
                     .bind(&new_name)
                     .bind(&new_model)
                     .bind(new_brand)
                     .bind(new_toner)
                     .bind(new_drum)
+                    .bind(&new_department)
                     .bind(printer_id)
+                    .bind(actor.id())
+                    .bind(new_acquisition)
+                    .bind(new_lease_end_date)
+                    .bind(new_connection)
                     .execute(&state.db)
                     .await
                     {
                         Ok(_) => {
                             info!("Printer updated! ID: {}", &printer_id);
-                            (StatusCode::OK, Ok(Json(printer_id)))
+                            (
+                                StatusCode::OK,
+                                Ok(Json(MutationResponse {
+                                    id: printer_id,
+                                    warnings,
+                                })),
+                            )
+                        }
+                        // Another request took the name since the check above.
+                        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                            error!("Printer name already exists: {}", e);
+                            (
+                                StatusCode::CONFLICT,
+                                Err(Json(ApiError::duplicate_name(conflict_message(
+                                    state.config.printer_name_scope,
+                                )))),
+                            )
                         }
                         Err(e) => {
                             error!("Error updating printer: {}", e);
                             (
                                 StatusCode::INTERNAL_SERVER_ERROR,
-                                Err(Json("Error updating printer.")),
+                                Err(Json("Error updating printer.".into())),
                             )
                         }
                     }
@@ -252,63 +1033,1247 @@ pub async fn update_printer(
                     error!("Error checking for duplicate printer name: {}", e);
                     (
                         StatusCode::INTERNAL_SERVER_ERROR,
-                        Err(Json("Error checking for duplicated printer name.")),
+                        Err(Json("Error checking for duplicated printer name.".into())),
                     )
                 }
             }
         }
-        Ok(None) => {
+        Ok(false) => {
             error!("Printer ID not found.");
-            (StatusCode::NOT_FOUND, Err(Json("Printer ID not found.")))
+            (
+                StatusCode::NOT_FOUND,
+                Err(Json("Printer ID not found.".into())),
+            )
         }
         Err(e) => {
             error!("Error fetching printer by ID: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Err(Json("Error fetching printer by ID")),
+                Err(Json("Error fetching printer by ID".into())),
             )
         }
     }
 }
 
-pub async fn delete_printer(
+/// QR code for the label on a device: an SVG encoding a deep link to the
+/// printer when `PRINTER_QR_BASE_URL` is set, or its bare id otherwise.
+pub async fn printer_qr_code(
+    Path(id): Path<Uuid>,
     State(state): State<Arc<AppState>>,
-    Json(request): Json<DeleteRequest>,
 ) -> impl IntoResponse {
-    match sqlx::query(r#"SELECT id FROM printers WHERE id = $1;"#)
-        .bind(request.id)
-        .fetch_optional(&state.db)
-        .await
-    {
-        Ok(Some(_)) => {
-            match sqlx::query(r#"DELETE FROM printers WHERE id = $1;"#)
-                .bind(request.id)
-                .execute(&state.db)
-                .await
-            {
-                Ok(_) => {
-                    info!("Printer deleted! ID: {}", &request.id);
-                    (StatusCode::OK, Ok(Json("Printer deleted!")))
+    match queries::exists(&state.db, Table::Printers, id).await {
+        Ok(true) => {
+            let payload = match &state.config.printer_qr_base_url {
+                Some(base_url) => format!("{}/{id}", base_url.trim_end_matches('/')),
+                None => id.to_string(),
+            };
+
+            match QrCode::new(payload.as_bytes()) {
+                Ok(code) => {
+                    info!("QR code generated for printer {id}");
+                    let svg = code.render::<svg::Color>().min_dimensions(200, 200).build();
+                    Ok(([(header::CONTENT_TYPE, "image/svg+xml")], svg))
                 }
                 Err(e) => {
-                    error!("Error deleting printer: {}", e);
-                    (
+                    error!("Error generating QR code: {}", e);
+                    Err((
                         StatusCode::INTERNAL_SERVER_ERROR,
-                        Ok(Json("Error deleting printer.")),
-                    )
+                        Json("Error generating QR code."),
+                    ))
                 }
             }
         }
-        Ok(None) => {
+        Ok(false) => {
             error!("Printer ID not found.");
-            (StatusCode::NOT_FOUND, Err(Json("Printer ID not found")))
+            Err((StatusCode::NOT_FOUND, Json("Printer ID not found.")))
         }
         Err(e) => {
-            error!("Error deleting printer: {}", e);
-            (
+            error!("Error fetching printer by ID: {}", e);
+            Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Err(Json("Error deleting printer.")),
-            )
+                Json("Error fetching printer by ID"),
+            ))
         }
     }
 }
+
+/// Printers with the highest reported page count first.
+pub async fn show_printers_by_usage(
+    Query(pagination): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let (limit, offset) = match pagination.resolve(&state.config) {
+        Ok(window) => window,
+        Err(message) => {
+            error!("Invalid pagination: {message}");
+            return Err((StatusCode::BAD_REQUEST, Json(message)));
+        }
+    };
+
+    let total: Result<(i64,), sqlx::Error> = sqlx::query_as(queries::COUNT_PRINTERS)
+        .fetch_one(&state.db)
+        .await;
+    let printers: Result<Vec<Printer>, sqlx::Error> = sqlx::query_as(
+        r#"
+        SELECT * FROM printers
+        ORDER BY page_count DESC, name ASC, id
+        LIMIT $1 OFFSET $2;
+        "#,
+    )
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await;
+    match (printers, total) {
+        (Ok(printers), Ok((total,))) => {
+            info!("Printers listed by usage");
+            Ok(Json(Page::new(printers, total, limit, offset, &uri)))
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            error!("Error listing printers by usage: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error listing printers by usage."),
+            ))
+        }
+    }
+}
+
+/// Stores the lifetime page count read from a printer's meter. Counters only
+/// grow, so a lower value is rejected unless `?reset=true` marks a meter reset.
+pub async fn report_printer_pages(
+    Path(id): Path<Uuid>,
+    Query(params): Query<ReportPagesParams>,
+    actor: Actor,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ReportPagesRequest>,
+) -> impl IntoResponse {
+    // Negative count
+    if request.page_count < 0 {
+        error!("Page count cannot be negative.");
+        return (
+            StatusCode::BAD_REQUEST,
+            Err(Json("Page count cannot be negative.")),
+        );
+    }
+
+    match sqlx::query_as::<_, Printer>(
+        r#"
+        UPDATE printers SET page_count = $2, updated_by = $4
+        WHERE id = $1 AND ($3 OR page_count <= $2)
+        RETURNING *;
+        "#,
+    )
+    .bind(id)
+    .bind(request.page_count)
+    .bind(params.reset)
+    .bind(actor.id())
+    .fetch_optional(&state.db)
+    .await
+    {
+        Ok(Some(printer)) => {
+            info!(
+                "Page count of printer {} set to {}",
+                &id, printer.page_count
+            );
+            (StatusCode::OK, Ok(Json(printer)))
+        }
+        Ok(None) => match queries::exists(&state.db, Table::Printers, id).await {
+            Ok(true) => {
+                error!("Page count of printer {} cannot decrease.", &id);
+                (
+                    StatusCode::BAD_REQUEST,
+                    Err(Json(
+                        "Page count cannot be lower than the stored count without reset.",
+                    )),
+                )
+            }
+            Ok(false) => {
+                error!("Printer ID not found.");
+                (StatusCode::NOT_FOUND, Err(Json("Printer ID not found.")))
+            }
+            Err(e) => {
+                error!("Error reporting printer pages: {e}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Err(Json("Error reporting printer pages.")),
+                )
+            }
+        },
+        Err(e) => {
+            error!("Error reporting printer pages: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error reporting printer pages.")),
+            )
+        }
+    }
+}
+
+/// Retires a printer: marks it `retired` with the date and reason and, with
+/// `return_toner`, puts its installed toner back into stock through a
+/// movement, all in one transaction.
+pub async fn decommission_printer(
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    Extension(deadline): Extension<Deadline>,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<DecommissionRequest>,
+) -> impl IntoResponse {
+    let reason = request.reason.trim();
+
+    // Reason is empty
+    if reason.is_empty() {
+        error!("Decommission reason cannot be empty.");
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json("Decommission reason cannot be empty."),
+        ));
+    }
+
+    // Reason too long
+    if reason.chars().count() > DECOMMISSION_REASON_MAX_LENGTH {
+        error!("Decommission reason is too long.");
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json("Decommission reason is too long."),
+        ));
+    }
+
+    let mut tx = match state.begin(deadline).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!("Error decommissioning printer: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error decommissioning printer."),
+            ));
+        }
+    };
+
+    // The lock keeps concurrent decommissions from both passing the check.
+    let printer =
+        match sqlx::query_as::<_, Printer>(r#"SELECT * FROM printers WHERE id = $1 FOR UPDATE;"#)
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+        {
+            Ok(Some(printer)) => printer,
+            Ok(None) => {
+                error!("Printer ID not found.");
+                return Err((StatusCode::NOT_FOUND, Json("Printer ID not found.")));
+            }
+            Err(e) => {
+                error!("Error decommissioning printer: {e}");
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json("Error decommissioning printer."),
+                ));
+            }
+        };
+
+    // Already retired
+    if printer.status == PrinterStatus::Retired {
+        error!("Printer {id} is already retired.");
+        return Err((StatusCode::CONFLICT, Json("Printer is already retired.")));
+    }
+
+    let retired = async {
+        let row = retire(&mut tx, &printer, reason, request.return_toner, actor.id()).await?;
+        tx.commit().await?;
+        Audited::<Printer>::from_row(&row, actor.is_admin())
+    }
+    .await;
+
+    match retired {
+        Ok(printer) => {
+            info!("Printer decommissioned! ID: {id}");
+            Ok(Json(printer))
+        }
+        Err(e) => {
+            error!("Error decommissioning printer: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error decommissioning printer."),
+            ))
+        }
+    }
+}
+
+/// Applies the decommission steps to the locked `printer`.
+async fn retire(
+    tx: &mut Transaction<'static, Postgres>,
+    printer: &Printer,
+    reason: &str,
+    return_toner: bool,
+    actor: Option<&str>,
+) -> Result<PgRow, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        UPDATE printers
+        SET status = $2, decommissioned_at = NOW(), decommission_reason = $3, updated_by = $4
+        WHERE id = $1
+        RETURNING *;
+        "#,
+    )
+    .bind(printer.id)
+    .bind(PrinterStatus::Retired)
+    .bind(reason)
+    .bind(actor)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    if return_toner {
+        let movement = Movement::new(printer.id, Some(printer.toner), None, 1);
+        sqlx::query(
+            r#"
+            INSERT INTO movements (id, printer_id, toner_id, drum_id, quantity, created_at)
+            VALUES ($1, $2, $3, NULL, $4, $5);
+            "#,
+        )
+        .bind(movement.id)
+        .bind(movement.printer_id)
+        .bind(movement.toner_id)
+        .bind(movement.quantity)
+        .bind(movement.created_at)
+        .execute(&mut **tx)
+        .await?;
+        sqlx::query(r#"UPDATE toners SET stock = stock + $1, updated_by = $3 WHERE id = $2;"#)
+            .bind(movement.quantity)
+            .bind(printer.toner)
+            .bind(actor)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(row)
+}
+
+/// Corrects the brand of a printer, recording the old and new brand in the
+/// audit log, in one transaction. The new brand is held to the same name
+/// scope and toner brand rule as a full update.
+pub async fn swap_printer_brand(
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    Extension(deadline): Extension<Deadline>,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<BrandSwapRequest>,
+) -> impl IntoResponse {
+    // Invalid id
+    if let Err(message) = validations::check_ids([request.brand], state.config.require_uuid_v4) {
+        error!("{message}");
+        return Err((StatusCode::BAD_REQUEST, Json(message.into())));
+    }
+
+    let mut tx = match state.begin(deadline).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!("Error changing printer brand: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error changing printer brand.".into()),
+            ));
+        }
+    };
+
+    let printer =
+        match sqlx::query_as::<_, Printer>(r#"SELECT * FROM printers WHERE id = $1 FOR UPDATE;"#)
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+        {
+            Ok(Some(printer)) => printer,
+            Ok(None) => {
+                error!("Printer ID not found.");
+                return Err((StatusCode::NOT_FOUND, Json("Printer ID not found.".into())));
+            }
+            Err(e) => {
+                error!("Error changing printer brand: {e}");
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json("Error changing printer brand.".into()),
+                ));
+            }
+        };
+
+    // Brand not found
+    match queries::exists(&mut *tx, Table::Brands, request.brand).await {
+        Ok(true) => {}
+        Ok(false) => {
+            error!("Brand ID not found.");
+            return Err((StatusCode::BAD_REQUEST, Json("Brand ID not found.".into())));
+        }
+        Err(e) => {
+            error!("Error changing printer brand: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error changing printer brand.".into()),
+            ));
+        }
+    }
+
+    // Toner brand mismatch
+    let warnings: Vec<String> = match check_toner_brand(
+        &mut *tx,
+        state.config.soft_mode(state.config.toner_brand_validation),
+        request.brand,
+        printer.toner,
+    )
+    .await
+    {
+        Ok(warning) => warning.into_iter().map(String::from).collect(),
+        Err((status, message)) => return Err((status, Json(message.into()))),
+    };
+
+    // Check duplicate
+    match name_taken(
+        &mut *tx,
+        state.config.printer_name_scope,
+        &printer.name,
+        request.brand,
+        Some(id),
+    )
+    .await
+    {
+        Ok(false) => {}
+        Ok(true) => {
+            error!("Printer name already exists for the new brand.");
+            return Err((
+                StatusCode::CONFLICT,
+                Json(ApiError::duplicate_name(conflict_message(
+                    state.config.printer_name_scope,
+                ))),
+            ));
+        }
+        Err(e) => {
+            error!("Error checking for duplicate printer name: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error checking for duplicated printer name.".into()),
+            ));
+        }
+    }
+
+    let changes = serde_json::json!({
+        "brand": { "old": printer.brand, "new": request.brand },
+    });
+    let swapped = async {
+        let row = sqlx::query(&audit::update_statement("printers", "brand"))
+            .bind(id)
+            .bind(request.brand)
+            .bind(actor.id())
+            .bind(changes.to_string())
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Audited::<Printer>::from_row(&row, actor.is_admin())
+    }
+    .await;
+
+    match swapped {
+        Ok(printer) => {
+            info!(
+                "Printer {} brand changed from {} to {}",
+                &id, &changes["brand"]["old"], &request.brand
+            );
+            Ok(Json(BrandSwapResponse { printer, warnings }))
+        }
+        // Another request took the name since the check above.
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            error!("Printer name already exists: {e}");
+            Err((
+                StatusCode::CONFLICT,
+                Json(ApiError::duplicate_name(conflict_message(
+                    state.config.printer_name_scope,
+                ))),
+            ))
+        }
+        Err(e) => {
+            error!("Error changing printer brand: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error changing printer brand.".into()),
+            ))
+        }
+    }
+}
+
+/// Sets the status of many printers at once, such as for a service window,
+/// in one transaction. Ids that do not exist are listed rather than failing
+/// the whole batch, and retired printers are left untouched.
+pub async fn bulk_update_printer_status(
+    actor: Actor,
+    Extension(deadline): Extension<Deadline>,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<BulkStatusRequest>,
+) -> impl IntoResponse {
+    // No ids
+    if request.ids.is_empty() {
+        error!("Printer ID list cannot be empty.");
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json("Printer ID list cannot be empty."),
+        ));
+    }
+
+    // Ids repeated in the list count once, in the order first given
+    let mut seen = HashSet::new();
+    let ids: Vec<Uuid> = request
+        .ids
+        .iter()
+        .copied()
+        .filter(|id| seen.insert(*id))
+        .collect();
+
+    // Too many ids
+    if ids.len() > BULK_STATUS_MAX_IDS {
+        error!("Printer ID list exceeds {BULK_STATUS_MAX_IDS} ids.");
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json("Printer ID list cannot have more than 1000 ids."),
+        ));
+    }
+
+    // Retiring skips the decommission workflow
+    if request.status == PrinterStatus::Retired {
+        error!("Printers cannot be retired in bulk.");
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json("Printers are retired through the decommission endpoint."),
+        ));
+    }
+
+    let updated = async {
+        let mut tx = state.begin(deadline).await?;
+
+        let found: Vec<(Uuid, PrinterStatus)> =
+            sqlx::query_as(r#"SELECT id, status FROM printers WHERE id = ANY($1) FOR UPDATE;"#)
+                .bind(&ids)
+                .fetch_all(&mut *tx)
+                .await?;
+
+        let updated = sqlx::query(
+            r#"
+            UPDATE printers
+            SET status = $2, updated_by = $3
+            WHERE id = ANY($1) AND status <> $4;
+            "#,
+        )
+        .bind(&ids)
+        .bind(request.status)
+        .bind(actor.id())
+        .bind(PrinterStatus::Retired)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>((updated, found))
+    }
+    .await;
+
+    match updated {
+        Ok((updated, found)) => {
+            let found_ids: HashSet<Uuid> = found.iter().map(|(id, _)| *id).collect();
+            let not_found = ids
+                .into_iter()
+                .filter(|id| !found_ids.contains(id))
+                .collect();
+            let retired = found
+                .into_iter()
+                .filter(|(_, status)| *status == PrinterStatus::Retired)
+                .map(|(id, _)| id)
+                .collect();
+
+            info!("Printer status updated in bulk! Updated: {updated}");
+            Ok(Json(BulkStatusResponse {
+                updated,
+                not_found,
+                retired,
+            }))
+        }
+        Err(e) => {
+            error!("Error updating printer status: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error updating printer status."),
+            ))
+        }
+    }
+}
+
+/// Copies an existing printer under a new id, lease terms included. The copy
+/// takes the `name` from the body when given, otherwise the source name with a
+/// ` (copy)` suffix, shortened to keep the copy within the name limit. The
+/// body may be omitted, but a body that is not a valid request is rejected.
+pub async fn clone_printer(
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    request: Result<Json<ClonePrinterRequest>, Response>,
+) -> impl IntoResponse {
+    let name = match request {
+        Ok(Json(request)) => request.name,
+        Err(_) if !content_type::has_body(&headers) => None,
+        // Invalid body
+        Err(rejection) => {
+            error!("Invalid clone request body: {}", rejection.status());
+            return (
+                StatusCode::BAD_REQUEST,
+                Err(Json("Request body must be a valid clone request.".into())),
+            );
+        }
+    };
+
+    let source = match sqlx::query_as::<_, Printer>(queries::PRINTER_BY_ID)
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await
+    {
+        Ok(Some(printer)) => printer,
+        Ok(None) => {
+            error!("Printer ID not found.");
+            return (
+                StatusCode::NOT_FOUND,
+                Err(Json("Printer ID not found.".into())),
+            );
+        }
+        Err(e) => {
+            error!("Error fetching printer by ID: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error fetching printer by ID".into())),
+            );
+        }
+    };
+
+    let name = name.unwrap_or_else(|| {
+        let kept: String = source
+            .name
+            .chars()
+            .take(NAME_MAX_LENGTH - COPY_SUFFIX.len())
+            .collect();
+        format!("{}{COPY_SUFFIX}", kept.trim_end())
+    });
+    let new_printer = Printer {
+        acquisition_type: source.acquisition_type,
+        lease_end_date: source.lease_end_date,
+        connection_type: source.connection_type,
+        ..Printer::new(
+            &name,
+            &source.model,
+            source.brand,
+            source.toner,
+            source.drum,
+            source.owner_department.as_deref(),
+            None,
+        )
+    };
+
+    // Name is empty
+    if new_printer.name.is_empty() {
+        error!("Printer name cannot be empty.");
+        return (
+            StatusCode::BAD_REQUEST,
+            Err(Json(ApiError::name_empty("Printer name cannot be empty."))),
+        );
+    }
+
+    // Name too short
+    if new_printer.name.chars().count() < NAME_MIN_LENGTH {
+        error!("Printer name is too short.");
+        return (
+            StatusCode::BAD_REQUEST,
+            Err(Json(ApiError::name_too_short(
+                "Printer name is too short.",
+                NAME_MIN_LENGTH,
+                new_printer.name.chars().count(),
+            ))),
+        );
+    }
+
+    // Name too long
+    if new_printer.name.chars().count() > NAME_MAX_LENGTH {
+        error!("Printer name is too long.");
+        return (
+            StatusCode::BAD_REQUEST,
+            Err(Json(ApiError::name_too_long(
+                "Printer name is too long.",
+                NAME_MAX_LENGTH,
+                new_printer.name.chars().count(),
+            ))),
+        );
+    }
+
+    // Name has characters that are not allowed
+    if let Err(e) =
+        validations::check_name_pattern(&state.config, Named::Printer, &new_printer.name)
+    {
+        error!("{}", e.message());
+        return (StatusCode::BAD_REQUEST, Err(Json(e)));
+    }
+
+    // Check duplicate
+    match name_taken(
+        &state.db,
+        state.config.printer_name_scope,
+        &new_printer.name,
+        new_printer.brand,
+        None,
+    )
+    .await
+    {
+        Ok(true) => {
+            error!("Printer '{}' already exists.", &new_printer.name);
+            (
+                StatusCode::CONFLICT,
+                Err(Json(ApiError::duplicate_name(conflict_message(
+                    state.config.printer_name_scope,
+                )))),
+            )
+        }
+        Ok(false) => {
+            match sqlx::query(
+                r#"
+                INSERT INTO printers
+                    (id, name, model, brand, toner, drum, owner_department, acquisition_type,
+                    lease_end_date, connection_type, created_by, updated_by)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $11);
+                "#,
+            )
+            .bind(new_printer.id)
+            .bind(&new_printer.name)
+            .bind(&new_printer.model)
+            .bind(new_printer.brand)
+            .bind(new_printer.toner)
+            .bind(new_printer.drum)
+            .bind(&new_printer.owner_department)
+            .bind(new_printer.acquisition_type)
+            .bind(new_printer.lease_end_date)
+            .bind(new_printer.connection_type)
+            .bind(actor.id())
+            .execute(&state.db)
+            .await
+            {
+                Ok(_) => {
+                    info!("Printer {} cloned! ID: {}", &id, &new_printer.id);
+                    (
+                        StatusCode::CREATED,
+                        Ok((
+                            location(format!("/api/v1/printer/{}", new_printer.id)),
+                            Json(new_printer),
+                        )),
+                    )
+                }
+                // Another request took the name since the check above.
+                Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                    error!("Printer '{}' already exists: {}", &new_printer.name, e);
+                    (
+                        StatusCode::CONFLICT,
+                        Err(Json(ApiError::duplicate_name(conflict_message(
+                            state.config.printer_name_scope,
+                        )))),
+                    )
+                }
+                Err(e) => {
+                    error!("Error cloning printer: {}", e);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Err(Json("Error cloning printer.".into())),
+                    )
+                }
+            }
+        }
+        Err(e) => {
+            error!("Error checking for duplicate printer name: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error checking for duplicated printer name.".into())),
+            )
+        }
+    }
+}
+
+pub async fn delete_printer(
+    actor: Actor,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<DeleteRequest>,
+) -> Response {
+    // Invalid id
+    if let Err(message) = validations::check_ids([request.id], state.config.require_uuid_v4) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Json(message)).into_response();
+    }
+
+    match queries::exists(&state.db, Table::Printers, request.id).await {
+        Ok(true) => {
+            match sqlx::query(&audit::delete_statement("printers"))
+                .bind(request.id)
+                .bind(actor.id())
+                .execute(&state.db)
+                .await
+            {
+                Ok(_) => {
+                    info!("Printer deleted! ID: {}", &request.id);
+                    deleted(&state.config, "Printer deleted!")
+                }
+                Err(e) => {
+                    error!("Error deleting printer: {}", e);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json("Error deleting printer."),
+                    )
+                        .into_response()
+                }
+            }
+        }
+        // Already deleted, as when a client retries a delete that succeeded
+        Ok(false) if state.config.idempotent_deletes => {
+            info!("Printer {} already absent.", &request.id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => {
+            error!("Printer ID not found.");
+            (StatusCode::NOT_FOUND, Json("Printer ID not found")).into_response()
+        }
+        Err(e) => {
+            error!("Error deleting printer: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error deleting printer."),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Creates printers in bulk from a `text/csv` body with the columns
+/// `name,model,brand,toner,drum`, where brand, toner and drum are given by name.
+///
+/// Every row runs inside its own savepoint, so an invalid or malformed row is
+/// reported and skipped while the remaining rows are still imported.
+pub async fn import_printers_csv(
+    actor: Actor,
+    State(state): State<Arc<AppState>>,
+    Extension(deadline): Extension<Deadline>,
+    headers: HeaderMap,
+    body: String,
+) -> impl IntoResponse {
+    let is_csv = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("text/csv"));
+
+    if !is_csv {
+        error!("Printer import requires a CSV body.");
+        return Err((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Json("Request body must be sent with `Content-Type: text/csv`."),
+        ));
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(body.as_bytes());
+
+    let columns = match reader.headers() {
+        Ok(columns) => columns.clone(),
+        Err(e) => {
+            error!("Invalid CSV header: {}", e);
+            return Err((StatusCode::BAD_REQUEST, Json("Invalid CSV header.")));
+        }
+    };
+
+    let mut tx = match state.begin(deadline).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!("Error starting printer import: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error importing printers."),
+            ));
+        }
+    };
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let result = match record {
+            Ok(record) => {
+                let line = record.position().map_or(0, |position| position.line());
+                match record.deserialize::<PrinterCsvRow>(Some(&columns)) {
+                    Ok(row) => {
+                        match import_printer_row(&mut tx, &state.config, &row, actor.id()).await {
+                            Ok((id, warnings)) => ImportRowResult::created(line, id, warnings),
+                            Err(message) => ImportRowResult::failed(line, message),
+                        }
+                    }
+                    Err(e) => ImportRowResult::failed(line, format!("Malformed row: {e}")),
+                }
+            }
+            Err(e) => {
+                let line = e.position().map_or(0, |position| position.line());
+                ImportRowResult::failed(line, format!("Malformed row: {e}"))
+            }
+        };
+        rows.push(result);
+    }
+
+    if let Err(e) = tx.commit().await {
+        error!("Error committing printer import: {}", e);
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json("Error importing printers."),
+        ));
+    }
+
+    let created = rows
+        .iter()
+        .filter(|row| matches!(row.status, ImportRowStatus::Created))
+        .count();
+    let failed = rows.len() - created;
+
+    info!(
+        "Printers imported! Created: {}, failed: {}",
+        created, failed
+    );
+    Ok(Json(ImportReport {
+        created,
+        failed,
+        rows,
+    }))
+}
+
+async fn import_printer_row(
+    tx: &mut Transaction<'_, Postgres>,
+    config: &Config,
+    row: &PrinterCsvRow,
+    actor: Option<&str>,
+) -> Result<(Uuid, Vec<String>), String> {
+    // Name is empty
+    if row.name.is_empty() {
+        return Err(String::from("Printer name cannot be empty."));
+    }
+
+    // Name too short
+    if row.name.chars().count() < NAME_MIN_LENGTH {
+        return Err(String::from("Printer name is too short."));
+    }
+
+    // Name too long
+    if row.name.chars().count() > NAME_MAX_LENGTH {
+        return Err(String::from("Printer name is too long."));
+    }
+
+    // Name has characters that are not allowed
+    validations::check_name_pattern(config, Named::Printer, &row.name)
+        .map_err(|e| String::from(e.message()))?;
+
+    // Model is empty
+    if row.model.is_empty() {
+        return Err(String::from("Printer model cannot be empty."));
+    }
+
+    // Model too short
+    if row.model.chars().count() < config.printer_model_min_length {
+        return Err(String::from("Printer model is too short."));
+    }
+
+    // Model too long
+    if row.model.chars().count() > config.printer_model_max_length {
+        return Err(String::from("Printer model is too long."));
+    }
+
+    // Department not allowed
+    validate_department(config, row.owner_department.as_deref()).map_err(String::from)?;
+
+    // Lease end date missing or not allowed
+    validate_acquisition(row.acquisition_type.unwrap_or_default(), row.lease_end_date)
+        .map_err(String::from)?;
+
+    let mut savepoint = tx.begin().await.map_err(|e| {
+        error!("Error starting import savepoint: {}", e);
+        String::from("Error importing row.")
+    })?;
+
+    let lookup_error = |e: sqlx::Error| {
+        error!("Error resolving import row: {}", e);
+        String::from("Error importing row.")
+    };
+
+    let brand: Option<(Uuid,)> = sqlx::query_as(r#"SELECT id FROM brands WHERE name = $1;"#)
+        .bind(&row.brand)
+        .fetch_optional(&mut *savepoint)
+        .await
+        .map_err(lookup_error)?;
+    let Some((brand,)) = brand else {
+        return Err(format!("Brand '{}' not found.", row.brand));
+    };
+
+    // Toner names repeat across brands and colors; the row's brand wins.
+    let toner: Option<(Uuid,)> = sqlx::query_as(
+        r#"SELECT id FROM toners WHERE name = $1 ORDER BY brand = $2 IS TRUE DESC, id LIMIT 1;"#,
+    )
+    .bind(&row.toner)
+    .bind(brand)
+    .fetch_optional(&mut *savepoint)
+    .await
+    .map_err(lookup_error)?;
+    let Some((toner,)) = toner else {
+        return Err(format!("Toner '{}' not found.", row.toner));
+    };
+
+    let drum: Option<(Uuid,)> = sqlx::query_as(r#"SELECT id FROM drums WHERE name = $1;"#)
+        .bind(&row.drum)
+        .fetch_optional(&mut *savepoint)
+        .await
+        .map_err(lookup_error)?;
+    let Some((drum,)) = drum else {
+        return Err(format!("Drum '{}' not found.", row.drum));
+    };
+
+    // Check duplicate
+    if name_taken(
+        &mut *savepoint,
+        config.printer_name_scope,
+        &row.name,
+        brand,
+        None,
+    )
+    .await
+    .map_err(lookup_error)?
+    {
+        return Err(format!(
+            "Printer '{}': {}",
+            row.name,
+            conflict_message(config.printer_name_scope)
+        ));
+    }
+
+    // Name same as model
+    let mut warnings: Vec<String> = check_name_model(config, &row.name, &row.model)
+        .map_err(|(_, message)| String::from(message))?
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+    // Toner brand mismatch
+    warnings.extend(
+        check_toner_brand(
+            &mut *savepoint,
+            config.soft_mode(config.toner_brand_validation),
+            brand,
+            toner,
+        )
+        .await
+        .map_err(|(_, message)| String::from(message))?
+        .map(String::from),
+    );
+
+    let new_printer = Printer {
+        acquisition_type: row.acquisition_type.unwrap_or_default(),
+        lease_end_date: row.lease_end_date,
+        connection_type: row.connection_type,
+        ..Printer::new(
+            &row.name,
+            &row.model,
+            brand,
+            toner,
+            drum,
+            row.owner_department.as_deref(),
+            None,
+        )
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO printers
+            (id, name, model, brand, toner, drum, owner_department, acquisition_type,
+            lease_end_date, connection_type, created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $11);
+        "#,
+    )
+    .bind(new_printer.id)
+    .bind(&new_printer.name)
+    .bind(&new_printer.model)
+    .bind(new_printer.brand)
+    .bind(new_printer.toner)
+    .bind(new_printer.drum)
+    .bind(&new_printer.owner_department)
+    .bind(new_printer.acquisition_type)
+    .bind(new_printer.lease_end_date)
+    .bind(new_printer.connection_type)
+    .bind(actor)
+    .execute(&mut *savepoint)
+    .await
+    .map_err(|e| match e {
+        // Another request took the name since the check above.
+        sqlx::Error::Database(e) if e.is_unique_violation() => {
+            error!("Printer '{}' already exists: {}", &new_printer.name, e);
+            String::from(conflict_message(config.printer_name_scope))
+        }
+        e => {
+            error!("Error importing printer: {}", e);
+            String::from("Error creating printer.")
+        }
+    })?;
+
+    savepoint.commit().await.map_err(|e| {
+        error!("Error releasing import savepoint: {}", e);
+        String::from("Error importing row.")
+    })?;
+
+    Ok((new_printer.id, warnings))
+}
+
+/// Whether another printer already uses `name` within the uniqueness scope.
+/// `exclude` skips the printer being updated.
+async fn name_taken<'e>(
+    executor: impl PgExecutor<'e>,
+    scope: PrinterNameScope,
+    name: &str,
+    brand: Uuid,
+    exclude: Option<Uuid>,
+) -> Result<bool, sqlx::Error> {
+    let brand = (scope == PrinterNameScope::Brand).then_some(brand);
+
+    let duplicate: Option<(Uuid,)> = sqlx::query_as(
+        r#"
+        SELECT id FROM printers
+        WHERE name = $1
+            AND ($2::uuid IS NULL OR brand = $2)
+            AND ($3::uuid IS NULL OR id != $3);
+        "#,
+    )
+    .bind(name)
+    .bind(brand)
+    .bind(exclude)
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(duplicate.is_some())
+}
+
+fn conflict_message(scope: PrinterNameScope) -> &'static str {
+    match scope {
+        PrinterNameScope::Global => "Printer already exists.",
+        PrinterNameScope::Brand => "Printer already exists for this brand.",
+    }
+}
+
+/// Appended to the name of a cloned printer when no name is given.
+const COPY_SUFFIX: &str = " (copy)";
+
+/// Width of the `printers.owner_department` column.
+const DEPARTMENT_MAX_LENGTH: usize = 50;
+
+/// Days ahead `GET /printers/expiring-leases` looks when `within_days` is
+/// omitted.
+const DEFAULT_LEASE_WINDOW_DAYS: i32 = 30;
+
+/// Width of the `printers.decommission_reason` column.
+const DECOMMISSION_REASON_MAX_LENGTH: usize = 200;
+
+/// Distinct ids `POST /printers/bulk-status` accepts in one request.
+const BULK_STATUS_MAX_IDS: usize = 1000;
+
+/// Checks an optional owner department against the column width and, when
+/// `PRINTER_DEPARTMENTS` is set, against the configured list.
+fn validate_department(config: &Config, department: Option<&str>) -> Result<(), &'static str> {
+    let Some(department) = department else {
+        return Ok(());
+    };
+
+    // Department is empty
+    if department.trim().is_empty() {
+        return Err("Printer department cannot be empty.");
+    }
+
+    // Department too long
+    if department.chars().count() > DEPARTMENT_MAX_LENGTH {
+        return Err("Printer department is too long.");
+    }
+
+    // Department not in the configured list
+    if !config.printer_departments.is_empty()
+        && !config.printer_departments.iter().any(|d| d == department)
+    {
+        return Err("Printer department is not allowed.");
+    }
+
+    Ok(())
+}
+
+/// Leased printers need a lease end date, and only they may have one.
+fn validate_acquisition(
+    acquisition_type: AcquisitionType,
+    lease_end_date: Option<NaiveDate>,
+) -> Result<(), &'static str> {
+    match (acquisition_type, lease_end_date) {
+        // Lease end missing
+        (AcquisitionType::Leased, None) => Err("Leased printers need a lease end date."),
+        // Lease end on an owned printer
+        (AcquisitionType::Owned, Some(_)) => Err("Only leased printers have a lease end date."),
+        _ => Ok(()),
+    }
+}
+
+/// Applies the toner/brand compatibility rule in `mode`: when the toner belongs
+/// to another brand than the printer, `warn` mode yields a warning and
+/// `strict` mode rejects the printer. Toners without a brand are not checked.
+async fn check_toner_brand<'e>(
+    executor: impl PgExecutor<'e>,
+    mode: ValidationMode,
+    brand: Uuid,
+    toner: Uuid,
+) -> Result<Option<&'static str>, (StatusCode, &'static str)> {
+    if mode == ValidationMode::Off {
+        return Ok(None);
+    }
+
+    let toner_brand: Option<(Option<Uuid>,)> =
+        sqlx::query_as(r#"SELECT brand FROM toners WHERE id = $1;"#)
+            .bind(toner)
+            .fetch_optional(executor)
+            .await
+            .map_err(|e| {
+                error!("Error fetching toner brand: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Error checking toner brand.",
+                )
+            })?;
+
+    match toner_brand {
+        Some((toner_brand,)) if toner_brand_mismatch(brand, toner_brand) => {
+            validations::soft_violation(mode, TONER_BRAND_MISMATCH)
+        }
+        _ => Ok(None),
+    }
+}
+
+pub const TONER_BRAND_MISMATCH: &str = "Toner brand does not match the printer brand.";
+
+/// Whether a toner of `toner_brand` does not belong with a printer of
+/// `brand`. Toners without a brand fit any printer.
+pub fn toner_brand_mismatch(brand: Uuid, toner_brand: Option<Uuid>) -> bool {
+    toner_brand.is_some_and(|toner_brand| toner_brand != brand)
+}
+
+/// A printer named after its model usually means the name was left as a
+/// placeholder, so it is a soft rule.
+fn check_name_model(
+    config: &Config,
+    name: &str,
+    model: &str,
+) -> Result<Option<&'static str>, (StatusCode, &'static str)> {
+    if !name.trim().eq_ignore_ascii_case(model.trim()) {
+        return Ok(None);
+    }
+    validations::soft_violation(
+        config.soft_mode(ValidationMode::Warn),
+        "Printer name is the same as its model.",
+    )
+}
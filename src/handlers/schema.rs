@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use schemars::{
+    schema::{RootSchema, Schema, SchemaObject},
+    schema_for,
+};
+use serde_json::Value;
+use tracing::{error, info};
+
+use crate::{
+    config::Config,
+    extractors::{Json, Path},
+    models::{
+        brand::CreateBrandRequest,
+        database::AppState,
+        printer::CreatePrinterRequest,
+        supplies::{drum::CreateDrumRequest, toner::CreateTonerRequest},
+    },
+};
+
+/// JSON Schema of the create request of `kind`, with the limits taken from
+/// the configuration filled in, for clients that build their forms from it.
+pub async fn show_schema(
+    Path(kind): Path<String>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let config = &state.config;
+    let schema = match kind.as_str() {
+        "brand" => {
+            let mut schema = schema_for!(CreateBrandRequest);
+            set_name_pattern(&mut schema, config);
+            schema
+        }
+        "printer" => {
+            let mut schema = schema_for!(CreatePrinterRequest);
+            set_name_pattern(&mut schema, config);
+            if let Some(model) = property(&mut schema, "model") {
+                let string = model.string();
+                string.min_length = u32::try_from(config.printer_model_min_length).ok();
+                string.max_length = u32::try_from(config.printer_model_max_length).ok();
+            }
+            if !config.printer_departments.is_empty() {
+                if let Some(department) = property(&mut schema, "owner_department") {
+                    department.enum_values = Some(
+                        config
+                            .printer_departments
+                            .iter()
+                            .cloned()
+                            .map(Value::String)
+                            .chain([Value::Null])
+                            .collect(),
+                    );
+                }
+            }
+            schema
+        }
+        "toner" => schema_for!(CreateTonerRequest),
+        "drum" => schema_for!(CreateDrumRequest),
+        _ => {
+            error!("No schema for {kind}.");
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json("Schema not found. Use brand, printer, toner or drum."),
+            ));
+        }
+    };
+
+    info!("Schema for {kind} retrieved");
+    Ok(Json(schema))
+}
+
+/// Names must also match `NAME_PATTERN` when it is set.
+fn set_name_pattern(schema: &mut RootSchema, config: &Config) {
+    if let (Some(pattern), Some(name)) = (&config.name_pattern, property(schema, "name")) {
+        name.string().pattern = Some(pattern.regex.as_str().to_string());
+    }
+}
+
+fn property<'a>(schema: &'a mut RootSchema, name: &str) -> Option<&'a mut SchemaObject> {
+    match schema.schema.object().properties.get_mut(name)? {
+        Schema::Object(property) => Some(property),
+        Schema::Bool(_) => None,
+    }
+}
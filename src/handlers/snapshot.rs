@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{OriginalUri, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension,
+};
+use tracing::{error, info};
+
+use crate::{
+    extractors::Json,
+    jobs::{self, JobError},
+    middleware::deadline::Deadline,
+    models::{
+        database::AppState,
+        pagination::{Page, PaginationParams},
+        snapshot::{Snapshot, SnapshotFilter},
+    },
+};
+
+/// Snapshots of the catalog counts, oldest first, for charting trends.
+/// `from` and `to` keep only those taken within the given timestamps.
+pub async fn show_snapshots(
+    Query(pagination): Query<PaginationParams>,
+    Query(filter): Query<SnapshotFilter>,
+    OriginalUri(uri): OriginalUri,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let (limit, offset) = match pagination.resolve(&state.config) {
+        Ok(window) => window,
+        Err(message) => {
+            error!("Invalid pagination: {message}");
+            return Err((StatusCode::BAD_REQUEST, Json(message)));
+        }
+    };
+
+    // Range is reversed
+    if let (Some(from), Some(to)) = (filter.from, filter.to) {
+        if from > to {
+            error!("Invalid snapshot range: {from} is after {to}.");
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json("`from` cannot be after `to`."),
+            ));
+        }
+    }
+
+    let total: Result<(i64,), sqlx::Error> = sqlx::query_as(
+        r#"
+        SELECT COUNT(*) FROM snapshots
+        WHERE ($1::timestamptz IS NULL OR taken_at >= $1)
+            AND ($2::timestamptz IS NULL OR taken_at <= $2);
+        "#,
+    )
+    .bind(filter.from)
+    .bind(filter.to)
+    .fetch_one(&state.db)
+    .await;
+    let snapshots: Result<Vec<Snapshot>, sqlx::Error> = sqlx::query_as(
+        r#"
+        SELECT * FROM snapshots
+        WHERE ($1::timestamptz IS NULL OR taken_at >= $1)
+            AND ($2::timestamptz IS NULL OR taken_at <= $2)
+        ORDER BY taken_at ASC, id ASC
+        LIMIT $3 OFFSET $4;
+        "#,
+    )
+    .bind(filter.from)
+    .bind(filter.to)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await;
+
+    match (snapshots, total) {
+        (Ok(snapshots), Ok((total,))) => {
+            info!("Snapshots listed: {} of {}", snapshots.len(), total);
+            Ok(Json(Page::new(snapshots, total, limit, offset, &uri)))
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            error!("Error listing snapshots: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error listing snapshots."),
+            ))
+        }
+    }
+}
+
+/// Takes a snapshot right away, outside the `SNAPSHOT_INTERVAL_SECONDS`
+/// schedule. Answers `409` while another snapshot is being taken.
+pub async fn create_snapshot(
+    Extension(deadline): Extension<Deadline>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let mut tx = match state.begin(deadline).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!("Error taking snapshot: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error taking snapshot.")),
+            );
+        }
+    };
+
+    let snapshot = match jobs::take_snapshot(&mut tx).await {
+        Ok(snapshot) => snapshot,
+        Err(JobError::Busy) => {
+            error!("A snapshot is already being taken.");
+            return (
+                StatusCode::CONFLICT,
+                Err(Json("A snapshot is already being taken.")),
+            );
+        }
+        Err(JobError::Database(e)) => {
+            error!("Error taking snapshot: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error taking snapshot.")),
+            );
+        }
+    };
+
+    match tx.commit().await {
+        Ok(_) => {
+            info!("Snapshot {} taken on demand", snapshot.id);
+            (StatusCode::CREATED, Ok(Json(snapshot)))
+        }
+        Err(e) => {
+            error!("Error taking snapshot: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error taking snapshot.")),
+            )
+        }
+    }
+}
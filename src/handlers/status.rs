@@ -1,14 +1,18 @@
 use std::{env, sync::Arc};
 
-use axum::{extract::State, Json};
+use axum::{extract::State, http::StatusCode, Json};
 use chrono::Utc;
-use tracing::info;
+use sqlx::migrate::Migrator;
+use tracing::{error, info};
 
 use crate::models::{
     database::AppState,
-    status::{Database, Dependencies, Status},
+    status::{Database, Dependencies, Readiness, ReadinessChecks, Requests, Status},
 };
 
+/// Migrations bundled at build time, used to tell whether the schema is current.
+static MIGRATOR: Migrator = sqlx::migrate!();
+
 pub async fn show_status(State(state): State<Arc<AppState>>) -> Json<Status> {
     let db_version: (String,) = sqlx::query_as(r#"SHOW server_version;"#)
         .fetch_one(&state.db)
@@ -39,5 +43,67 @@ pub async fn show_status(State(state): State<Arc<AppState>>) -> Json<Status> {
     Json(Status {
         updated_at: Utc::now(),
         dependencies: Dependencies { database },
+        requests: Requests {
+            in_flight: state.in_flight.count(),
+            limit: state.in_flight.limit(),
+        },
     })
 }
+
+/// Liveness probe: answers as long as the process is able to serve HTTP,
+/// without touching any dependency.
+pub async fn show_health() -> Json<&'static str> {
+    Json("OK")
+}
+
+/// Readiness probe: `200` only when the database is reachable and every
+/// migration bundled with this build has been applied, `503` otherwise.
+pub async fn show_ready(State(state): State<Arc<AppState>>) -> (StatusCode, Json<Readiness>) {
+    let applied: Result<Vec<(i64,)>, sqlx::Error> =
+        sqlx::query_as(r#"SELECT version FROM _sqlx_migrations WHERE success;"#)
+            .fetch_all(&state.db)
+            .await;
+
+    let (database, pending_migrations) = match applied {
+        Ok(applied) => {
+            let applied: Vec<i64> = applied.into_iter().map(|(version,)| version).collect();
+            let pending = MIGRATOR
+                .iter()
+                .filter(|migration| migration.migration_type.is_up_migration())
+                .map(|migration| migration.version)
+                .filter(|version| !applied.contains(version))
+                .collect();
+            (true, pending)
+        }
+        // The table is missing when no migration ever ran.
+        Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some("42P01") => (
+            true,
+            MIGRATOR.iter().map(|migration| migration.version).collect(),
+        ),
+        Err(e) => {
+            error!("Readiness check failed: {e}");
+            (false, Vec::new())
+        }
+    };
+
+    let migrations = database && pending_migrations.is_empty();
+    let ready = database && migrations;
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    info!("Readiness queried: {ready}");
+    (
+        status,
+        Json(Readiness {
+            ready,
+            checks: ReadinessChecks {
+                database,
+                migrations,
+            },
+            pending_migrations,
+        }),
+    )
+}
@@ -1,25 +1,33 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, State},
+    extract::{OriginalUri, Query, State},
     http::StatusCode,
-    response::IntoResponse,
-    Json,
+    response::{IntoResponse, Response},
 };
 use tracing::{error, info};
 use uuid::Uuid;
 
-use crate::models::{
-    database::AppState,
-    supplies::drum::{CreateDrumRequest, Drum, UpdateDrumRequest},
-    DeleteRequest,
+use crate::{
+    audit,
+    extractors::{Actor, Json, Path},
+    handlers::{deleted, location},
+    models::{
+        audit::Audited,
+        database::AppState,
+        error::ApiError,
+        pagination::{Page, PaginationParams},
+        supplies::drum::{CreateDrumRequest, Drum, UpdateDrumRequest},
+        DeleteRequest,
+    },
+    queries::{self, Table},
+    validations::{self, NAME_MAX_LENGTH, NAME_MIN_LENGTH},
 };
 
 pub async fn count_drums(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let drum_count: Result<(i32,), sqlx::Error> =
-        sqlx::query_as(r#"SELECT COUNT(*)::int FROM drums;"#)
-            .fetch_one(&state.db)
-            .await;
+    let drum_count: Result<(i64,), sqlx::Error> = sqlx::query_as(queries::COUNT_DRUMS)
+        .fetch_one(&state.db)
+        .await;
 
     match drum_count {
         Ok((count,)) => {
@@ -38,13 +46,19 @@ pub async fn count_drums(State(state): State<Arc<AppState>>) -> impl IntoRespons
 
 pub async fn search_drum(
     Path(id): Path<Uuid>,
+    actor: Actor,
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
-    match sqlx::query_as::<_, Drum>(r#"SELECT * FROM drums WHERE id = $1;"#)
+    let drum = sqlx::query(queries::DRUM_BY_ID)
         .bind(id)
         .fetch_optional(&state.db)
         .await
-    {
+        .and_then(|row| {
+            row.map(|row| Audited::<Drum>::from_row(&row, actor.is_admin()))
+                .transpose()
+        });
+
+    match drum {
         Ok(Some(drum)) => {
             info!("Drum found: {id}");
             (StatusCode::OK, Json(Some(drum)))
@@ -60,16 +74,33 @@ pub async fn search_drum(
     }
 }
 
-pub async fn show_drums(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let drums: Result<Vec<Drum>, sqlx::Error> = sqlx::query_as(r#"SELECT * FROM drums;"#)
+pub async fn show_drums(
+    Query(pagination): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let (limit, offset) = match pagination.resolve(&state.config) {
+        Ok(window) => window,
+        Err(message) => {
+            error!("Invalid pagination: {message}");
+            return Err((StatusCode::BAD_REQUEST, Json(message)));
+        }
+    };
+
+    let total: Result<(i64,), sqlx::Error> = sqlx::query_as(queries::COUNT_DRUMS)
+        .fetch_one(&state.db)
+        .await;
+    let drums: Result<Vec<Drum>, sqlx::Error> = sqlx::query_as(queries::LIST_DRUMS)
+        .bind(limit)
+        .bind(offset)
         .fetch_all(&state.db)
         .await;
-    match drums {
-        Ok(drums) => {
+    match (drums, total) {
+        (Ok(drums), Ok((total,))) => {
             info!("Drums listed successfully");
-            Ok(Json(drums))
+            Ok(Json(Page::new(drums, total, limit, offset, &uri)))
         }
-        Err(e) => {
+        (Err(e), _) | (_, Err(e)) => {
             error!("Error listing drums: {e}");
             Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -79,7 +110,35 @@ pub async fn show_drums(State(state): State<Arc<AppState>>) -> impl IntoResponse
     }
 }
 
+/// Drums that no printer refers to, candidates for catalog cleanup.
+pub async fn show_unused_drums(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match sqlx::query_as::<_, Drum>(
+        r#"
+        SELECT drums.* FROM drums
+        LEFT JOIN printers ON printers.drum = drums.id
+        WHERE printers.id IS NULL
+        ORDER BY drums.name;
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(drums) => {
+            info!("Unused drums listed: {}", drums.len());
+            Ok(Json(drums))
+        }
+        Err(e) => {
+            error!("Error listing unused drums: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error listing unused drums."),
+            ))
+        }
+    }
+}
+
 pub async fn create_drum(
+    actor: Actor,
     State(state): State<Arc<AppState>>,
     Json(request): Json<CreateDrumRequest>,
 ) -> impl IntoResponse {
@@ -93,7 +152,10 @@ pub async fn create_drum(
     {
         Ok(Some(_)) => {
             error!("Drum '{}' already exists.", &new_drum.name);
-            (StatusCode::CONFLICT, Err(Json("Drum already exists.")))
+            (
+                StatusCode::CONFLICT,
+                Err(Json(ApiError::duplicate_name("Drum already exists."))),
+            )
         }
         Ok(None) => {
             // Name is empty
@@ -101,92 +163,124 @@ pub async fn create_drum(
                 error!("Drum name cannot be empty.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Drum name cannot be empty.")),
+                    Err(Json(ApiError::name_empty("Drum name cannot be empty."))),
                 );
             }
 
             // Name too short
-            if new_drum.name.len() < 4 {
+            if new_drum.name.chars().count() < NAME_MIN_LENGTH {
                 error!("Drum name is too short.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Drum name is too short.")),
+                    Err(Json(ApiError::name_too_short(
+                        "Drum name is too short.",
+                        NAME_MIN_LENGTH,
+                        new_drum.name.chars().count(),
+                    ))),
                 );
             }
 
             // Name too long
-            if new_drum.name.len() > 20 {
+            if new_drum.name.chars().count() > NAME_MAX_LENGTH {
                 error!("Drum name is too long.");
-                return (StatusCode::BAD_REQUEST, Err(Json("Drum name is too long.")));
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Err(Json(ApiError::name_too_long(
+                        "Drum name is too long.",
+                        NAME_MAX_LENGTH,
+                        new_drum.name.chars().count(),
+                    ))),
+                );
             }
 
             match sqlx::query(
                 r#"
-                INSERT INTO drums (id, name)
-                VALUES ($1, $2)
+                INSERT INTO drums (id, name, created_by, updated_by)
+                VALUES ($1, $2, $3, $3)
                 "#,
             )
             .bind(new_drum.id)
             .bind(&new_drum.name)
+            .bind(actor.id())
             .execute(&state.db)
             .await
             {
                 Ok(_) => {
                     info!("Drum created! ID: {}", &new_drum.id);
-                    (StatusCode::CREATED, Ok(Json(new_drum.id)))
+                    (
+                        StatusCode::CREATED,
+                        Ok((
+                            location(format!("/api/v1/supplies/drum/{}", new_drum.id)),
+                            Json(new_drum.id),
+                        )),
+                    )
                 }
                 Err(e) => {
                     error!("Error creating drum: {}", e);
                     (
                         StatusCode::INTERNAL_SERVER_ERROR,
-                        Err(Json("Error creating drum.")),
+                        Err(Json("Error creating drum.".into())),
                     )
                 }
             }
         }
         Err(_) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Err(Json("Error creating drum.")),
+            Err(Json("Error creating drum.".into())),
         ),
     }
 }
 
 pub async fn update_drum(
+    actor: Actor,
     State(state): State<Arc<AppState>>,
     Json(request): Json<UpdateDrumRequest>,
 ) -> impl IntoResponse {
     let drum_id = request.id;
     let new_name = request.name;
 
+    // Invalid id
+    if let Err(message) = validations::check_ids([drum_id], state.config.require_uuid_v4) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+    }
+
     // ID not found
-    match sqlx::query(r#"SELECT id FROM drums WHERE id = $1;"#)
-        .bind(drum_id)
-        .fetch_optional(&state.db)
-        .await
-    {
-        Ok(Some(_)) => {
+    match queries::exists(&state.db, Table::Drums, drum_id).await {
+        Ok(true) => {
             // Name is empty
             if new_name.is_empty() {
                 error!("Drum name cannot be empty.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Drum name cannot be empty.")),
+                    Err(Json(ApiError::name_empty("Drum name cannot be empty."))),
                 );
             }
 
             // Name too short
-            if new_name.len() < 4 {
+            if new_name.chars().count() < NAME_MIN_LENGTH {
                 error!("Drum name is too short.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Drum name is too short.")),
+                    Err(Json(ApiError::name_too_short(
+                        "Drum name is too short.",
+                        NAME_MIN_LENGTH,
+                        new_name.chars().count(),
+                    ))),
                 );
             }
 
             // Name too long
-            if new_name.len() > 20 {
+            if new_name.chars().count() > NAME_MAX_LENGTH {
                 error!("Drum name is too long.");
-                return (StatusCode::BAD_REQUEST, Err(Json("Drum name is too long.")));
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Err(Json(ApiError::name_too_long(
+                        "Drum name is too long.",
+                        NAME_MAX_LENGTH,
+                        new_name.chars().count(),
+                    ))),
+                );
             }
 
             // Check duplicate
@@ -198,14 +292,20 @@ pub async fn update_drum(
             {
                 Ok(Some(_)) => {
                     error!("Drum name already exists.");
-                    (StatusCode::CONFLICT, Err(Json("Drum already exists.")))
+                    (
+                        StatusCode::CONFLICT,
+                        Err(Json(ApiError::duplicate_name("Drum already exists."))),
+                    )
                 }
                 Ok(None) => {
-                    match sqlx::query(r#"UPDATE drums SET name = $1 WHERE id = $2;"#)
-                        .bind(&new_name)
-                        .bind(drum_id)
-                        .execute(&state.db)
-                        .await
+                    match sqlx::query(
+                        r#"UPDATE drums SET name = $1, updated_by = $3 WHERE id = $2;"#,
+                    )
+                    .bind(&new_name)
+                    .bind(drum_id)
+                    .bind(actor.id())
+                    .execute(&state.db)
+                    .await
                     {
                         Ok(_) => {
                             info!("Drum updated! ID: {}", &drum_id);
@@ -215,7 +315,7 @@ pub async fn update_drum(
                             error!("Error updating drum: {}", e);
                             (
                                 StatusCode::INTERNAL_SERVER_ERROR,
-                                Err(Json("Error updating drum.")),
+                                Err(Json("Error updating drum.".into())),
                             )
                         }
                     }
@@ -224,63 +324,77 @@ pub async fn update_drum(
                     error!("Error checking for duplicate drum name: {}", e);
                     (
                         StatusCode::INTERNAL_SERVER_ERROR,
-                        Err(Json("Error checking for duplicated drum name.")),
+                        Err(Json("Error checking for duplicated drum name.".into())),
                     )
                 }
             }
         }
-        Ok(None) => {
+        Ok(false) => {
             error!("Drum ID not found.");
-            (StatusCode::NOT_FOUND, Err(Json("Drum ID not found.")))
+            (
+                StatusCode::NOT_FOUND,
+                Err(Json("Drum ID not found.".into())),
+            )
         }
         Err(e) => {
             error!("Error fetching drum by ID: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Err(Json("Error fetching drum by ID")),
+                Err(Json("Error fetching drum by ID".into())),
             )
         }
     }
 }
 
 pub async fn delete_drum(
+    actor: Actor,
     State(state): State<Arc<AppState>>,
     Json(request): Json<DeleteRequest>,
-) -> impl IntoResponse {
-    match sqlx::query(r#"SELECT id FROM drums WHERE id = $1;"#)
-        .bind(request.id)
-        .fetch_optional(&state.db)
-        .await
-    {
-        Ok(Some(_)) => {
-            match sqlx::query(r#"DELETE FROM drums WHERE id = $1;"#)
+) -> Response {
+    // Invalid id
+    if let Err(message) = validations::check_ids([request.id], state.config.require_uuid_v4) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Json(message)).into_response();
+    }
+
+    match queries::exists(&state.db, Table::Drums, request.id).await {
+        Ok(true) => {
+            match sqlx::query(&audit::delete_statement("drums"))
                 .bind(request.id)
+                .bind(actor.id())
                 .execute(&state.db)
                 .await
             {
                 Ok(_) => {
                     info!("Drum deleted! ID: {}", &request.id);
-                    (StatusCode::OK, Ok(Json("Drum deleted!")))
+                    deleted(&state.config, "Drum deleted!")
                 }
                 Err(e) => {
                     error!("Error deleting drum: {}", e);
                     (
                         StatusCode::INTERNAL_SERVER_ERROR,
-                        Ok(Json("Error deleting drum.")),
+                        Json("Error deleting drum."),
                     )
+                        .into_response()
                 }
             }
         }
-        Ok(None) => {
+        // Already deleted, as when a client retries a delete that succeeded
+        Ok(false) if state.config.idempotent_deletes => {
+            info!("Drum {} already absent.", &request.id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => {
             error!("Drum ID not found.");
-            (StatusCode::NOT_FOUND, Err(Json("Drum ID not found")))
+            (StatusCode::NOT_FOUND, Json("Drum ID not found")).into_response()
         }
         Err(e) => {
             error!("Error deleting drum: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Err(Json("Error deleting drum.")),
+                Json("Error deleting drum."),
             )
+                .into_response()
         }
     }
 }
@@ -1,25 +1,38 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, State},
+    extract::{OriginalUri, Query, State},
     http::StatusCode,
-    response::IntoResponse,
-    Json,
+    response::{IntoResponse, Response},
 };
+use sqlx::{PgConnection, PgExecutor};
 use tracing::{error, info};
 use uuid::Uuid;
 
-use crate::models::{
-    database::AppState,
-    supplies::toner::{CreateTonerRequest, Toner, UpdateTonerRequest},
-    DeleteRequest,
+use crate::{
+    audit,
+    config::ValidationMode,
+    extractors::{Actor, Json, Path},
+    handlers::{deleted, location},
+    models::{
+        audit::Audited,
+        database::AppState,
+        error::ApiError,
+        pagination::{Page, PaginationParams},
+        supplies::toner::{
+            AlternativeRequest, CreateTonerRequest, DuplicateToners, ReorderSuggestion, Toner,
+            UpdateTonerRequest,
+        },
+        DeleteRequest, MutationResponse,
+    },
+    queries::{self, Table},
+    validations::{self, NAME_MAX_LENGTH, NAME_MIN_LENGTH},
 };
 
 pub async fn count_toners(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let toner_count: Result<(i32,), sqlx::Error> =
-        sqlx::query_as(r#"SELECT COUNT(*)::int FROM toners;"#)
-            .fetch_one(&state.db)
-            .await;
+    let toner_count: Result<(i64,), sqlx::Error> = sqlx::query_as(queries::COUNT_TONERS)
+        .fetch_one(&state.db)
+        .await;
     match toner_count {
         Ok((count,)) => {
             info!("Successfully retrieved toner count: {}", count);
@@ -37,13 +50,19 @@ pub async fn count_toners(State(state): State<Arc<AppState>>) -> impl IntoRespon
 
 pub async fn search_toner(
     Path(id): Path<Uuid>,
+    actor: Actor,
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
-    match sqlx::query_as::<_, Toner>(r#"SELECT * FROM toners WHERE id = $1;"#)
+    let toner = sqlx::query(queries::TONER_BY_ID)
         .bind(id)
         .fetch_optional(&state.db)
         .await
-    {
+        .and_then(|row| {
+            row.map(|row| Audited::<Toner>::from_row(&row, actor.is_admin()))
+                .transpose()
+        });
+
+    match toner {
         Ok(Some(toner)) => {
             info!("Toner found: {id}");
             (StatusCode::OK, Json(Some(toner)))
@@ -59,16 +78,33 @@ pub async fn search_toner(
     }
 }
 
-pub async fn show_toners(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let toners: Result<Vec<Toner>, sqlx::Error> = sqlx::query_as(r#"SELECT * FROM toners;"#)
+pub async fn show_toners(
+    Query(pagination): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let (limit, offset) = match pagination.resolve(&state.config) {
+        Ok(window) => window,
+        Err(message) => {
+            error!("Invalid pagination: {message}");
+            return Err((StatusCode::BAD_REQUEST, Json(message)));
+        }
+    };
+
+    let total: Result<(i64,), sqlx::Error> = sqlx::query_as(queries::COUNT_TONERS)
+        .fetch_one(&state.db)
+        .await;
+    let toners: Result<Vec<Toner>, sqlx::Error> = sqlx::query_as(queries::LIST_TONERS)
+        .bind(limit)
+        .bind(offset)
         .fetch_all(&state.db)
         .await;
-    match toners {
-        Ok(toners) => {
+    match (toners, total) {
+        (Ok(toners), Ok((total,))) => {
             info!("Toners listed successfully");
-            Ok(Json(toners))
+            Ok(Json(Page::new(toners, total, limit, offset, &uri)))
         }
-        Err(e) => {
+        (Err(e), _) | (_, Err(e)) => {
             error!("Error listing toners: {e}");
             Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -78,21 +114,303 @@ pub async fn show_toners(State(state): State<Arc<AppState>>) -> impl IntoRespons
     }
 }
 
+/// Groups of toners sharing a brand, color and yield, which are usually the
+/// same cartridge entered twice. Toners missing any of the three are skipped.
+pub async fn show_duplicate_toners(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match sqlx::query_as::<_, DuplicateToners>(
+        r#"
+        SELECT brand, lower(btrim(color)) AS color, page_yield,
+            array_agg(id ORDER BY id) AS toners
+        FROM toners
+        WHERE brand IS NOT NULL AND color IS NOT NULL AND page_yield IS NOT NULL
+        GROUP BY brand, lower(btrim(color)), page_yield
+        HAVING COUNT(*) > 1
+        ORDER BY brand, color, page_yield;
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(duplicates) => {
+            info!("Duplicate toner groups listed: {}", duplicates.len());
+            Ok(Json(duplicates))
+        }
+        Err(e) => {
+            error!("Error listing duplicate toners: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error listing duplicate toners."),
+            ))
+        }
+    }
+}
+
+/// Toners that no printer refers to, candidates for catalog cleanup.
+pub async fn show_unused_toners(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match sqlx::query_as::<_, Toner>(
+        r#"
+        SELECT toners.* FROM toners
+        LEFT JOIN printers ON printers.toner = toners.id
+        WHERE printers.id IS NULL
+        ORDER BY toners.name;
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(toners) => {
+            info!("Unused toners listed: {}", toners.len());
+            Ok(Json(toners))
+        }
+        Err(e) => {
+            error!("Error listing unused toners: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error listing unused toners."),
+            ))
+        }
+    }
+}
+
+/// Toners interchangeable with a toner, such as compatible cartridges that
+/// procurement may buy instead.
+pub async fn show_toner_alternatives(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match queries::exists(&state.db, Table::Toners, id).await {
+        Ok(true) => match toner_alternatives(&state.db, id).await {
+            Ok(toners) => {
+                info!("Alternatives of toner {} listed: {}", &id, toners.len());
+                (StatusCode::OK, Ok(Json(toners)))
+            }
+            Err(e) => {
+                error!("Error listing toner alternatives: {e}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Err(Json("Error listing toner alternatives.")),
+                )
+            }
+        },
+        Ok(false) => {
+            error!("Toner ID not found.");
+            (StatusCode::NOT_FOUND, Err(Json("Toner ID not found.")))
+        }
+        Err(e) => {
+            error!("Error listing toner alternatives: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error listing toner alternatives.")),
+            )
+        }
+    }
+}
+
+/// Marks two toners as interchangeable, which lists each one among the
+/// alternatives of the other. Answers with the toner's alternatives.
+pub async fn add_toner_alternative(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<AlternativeRequest>,
+) -> impl IntoResponse {
+    // Toner linked to itself
+    if request.toner == id {
+        error!("Toner {} cannot be its own alternative.", &id);
+        return (
+            StatusCode::BAD_REQUEST,
+            Err(Json("Toner cannot be its own alternative.")),
+        );
+    }
+
+    for toner in [id, request.toner] {
+        match queries::exists(&state.db, Table::Toners, toner).await {
+            Ok(true) => {}
+            Ok(false) => {
+                error!("Toner ID not found: {}", &toner);
+                return (StatusCode::NOT_FOUND, Err(Json("Toner ID not found.")));
+            }
+            Err(e) => {
+                error!("Error adding toner alternative: {e}");
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Err(Json("Error adding toner alternative.")),
+                );
+            }
+        }
+    }
+
+    let linked = sqlx::query(
+        r#"
+        INSERT INTO toner_equivalents (toner_id, equivalent_id)
+        VALUES (LEAST($1::uuid, $2::uuid), GREATEST($1::uuid, $2::uuid))
+        ON CONFLICT DO NOTHING;
+        "#,
+    )
+    .bind(id)
+    .bind(request.toner)
+    .execute(&state.db)
+    .await;
+
+    let toners = match linked {
+        Ok(_) => toner_alternatives(&state.db, id).await,
+        Err(e) => Err(e),
+    };
+
+    match toners {
+        Ok(toners) => {
+            info!(
+                "Toners {} and {} marked as alternatives",
+                &id, &request.toner
+            );
+            (StatusCode::OK, Ok(Json(toners)))
+        }
+        Err(e) => {
+            error!("Error adding toner alternative: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error adding toner alternative.")),
+            )
+        }
+    }
+}
+
+pub async fn remove_toner_alternative(
+    Path((id, alternative)): Path<(Uuid, Uuid)>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match sqlx::query(
+        r#"
+        DELETE FROM toner_equivalents
+        WHERE toner_id = LEAST($1::uuid, $2::uuid) AND equivalent_id = GREATEST($1::uuid, $2::uuid);
+        "#,
+    )
+    .bind(id)
+    .bind(alternative)
+    .execute(&state.db)
+    .await
+    {
+        Ok(result) if result.rows_affected() > 0 => {
+            info!(
+                "Toners {} and {} are no longer alternatives",
+                &id, &alternative
+            );
+            (StatusCode::OK, Ok(Json(id)))
+        }
+        Ok(_) => {
+            error!("Toner {} is not an alternative of {}.", &alternative, &id);
+            (
+                StatusCode::NOT_FOUND,
+                Err(Json("Toner is not an alternative of this toner.")),
+            )
+        }
+        Err(e) => {
+            error!("Error removing toner alternative: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error removing toner alternative.")),
+            )
+        }
+    }
+}
+
+/// Toners linked to `toner` in either direction, by name.
+async fn toner_alternatives<'e>(
+    executor: impl PgExecutor<'e>,
+    toner: Uuid,
+) -> Result<Vec<Toner>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT toners.* FROM toner_equivalents
+        JOIN toners ON toners.id IN (toner_equivalents.toner_id, toner_equivalents.equivalent_id)
+        WHERE $1 IN (toner_equivalents.toner_id, toner_equivalents.equivalent_id)
+            AND toners.id != $1
+        ORDER BY toners.name, toners.id;
+        "#,
+    )
+    .bind(toner)
+    .fetch_all(executor)
+    .await
+}
+
+/// Toners below their `min_stock`, most urgent first: those that run out
+/// soonest at the recent consumption rate, then the largest shortfalls.
+pub async fn show_reorder_suggestions(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match sqlx::query_as::<_, ReorderSuggestion>(
+        r#"
+        WITH consumption AS (
+            SELECT toner_id, SUM(-quantity)::FLOAT8 / $1 AS daily_consumption
+            FROM movements
+            WHERE toner_id IS NOT NULL
+                AND quantity < 0
+                AND created_at > now() - make_interval(days => $1)
+            GROUP BY toner_id
+        )
+        SELECT
+            id, name, stock, min_stock, daily_consumption,
+            CASE WHEN daily_consumption > 0
+                THEN GREATEST(stock, 0) / daily_consumption
+            END AS days_of_stock,
+            min_stock - stock + CEIL(daily_consumption * $2)::INTEGER AS suggested_quantity
+        FROM (
+            SELECT toners.*, COALESCE(consumption.daily_consumption, 0) AS daily_consumption
+            FROM toners
+            LEFT JOIN consumption ON consumption.toner_id = toners.id
+        ) AS toners
+        WHERE stock < min_stock
+        ORDER BY days_of_stock ASC NULLS LAST, min_stock - stock DESC, id;
+        "#,
+    )
+    .bind(state.config.reorder_lookback_days)
+    .bind(state.config.reorder_coverage_days)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(suggestions) => {
+            info!("Toner reorder suggestions listed: {}", suggestions.len());
+            Ok(Json(suggestions))
+        }
+        Err(e) => {
+            error!("Error listing toner reorder suggestions: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error listing toner reorder suggestions."),
+            ))
+        }
+    }
+}
+
 pub async fn create_toner(
+    actor: Actor,
     State(state): State<Arc<AppState>>,
     Json(request): Json<CreateTonerRequest>,
 ) -> impl IntoResponse {
-    let new_toner = Toner::new(&request.name);
+    let new_toner = Toner::new(
+        &request.name,
+        request.brand,
+        request.min_stock,
+        request.color.as_deref(),
+        request.page_yield,
+    );
+
+    // Invalid id
+    if let Err(message) = validations::check_ids(new_toner.brand, state.config.require_uuid_v4) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+    }
 
     // Check duplicate
-    match sqlx::query(r#"SELECT id FROM toners WHERE name = $1;"#)
-        .bind(&new_toner.name)
-        .fetch_optional(&state.db)
-        .await
+    match find_same_sku(
+        &state.db,
+        new_toner.brand,
+        &new_toner.name,
+        new_toner.color.as_deref(),
+        None,
+    )
+    .await
     {
-        Ok(Some(_)) => {
+        Ok(Some(existing)) => {
             error!("Toner '{}' already exists.", &new_toner.name);
-            return (StatusCode::CONFLICT, Err(Json("Toner already exists.")));
+            (StatusCode::CONFLICT, Err(Json(existing.into())))
         }
         Ok(None) => {
             // Name is empty
@@ -100,125 +418,408 @@ pub async fn create_toner(
                 error!("Toner name cannot be empty.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Toner name cannot be empty.")),
+                    Err(Json(ApiError::name_empty("Toner name cannot be empty."))),
                 );
             }
 
             // Name too short
-            if new_toner.name.len() < 4 {
+            if new_toner.name.chars().count() < NAME_MIN_LENGTH {
                 error!("Toner name is too short.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Toner name is too short.")),
+                    Err(Json(ApiError::name_too_short(
+                        "Toner name is too short.",
+                        NAME_MIN_LENGTH,
+                        new_toner.name.chars().count(),
+                    ))),
                 );
             }
 
             // Name too long
-            if new_toner.name.len() > 20 {
+            if new_toner.name.chars().count() > NAME_MAX_LENGTH {
                 error!("Toner name is too long.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Toner name is too long.")),
+                    Err(Json(ApiError::name_too_long(
+                        "Toner name is too long.",
+                        NAME_MAX_LENGTH,
+                        new_toner.name.chars().count(),
+                    ))),
+                );
+            }
+
+            // Negative minimum stock
+            if new_toner.min_stock < 0 {
+                error!("Toner minimum stock cannot be negative.");
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Err(Json("Minimum stock cannot be negative.".into())),
+                );
+            }
+
+            // Color empty or too long
+            if let Err(message) = check_color(new_toner.color.as_deref()) {
+                error!("{message}");
+                return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+            }
+
+            // Yield not positive
+            if new_toner
+                .page_yield
+                .is_some_and(|page_yield| page_yield <= 0)
+            {
+                error!("Toner page yield must be positive.");
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Err(Json("Toner page yield must be positive.".into())),
                 );
             }
 
-            match sqlx::query(r#"INSERT INTO toners (id, name) VALUES ($1, $2);"#)
+            // Brand not found
+            if let Some(brand) = new_toner.brand {
+                match queries::exists(&state.db, Table::Brands, brand).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        error!("Toner brand not found: {}", brand);
+                        return (
+                            StatusCode::BAD_REQUEST,
+                            Err(Json("Toner brand not found.".into())),
+                        );
+                    }
+                    Err(e) => {
+                        error!("Error fetching toner brand: {}", e);
+                        return (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            Err(Json("Error creating toner.".into())),
+                        );
+                    }
+                }
+            }
+
+            let mut tx = match state.db.begin().await {
+                Ok(tx) => tx,
+                Err(e) => {
+                    error!("Error creating toner: {}", e);
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Err(Json("Error creating toner.".into())),
+                    );
+                }
+            };
+
+            // Brand at its toner limit
+            if let (Some(brand), Some(limit)) = (new_toner.brand, state.config.max_toners_per_brand)
+            {
+                match brand_toner_count(&mut tx, brand, None).await {
+                    Ok(count) if count >= limit => {
+                        error!("Toner brand {} already has {} toners.", brand, count);
+                        return (
+                            StatusCode::CONFLICT,
+                            Err(Json(ApiError::brand_toner_limit(
+                                "Toner brand already has the maximum number of toners.",
+                                limit,
+                                count,
+                            ))),
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        error!("Error counting brand toners: {}", e);
+                        return (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            Err(Json("Error creating toner.".into())),
+                        );
+                    }
+                }
+            }
+
+            // Same brand, color and yield as another toner
+            let warnings: Vec<String> = match find_duplicate(&state, &new_toner).await {
+                Ok(Some(duplicate)) => {
+                    info!("Toner duplicates {duplicate}");
+                    match validations::soft_violation(
+                        state.config.soft_mode(ValidationMode::Warn),
+                        "A toner with the same brand, color and yield already exists.",
+                    ) {
+                        Ok(warning) => warning.into_iter().map(String::from).collect(),
+                        Err((status, message)) => return (status, Err(Json(message.into()))),
+                    }
+                }
+                Ok(None) => Vec::new(),
+                Err(e) => {
+                    error!("Error checking duplicate toners: {e}");
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Err(Json("Error creating toner.".into())),
+                    );
+                }
+            };
+
+            let inserted = async {
+                sqlx::query(
+                    r#"
+                    INSERT INTO toners
+                        (id, name, brand, min_stock, color, page_yield, created_by, updated_by)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $7);
+                    "#,
+                )
                 .bind(new_toner.id)
                 .bind(&new_toner.name)
-                .execute(&state.db)
-                .await
-            {
+                .bind(new_toner.brand)
+                .bind(new_toner.min_stock)
+                .bind(&new_toner.color)
+                .bind(new_toner.page_yield)
+                .bind(actor.id())
+                .execute(&mut *tx)
+                .await?;
+                tx.commit().await
+            }
+            .await;
+
+            match inserted {
                 Ok(_) => {
                     info!("Toner created! ID: {}", &new_toner.id);
-                    (StatusCode::CREATED, Ok(Json(new_toner.id)))
+                    (
+                        StatusCode::CREATED,
+                        Ok((
+                            location(format!("/api/v1/supplies/toner/{}", new_toner.id)),
+                            Json(MutationResponse {
+                                id: new_toner.id,
+                                warnings,
+                            }),
+                        )),
+                    )
+                }
+                // Another request took the combination since the check above.
+                Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                    error!("Toner already exists: {}", e);
+                    let existing = stored_sku(
+                        &state,
+                        new_toner.brand,
+                        &new_toner.name,
+                        new_toner.color.as_deref(),
+                        None,
+                    )
+                    .await;
+                    (StatusCode::CONFLICT, Err(Json(existing)))
                 }
                 Err(e) => {
                     error!("Error creating toner: {}", e);
                     (
                         StatusCode::INTERNAL_SERVER_ERROR,
-                        Err(Json("Error creating toner.")),
+                        Err(Json("Error creating toner.".into())),
                     )
                 }
             }
         }
         Err(_) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Err(Json("Error creating toner.")),
+            Err(Json("Error creating toner.".into())),
         ),
     }
 }
 
 pub async fn update_toner(
+    actor: Actor,
     State(state): State<Arc<AppState>>,
     Json(request): Json<UpdateTonerRequest>,
 ) -> impl IntoResponse {
     let toner_id = request.id;
     let new_name = request.name;
+    let new_brand = request.brand;
+    let new_min_stock = request.min_stock;
+    let new_color = request.color;
+    let new_page_yield = request.page_yield;
+
+    // Invalid id
+    if let Err(message) = validations::check_ids(
+        [toner_id].into_iter().chain(new_brand),
+        state.config.require_uuid_v4,
+    ) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+    }
 
     // ID not found
-    match sqlx::query(r#"SELECT id FROM toners WHERE id = $1;"#)
-        .bind(toner_id)
-        .fetch_optional(&state.db)
-        .await
-    {
-        Ok(Some(_)) => {
+    match queries::exists(&state.db, Table::Toners, toner_id).await {
+        Ok(true) => {
             // Name is empty
             if new_name.is_empty() {
                 error!("Toner name cannot be empty.");
                 return (
                     StatusCode::CONFLICT,
-                    Err(Json("Toner name cannot be empty.")),
+                    Err(Json(ApiError::name_empty("Toner name cannot be empty."))),
                 );
             }
 
             // Name too short
-            if new_name.len() < 4 {
+            if new_name.chars().count() < NAME_MIN_LENGTH {
                 error!("Toner name is too short.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Toner name is too short.")),
+                    Err(Json(ApiError::name_too_short(
+                        "Toner name is too short.",
+                        NAME_MIN_LENGTH,
+                        new_name.chars().count(),
+                    ))),
                 );
             }
 
             // Name too long
-            if new_name.len() > 20 {
+            if new_name.chars().count() > NAME_MAX_LENGTH {
                 error!("Toner name is too long.");
                 return (
                     StatusCode::BAD_REQUEST,
-                    Err(Json("Toner name is too long.")),
+                    Err(Json(ApiError::name_too_long(
+                        "Toner name is too long.",
+                        NAME_MAX_LENGTH,
+                        new_name.chars().count(),
+                    ))),
                 );
             }
 
+            // Negative minimum stock
+            if new_min_stock.is_some_and(|min_stock| min_stock < 0) {
+                error!("Toner minimum stock cannot be negative.");
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Err(Json("Minimum stock cannot be negative.".into())),
+                );
+            }
+
+            // Color empty or too long
+            if let Err(message) = check_color(new_color.as_deref()) {
+                error!("{message}");
+                return (StatusCode::BAD_REQUEST, Err(Json(message.into())));
+            }
+
+            // Yield not positive
+            if new_page_yield.is_some_and(|page_yield| page_yield <= 0) {
+                error!("Toner page yield must be positive.");
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Err(Json("Toner page yield must be positive.".into())),
+                );
+            }
+
+            // Brand not found
+            if let Some(brand) = new_brand {
+                match queries::exists(&state.db, Table::Brands, brand).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        error!("Toner brand not found: {}", brand);
+                        return (
+                            StatusCode::BAD_REQUEST,
+                            Err(Json("Toner brand not found.".into())),
+                        );
+                    }
+                    Err(e) => {
+                        error!("Error fetching toner brand: {}", e);
+                        return (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            Err(Json("Error updating toner.".into())),
+                        );
+                    }
+                }
+            }
+
+            let mut tx = match state.db.begin().await {
+                Ok(tx) => tx,
+                Err(e) => {
+                    error!("Error updating toner: {}", e);
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Err(Json("Error updating toner.".into())),
+                    );
+                }
+            };
+
+            // New brand at its toner limit
+            if let (Some(brand), Some(limit)) = (new_brand, state.config.max_toners_per_brand) {
+                match moved_toner_count(&mut tx, brand, toner_id).await {
+                    Ok(Some(count)) if count >= limit => {
+                        error!("Toner brand {} already has {} toners.", brand, count);
+                        return (
+                            StatusCode::CONFLICT,
+                            Err(Json(ApiError::brand_toner_limit(
+                                "Toner brand already has the maximum number of toners.",
+                                limit,
+                                count,
+                            ))),
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        error!("Error counting brand toners: {}", e);
+                        return (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            Err(Json("Error updating toner.".into())),
+                        );
+                    }
+                }
+            }
+
             // Check duplicate
-            match sqlx::query(r#"SELECT id FROM toners WHERE name = $1 AND id != $2;"#)
-                .bind(&new_name)
-                .bind(toner_id)
-                .fetch_optional(&state.db)
-                .await
+            match find_same_sku(
+                &state.db,
+                new_brand,
+                &new_name,
+                new_color.as_deref(),
+                Some(toner_id),
+            )
+            .await
             {
-                Ok(Some(_)) => {
-                    error!("Toner name already exists.");
-                    (
-                        StatusCode::CONFLICT,
-                        Err(Json("Toner name already exists.")),
-                    )
+                Ok(Some(existing)) => {
+                    error!("Toner '{}' already exists.", &new_name);
+                    (StatusCode::CONFLICT, Err(Json(existing.into())))
                 }
                 Ok(None) => {
-                    match sqlx::query(r#"UPDATE toners SET name = $1 WHERE id = $2;"#)
+                    let updated = async {
+                        sqlx::query(
+                            r#"
+                            UPDATE toners
+                            SET name = $1, brand = $2, min_stock = COALESCE($5, min_stock),
+                                color = COALESCE($6, color),
+                                page_yield = COALESCE($7, page_yield), updated_by = $4
+                            WHERE id = $3;
+                            "#,
+                        )
                         .bind(&new_name)
+                        .bind(new_brand)
                         .bind(toner_id)
-                        .execute(&state.db)
-                        .await
-                    {
+                        .bind(actor.id())
+                        .bind(new_min_stock)
+                        .bind(&new_color)
+                        .bind(new_page_yield)
+                        .execute(&mut *tx)
+                        .await?;
+                        tx.commit().await
+                    }
+                    .await;
+
+                    match updated {
                         Ok(_) => {
                             info!("Toner updated! ID: {}", &toner_id);
                             (StatusCode::OK, Ok(Json(toner_id)))
                         }
+                        // Another request took the combination since the check above.
+                        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                            error!("Toner already exists: {}", e);
+                            let existing = stored_sku(
+                                &state,
+                                new_brand,
+                                &new_name,
+                                new_color.as_deref(),
+                                Some(toner_id),
+                            )
+                            .await;
+                            (StatusCode::CONFLICT, Err(Json(existing)))
+                        }
                         Err(e) => {
                             error!("Error updating toner: {}", e);
                             (
                                 StatusCode::INTERNAL_SERVER_ERROR,
-                                Err(Json("Error updating toner.")),
+                                Err(Json("Error updating toner.".into())),
                             )
                         }
                     }
@@ -227,63 +828,220 @@ pub async fn update_toner(
                     error!("Error checking for duplicate toner name: {}", e);
                     (
                         StatusCode::INTERNAL_SERVER_ERROR,
-                        Err(Json("Error checking for duplicated toner name.")),
+                        Err(Json("Error checking for duplicated toner name.".into())),
                     )
                 }
             }
         }
-        Ok(None) => {
+        Ok(false) => {
             error!("Toner ID not found.");
-            (StatusCode::NOT_FOUND, Err(Json("Toner ID not found.")))
+            (
+                StatusCode::NOT_FOUND,
+                Err(Json("Toner ID not found.".into())),
+            )
         }
         Err(e) => {
             error!("Error fetching toner by ID: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Err(Json("Error fetching toner by ID")),
+                Err(Json("Error fetching toner by ID".into())),
             )
         }
     }
 }
 
 pub async fn delete_toner(
+    actor: Actor,
     State(state): State<Arc<AppState>>,
     Json(request): Json<DeleteRequest>,
-) -> impl IntoResponse {
-    match sqlx::query(r#"SELECT id FROM toners WHERE id = $1;"#)
-        .bind(request.id)
-        .fetch_optional(&state.db)
-        .await
-    {
-        Ok(Some(_)) => {
-            match sqlx::query(r#"DELETE FROM toners WHERE id = $1;"#)
+) -> Response {
+    // Invalid id
+    if let Err(message) = validations::check_ids([request.id], state.config.require_uuid_v4) {
+        error!("{message}");
+        return (StatusCode::BAD_REQUEST, Json(message)).into_response();
+    }
+
+    match queries::exists(&state.db, Table::Toners, request.id).await {
+        Ok(true) => {
+            match sqlx::query(&audit::delete_statement("toners"))
                 .bind(request.id)
+                .bind(actor.id())
                 .execute(&state.db)
                 .await
             {
                 Ok(_) => {
                     info!("Toner deleted! ID: {}", &request.id);
-                    (StatusCode::OK, Ok(Json("Toner deleted!")))
+                    deleted(&state.config, "Toner deleted!")
                 }
                 Err(e) => {
                     error!("Error deleting toner: {}", e);
                     (
                         StatusCode::INTERNAL_SERVER_ERROR,
-                        Ok(Json("Error deleting toner.")),
+                        Json("Error deleting toner."),
                     )
+                        .into_response()
                 }
             }
         }
-        Ok(None) => {
+        // Already deleted, as when a client retries a delete that succeeded
+        Ok(false) if state.config.idempotent_deletes => {
+            info!("Toner {} already absent.", &request.id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => {
             error!("Toner ID not found.");
-            (StatusCode::NOT_FOUND, Err(Json("Toner ID not found")))
+            (StatusCode::NOT_FOUND, Json("Toner ID not found")).into_response()
         }
         Err(e) => {
             error!("Error deleting toner: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Err(Json("Error deleting drum.")),
+                Json("Error deleting drum."),
             )
+                .into_response()
         }
     }
 }
+
+/// Number of toners of `brand` other than `except`, for the
+/// `MAX_TONERS_PER_BRAND` check. The brand row stays locked until `conn`'s
+/// transaction ends, so saves counting the same brand take turns and cannot
+/// both take its last place.
+pub async fn brand_toner_count(
+    conn: &mut PgConnection,
+    brand: Uuid,
+    except: Option<Uuid>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query(r#"SELECT id FROM brands WHERE id = $1 FOR UPDATE;"#)
+        .bind(brand)
+        .execute(&mut *conn)
+        .await?;
+    let (count,): (i64,) = sqlx::query_as(
+        r#"SELECT COUNT(*) FROM toners WHERE brand = $1 AND id IS DISTINCT FROM $2;"#,
+    )
+    .bind(brand)
+    .bind(except)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(count)
+}
+
+/// Like [`brand_toner_count`] for `toner`, new or existing, moving to
+/// `brand`, or `None` when it already belongs to `brand` and takes no new
+/// place.
+pub async fn moved_toner_count(
+    conn: &mut PgConnection,
+    brand: Uuid,
+    toner: Uuid,
+) -> Result<Option<i64>, sqlx::Error> {
+    let current: Option<(Option<Uuid>,)> =
+        sqlx::query_as(r#"SELECT brand FROM toners WHERE id = $1;"#)
+            .bind(toner)
+            .fetch_optional(&mut *conn)
+            .await?;
+    if current == Some((Some(brand),)) {
+        return Ok(None);
+    }
+
+    brand_toner_count(conn, brand, Some(toner)).await.map(Some)
+}
+
+/// Colors are optional, but must not be blank when given.
+fn check_color(color: Option<&str>) -> Result<(), &'static str> {
+    match color {
+        Some(color) if color.trim().is_empty() => Err("Toner color cannot be empty."),
+        Some(color) if color.chars().count() > 20 => Err("Toner color is too long."),
+        _ => Ok(()),
+    }
+}
+
+/// Toner other than `except` with the brand, name and color of a toner being
+/// saved, which `toners_brand_name_color_key` keeps unique. On update a `None`
+/// color keeps the stored one, so `except` supplies it.
+async fn find_same_sku<'e>(
+    executor: impl PgExecutor<'e>,
+    brand: Option<Uuid>,
+    name: &str,
+    color: Option<&str>,
+    except: Option<Uuid>,
+) -> Result<Option<DuplicateSku>, sqlx::Error> {
+    let existing: Option<(Option<Uuid>, String, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT brand, name, color FROM toners
+        WHERE brand IS NOT DISTINCT FROM $1 AND name = $2
+            AND lower(btrim(color)) IS NOT DISTINCT FROM
+                lower(btrim(COALESCE($3, (SELECT color FROM toners WHERE id = $4))))
+            AND id IS DISTINCT FROM $4
+        LIMIT 1;
+        "#,
+    )
+    .bind(brand)
+    .bind(name)
+    .bind(color)
+    .bind(except)
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(existing.map(|(brand, name, color)| DuplicateSku { brand, name, color }))
+}
+
+/// The `DUPLICATE_TONER` error for a save that lost the race for
+/// `toners_brand_name_color_key`, naming the toner stored by the other request
+/// or, when it cannot be read, the values sent.
+async fn stored_sku(
+    state: &AppState,
+    brand: Option<Uuid>,
+    name: &str,
+    color: Option<&str>,
+    except: Option<Uuid>,
+) -> ApiError {
+    match find_same_sku(&state.db, brand, name, color, except).await {
+        Ok(Some(existing)) => existing.into(),
+        _ => ApiError::duplicate_toner(DUPLICATE_SKU_MESSAGE, brand, name, color),
+    }
+}
+
+const DUPLICATE_SKU_MESSAGE: &str = "A toner with this brand, name and color already exists.";
+
+/// Brand, name and color of the toner a save collides with.
+struct DuplicateSku {
+    brand: Option<Uuid>,
+    name: String,
+    color: Option<String>,
+}
+
+impl From<DuplicateSku> for ApiError {
+    fn from(existing: DuplicateSku) -> Self {
+        ApiError::duplicate_toner(
+            DUPLICATE_SKU_MESSAGE,
+            existing.brand,
+            &existing.name,
+            existing.color.as_deref(),
+        )
+    }
+}
+
+/// Finds a toner of the same brand, color and yield as `toner`. Colors are
+/// compared ignoring case and surrounding spaces.
+async fn find_duplicate(state: &AppState, toner: &Toner) -> Result<Option<Uuid>, sqlx::Error> {
+    let (Some(brand), Some(color), Some(page_yield)) =
+        (toner.brand, &toner.color, toner.page_yield)
+    else {
+        return Ok(None);
+    };
+
+    sqlx::query_scalar(
+        r#"
+        SELECT id FROM toners
+        WHERE brand = $1 AND lower(btrim(color)) = lower(btrim($2)) AND page_yield = $3
+        ORDER BY id
+        LIMIT 1;
+        "#,
+    )
+    .bind(brand)
+    .bind(color)
+    .bind(page_yield)
+    .fetch_optional(&state.db)
+    .await
+}
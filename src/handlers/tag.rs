@@ -0,0 +1,248 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Extension};
+use sqlx::PgExecutor;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    extractors::{Json, Path},
+    ids,
+    middleware::deadline::Deadline,
+    models::{
+        database::AppState,
+        tag::{self, TagCount, TagRequest},
+    },
+    queries::{self, Table},
+};
+
+/// Width of the `tags.name` column.
+const TAG_MAX_LENGTH: usize = 30;
+
+/// Every tag in use with the number of printers carrying it.
+pub async fn show_tags(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let tags: Result<Vec<TagCount>, sqlx::Error> = sqlx::query_as(
+        r#"
+        SELECT tags.name, COUNT(*) AS count
+        FROM tags
+        JOIN printer_tags ON printer_tags.tag_id = tags.id
+        GROUP BY tags.name
+        ORDER BY tags.name ASC;
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    match tags {
+        Ok(tags) => {
+            info!("Tags listed: {}", tags.len());
+            Ok(Json(tags))
+        }
+        Err(e) => {
+            error!("Error listing tags: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error listing tags."),
+            ))
+        }
+    }
+}
+
+pub async fn show_printer_tags(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match queries::exists(&state.db, Table::Printers, id).await {
+        Ok(true) => match printer_tags(&state.db, id).await {
+            Ok(tags) => {
+                info!("Tags of printer {} listed", &id);
+                (StatusCode::OK, Ok(Json(tags)))
+            }
+            Err(e) => {
+                error!("Error listing printer tags: {e}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Err(Json("Error listing printer tags.")),
+                )
+            }
+        },
+        Ok(false) => {
+            error!("Printer ID not found.");
+            (StatusCode::NOT_FOUND, Err(Json("Printer ID not found.")))
+        }
+        Err(e) => {
+            error!("Error listing printer tags: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error listing printer tags.")),
+            )
+        }
+    }
+}
+
+/// Labels a printer, creating the tag on first use. Answers with the
+/// printer's tags.
+pub async fn add_printer_tag(
+    Path(id): Path<Uuid>,
+    Extension(deadline): Extension<Deadline>,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<TagRequest>,
+) -> impl IntoResponse {
+    let name = tag::normalize(&request.tag);
+
+    // Tag is empty
+    if name.is_empty() {
+        error!("Tag cannot be empty.");
+        return (StatusCode::BAD_REQUEST, Err(Json("Tag cannot be empty.")));
+    }
+
+    // Tag too long
+    if name.chars().count() > TAG_MAX_LENGTH {
+        error!("Tag is too long.");
+        return (StatusCode::BAD_REQUEST, Err(Json("Tag is too long.")));
+    }
+
+    let mut tx = match state.begin(deadline).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!("Error tagging printer: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error tagging printer.")),
+            );
+        }
+    };
+
+    match queries::exists(&mut *tx, Table::Printers, id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            error!("Printer ID not found.");
+            return (StatusCode::NOT_FOUND, Err(Json("Printer ID not found.")));
+        }
+        Err(e) => {
+            error!("Error tagging printer: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error tagging printer.")),
+            );
+        }
+    }
+
+    // The no-op update makes `RETURNING` yield the id of an existing tag too.
+    let tag_id: Result<(Uuid,), sqlx::Error> = sqlx::query_as(
+        r#"
+        INSERT INTO tags (id, name) VALUES ($1, $2)
+        ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
+        RETURNING id;
+        "#,
+    )
+    .bind(ids::new_id())
+    .bind(&name)
+    .fetch_one(&mut *tx)
+    .await;
+
+    let linked = match tag_id {
+        Ok((tag_id,)) => {
+            sqlx::query(
+                r#"
+                INSERT INTO printer_tags (printer_id, tag_id) VALUES ($1, $2)
+                ON CONFLICT DO NOTHING;
+                "#,
+            )
+            .bind(id)
+            .bind(tag_id)
+            .execute(&mut *tx)
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    let tags = match linked {
+        Ok(_) => printer_tags(&mut *tx, id).await,
+        Err(e) => Err(e),
+    };
+
+    match tags {
+        Ok(tags) => match tx.commit().await {
+            Ok(_) => {
+                info!("Printer {} tagged '{}'", &id, &name);
+                (StatusCode::OK, Ok(Json(tags)))
+            }
+            Err(e) => {
+                error!("Error tagging printer: {e}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Err(Json("Error tagging printer.")),
+                )
+            }
+        },
+        Err(e) => {
+            error!("Error tagging printer: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error tagging printer.")),
+            )
+        }
+    }
+}
+
+pub async fn remove_printer_tag(
+    Path((id, name)): Path<(Uuid, String)>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let name = tag::normalize(&name);
+
+    match sqlx::query(
+        r#"
+        DELETE FROM printer_tags
+        USING tags
+        WHERE printer_tags.tag_id = tags.id
+            AND printer_tags.printer_id = $1
+            AND tags.name = $2;
+        "#,
+    )
+    .bind(id)
+    .bind(&name)
+    .execute(&state.db)
+    .await
+    {
+        Ok(result) if result.rows_affected() > 0 => {
+            info!("Tag '{}' removed from printer {}", &name, &id);
+            (StatusCode::OK, Ok(Json(id)))
+        }
+        Ok(_) => {
+            error!("Printer {} is not tagged '{}'.", &id, &name);
+            (
+                StatusCode::NOT_FOUND,
+                Err(Json("Printer does not have this tag.")),
+            )
+        }
+        Err(e) => {
+            error!("Error removing printer tag: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Err(Json("Error removing printer tag.")),
+            )
+        }
+    }
+}
+
+async fn printer_tags<'e>(
+    executor: impl PgExecutor<'e>,
+    printer: Uuid,
+) -> Result<Vec<String>, sqlx::Error> {
+    let tags: Vec<(String,)> = sqlx::query_as(
+        r#"
+        SELECT tags.name
+        FROM printer_tags
+        JOIN tags ON tags.id = printer_tags.tag_id
+        WHERE printer_tags.printer_id = $1
+        ORDER BY tags.name ASC;
+        "#,
+    )
+    .bind(printer)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(tags.into_iter().map(|(name,)| name).collect())
+}
@@ -0,0 +1,87 @@
+use std::{fmt, sync::OnceLock};
+
+use serde::{
+    de::{Error, Unexpected, Visitor},
+    Deserialize, Deserializer,
+};
+use ulid::Ulid;
+use uuid::Uuid;
+
+use crate::config::IdFormat;
+
+static FORMAT: OnceLock<IdFormat> = OnceLock::new();
+
+/// Selects the format of newly generated ids. Called once at startup.
+pub fn init(format: IdFormat) {
+    let _ = FORMAT.set(format);
+}
+
+/// Generates a new id in the configured format.
+///
+/// ULIDs are stored through their UUID representation, so they fit the
+/// existing `UUID` columns while still sorting by creation time.
+pub fn new_id() -> Uuid {
+    match FORMAT.get().copied().unwrap_or(IdFormat::Uuid) {
+        IdFormat::Uuid => Uuid::new_v4(),
+        IdFormat::Ulid => Ulid::new().into(),
+    }
+}
+
+/// Parses an id written either as a UUID or as a ULID.
+pub fn parse(value: &str) -> Option<Uuid> {
+    Uuid::parse_str(value)
+        .ok()
+        .or_else(|| Ulid::from_string(value).ok().map(Uuid::from))
+}
+
+/// Parses every id in `values`, or `None` when any of them is invalid.
+pub fn parse_all<const N: usize>(values: [&str; N]) -> Option<[Uuid; N]> {
+    let mut ids = [Uuid::nil(); N];
+    for (id, value) in ids.iter_mut().zip(values) {
+        *id = parse(value)?;
+    }
+    Some(ids)
+}
+
+/// Deserializes an id written either as a UUID or as a ULID, for request
+/// fields marked `#[serde(deserialize_with = "ids::deserialize")]`.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Uuid, D::Error> {
+    AnyId::deserialize(deserializer).map(|AnyId(id)| id)
+}
+
+/// [`deserialize`] for an optional id. The field also needs
+/// `#[serde(default)]` to be left out.
+pub fn deserialize_option<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Uuid>, D::Error> {
+    Option::<AnyId>::deserialize(deserializer).map(|id| id.map(|AnyId(id)| id))
+}
+
+/// [`deserialize`] for a list of ids.
+pub fn deserialize_all<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Uuid>, D::Error> {
+    Vec::<AnyId>::deserialize(deserializer).map(|ids| ids.into_iter().map(|AnyId(id)| id).collect())
+}
+
+struct AnyId(Uuid);
+
+impl<'de> Deserialize<'de> for AnyId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(AnyIdVisitor)
+    }
+}
+
+struct AnyIdVisitor;
+
+impl Visitor<'_> for AnyIdVisitor {
+    type Value = AnyId;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a UUID or ULID string")
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<AnyId, E> {
+        parse(value)
+            .map(AnyId)
+            .ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))
+    }
+}
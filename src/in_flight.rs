@@ -0,0 +1,60 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// Count of the requests being served, capped at `limit` so a burst cannot
+/// queue more work than the database pool can take. Zero disables the cap.
+pub struct InFlight {
+    limit: usize,
+    count: AtomicUsize,
+}
+
+/// A request admitted by [`InFlight::try_admit`], counted until dropped.
+pub struct Admission {
+    in_flight: Arc<InFlight>,
+}
+
+impl InFlight {
+    pub fn new(limit: usize) -> Self {
+        InFlight {
+            limit,
+            count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Counts a new request, or returns `None` when `limit` requests are
+    /// already being served.
+    pub fn try_admit(self: &Arc<Self>) -> Option<Admission> {
+        self.count
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (self.limit == 0 || count < self.limit).then_some(count + 1)
+            })
+            .ok()
+            .map(|_| Admission {
+                in_flight: self.clone(),
+            })
+    }
+
+    /// Counts a request that is served whatever the load.
+    pub fn admit(self: &Arc<Self>) -> Admission {
+        self.count.fetch_add(1, Ordering::AcqRel);
+        Admission {
+            in_flight: self.clone(),
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Acquire)
+    }
+
+    pub fn limit(&self) -> Option<usize> {
+        (self.limit > 0).then_some(self.limit)
+    }
+}
+
+impl Drop for Admission {
+    fn drop(&mut self) {
+        self.in_flight.count.fetch_sub(1, Ordering::AcqRel);
+    }
+}
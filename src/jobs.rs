@@ -0,0 +1,188 @@
+use std::{sync::Arc, time::Duration};
+
+use serde::Serialize;
+use sqlx::{prelude::FromRow, PgConnection};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    middleware::deadline::Deadline,
+    models::{database::AppState, snapshot::Snapshot},
+};
+
+/// Bulk admin operations. Each one takes a transaction-scoped advisory lock
+/// keyed by its name, so two runs of the same operation never overlap.
+#[derive(Clone, Copy)]
+pub enum AdminOperation {
+    RecomputeCounts,
+    MergeBrands,
+    ReplaceToner,
+    ImportCatalog,
+    TakeSnapshot,
+}
+
+impl AdminOperation {
+    pub fn name(self) -> &'static str {
+        match self {
+            AdminOperation::RecomputeCounts => "recompute-counts",
+            AdminOperation::MergeBrands => "merge-brands",
+            AdminOperation::ReplaceToner => "replace-toner",
+            AdminOperation::ImportCatalog => "import-catalog",
+            AdminOperation::TakeSnapshot => "take-snapshot",
+        }
+    }
+
+    /// Takes the operation's lock for the rest of the current transaction,
+    /// without waiting. Returns `false` when another run holds it.
+    pub async fn try_lock(self, conn: &mut PgConnection) -> Result<bool, sqlx::Error> {
+        let (locked,): (bool,) =
+            sqlx::query_as(r#"SELECT pg_try_advisory_xact_lock(hashtext($1));"#)
+                .bind(self.name())
+                .fetch_one(conn)
+                .await?;
+        Ok(locked)
+    }
+}
+
+pub enum JobError {
+    /// Another run of the same operation is in progress.
+    Busy,
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for JobError {
+    fn from(e: sqlx::Error) -> Self {
+        JobError::Database(e)
+    }
+}
+
+/// A brand whose stored `printer_count` had drifted from the real count.
+#[derive(Serialize, FromRow)]
+pub struct CountDiscrepancy {
+    pub brand: Uuid,
+    pub brand_name: String,
+    pub stored: i32,
+    pub actual: i32,
+}
+
+/// Recomputes `brands.printer_count` from the printers table, fixing and
+/// returning the rows that had drifted. Must run inside a transaction, which
+/// holds the operation's lock until it ends.
+pub async fn reconcile_brand_printer_counts(
+    conn: &mut PgConnection,
+) -> Result<Vec<CountDiscrepancy>, JobError> {
+    if !AdminOperation::RecomputeCounts.try_lock(&mut *conn).await? {
+        return Err(JobError::Busy);
+    }
+
+    let fixed: Vec<CountDiscrepancy> = sqlx::query_as(
+        r#"
+        WITH actual AS (
+            SELECT brands.id, brands.printer_count AS stored, COUNT(printers.id)::int AS count
+            FROM brands
+            LEFT JOIN printers ON printers.brand = brands.id
+            GROUP BY brands.id, brands.printer_count
+        )
+        UPDATE brands
+        SET printer_count = actual.count
+        FROM actual
+        WHERE brands.id = actual.id AND actual.stored <> actual.count
+        RETURNING brands.id AS brand, brands.name AS brand_name, actual.stored, actual.count AS actual;
+        "#,
+    )
+    .fetch_all(conn)
+    .await?;
+
+    for discrepancy in &fixed {
+        warn!(
+            "Brand '{}' printer count fixed: {} -> {}",
+            discrepancy.brand_name, discrepancy.stored, discrepancy.actual
+        );
+    }
+
+    Ok(fixed)
+}
+
+/// Runs the count reconciliation every `COUNT_RECONCILE_INTERVAL_SECONDS`.
+pub async fn reconcile_counts_periodically(state: Arc<AppState>) {
+    let period = Duration::from_secs(state.config.count_reconcile_interval_seconds);
+    let mut interval = tokio::time::interval(period);
+
+    loop {
+        interval.tick().await;
+        // Bounded like a request, so a stuck run does not hold its lock.
+        let deadline = Deadline::after(Duration::from_secs(state.config.request_timeout_seconds));
+        let mut tx = match state.begin(deadline).await {
+            Ok(tx) => tx,
+            Err(e) => {
+                error!("Error reconciling counts: {e}");
+                continue;
+            }
+        };
+        match reconcile_brand_printer_counts(&mut tx).await {
+            Ok(fixed) => match tx.commit().await {
+                Ok(_) => info!("Count reconciliation done, {} rows fixed", fixed.len()),
+                Err(e) => error!("Error reconciling counts: {e}"),
+            },
+            Err(JobError::Busy) => warn!("Count reconciliation already running, skipped"),
+            Err(JobError::Database(e)) => error!("Error reconciling counts: {e}"),
+        }
+    }
+}
+
+/// Records the current headline counts of the catalog in `snapshots`. Must run
+/// inside a transaction, which holds the operation's lock until it ends.
+pub async fn take_snapshot(conn: &mut PgConnection) -> Result<Snapshot, JobError> {
+    if !AdminOperation::TakeSnapshot.try_lock(&mut *conn).await? {
+        return Err(JobError::Busy);
+    }
+
+    let snapshot = sqlx::query_as(
+        r#"
+        INSERT INTO snapshots (
+            printers, active_printers, brands, toners, drums,
+            low_stock_toners, toner_stock, drum_stock
+        )
+        SELECT
+            (SELECT COUNT(*) FROM printers),
+            (SELECT COUNT(*) FROM printers WHERE status = 'active'),
+            (SELECT COUNT(*) FROM brands),
+            (SELECT COUNT(*) FROM toners),
+            (SELECT COUNT(*) FROM drums),
+            (SELECT COUNT(*) FROM toners WHERE stock < min_stock),
+            (SELECT coalesce(SUM(stock), 0) FROM toners),
+            (SELECT coalesce(SUM(stock), 0) FROM drums)
+        RETURNING *;
+        "#,
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(snapshot)
+}
+
+/// Takes a snapshot every `SNAPSHOT_INTERVAL_SECONDS`, starting at launch.
+pub async fn take_snapshots_periodically(state: Arc<AppState>) {
+    let period = Duration::from_secs(state.config.snapshot_interval_seconds);
+    let mut interval = tokio::time::interval(period);
+
+    loop {
+        interval.tick().await;
+        let deadline = Deadline::after(Duration::from_secs(state.config.request_timeout_seconds));
+        let mut tx = match state.begin(deadline).await {
+            Ok(tx) => tx,
+            Err(e) => {
+                error!("Error taking snapshot: {e}");
+                continue;
+            }
+        };
+        match take_snapshot(&mut tx).await {
+            Ok(snapshot) => match tx.commit().await {
+                Ok(_) => info!("Snapshot {} taken", snapshot.id),
+                Err(e) => error!("Error taking snapshot: {e}"),
+            },
+            Err(JobError::Busy) => warn!("Snapshot already being taken, skipped"),
+            Err(JobError::Database(e)) => error!("Error taking snapshot: {e}"),
+        }
+    }
+}
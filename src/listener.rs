@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use sqlx::{postgres::PgListener, PgPool};
+use tracing::{error, info, warn};
+
+use crate::{
+    cache::{self, Cache},
+    events::{self, Events},
+};
+
+/// Listens on one connection for the notifications of the database triggers:
+/// table changes drop the matching cache entries, so edits made outside the
+/// API are seen, and audit log changes are streamed to the `GET /events`
+/// clients. Reconnects to Postgres when the connection drops.
+pub async fn listen_for_notifications(pool: PgPool, cache: &Cache, events: &Events) {
+    let channels: Vec<&str> = cache::CHANNELS
+        .iter()
+        .map(|(channel, _)| *channel)
+        .chain([events::CHANNEL])
+        .collect();
+
+    loop {
+        let mut listener = match PgListener::connect_with(&pool).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("Error connecting notification listener: {e}");
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+        };
+
+        if let Err(e) = listener.listen_all(channels.iter().copied()).await {
+            error!("Error subscribing notification listener: {e}");
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            continue;
+        }
+
+        info!("Notification listener subscribed to {:?}", channels);
+        // Anything could have changed while we were not listening.
+        cache.clear();
+
+        loop {
+            match listener.try_recv().await {
+                Ok(Some(notification)) if notification.channel() == events::CHANNEL => {
+                    events.publish(notification.payload());
+                }
+                Ok(Some(notification)) => cache.invalidate_channel(notification.channel()),
+                Ok(None) => {
+                    warn!(
+                        "Notification listener lost its connection, invalidating all cache entries; changes may be missed"
+                    );
+                    cache.clear();
+                }
+                Err(e) => {
+                    error!("Notification listener error: {e}");
+                    cache.clear();
+                    break;
+                }
+            }
+        }
+    }
+}
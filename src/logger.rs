@@ -1,7 +1,13 @@
 use chrono::{DateTime, FixedOffset, Utc};
 use std::fmt;
+use tracing::info;
 use tracing_subscriber::fmt::format::Writer;
 use tracing_subscriber::fmt::time::FormatTime;
+use tracing_subscriber::EnvFilter;
+
+/// Filter used when `RUST_LOG` is unset or invalid: `info` for this crate,
+/// `warn` for dependencies.
+const DEFAULT_FILTER: &str = concat!(env!("CARGO_CRATE_NAME"), "=info,warn");
 
 struct UtcFormattedTime;
 
@@ -15,11 +21,20 @@ impl FormatTime for UtcFormattedTime {
 }
 
 pub fn init() {
+    // Loaded here as well so `RUST_LOG` can be set in the `.env` file.
+    dotenvy::dotenv().ok();
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let active = filter.to_string();
+
     tracing_subscriber::fmt()
+        .with_env_filter(filter)
         .pretty()
         .with_timer(UtcFormattedTime)
         .with_file(false)
         .with_line_number(false)
         .with_target(false)
         .init();
+
+    info!("📝 Filtro de logs: {active}");
 }
@@ -1,25 +1,39 @@
 use tracing::{error, info};
 
+mod audit;
+mod base_path;
+mod cache;
 mod config;
+mod events;
+mod extractors;
 mod handlers;
+mod ids;
+mod in_flight;
+mod jobs;
+mod listener;
 mod logger;
+mod middleware;
 mod models;
+mod queries;
+mod recent_ops;
 mod router;
 mod server;
+mod validations;
 
 #[tokio::main]
 async fn main() {
     println!("🌟 Printer Supplies API 🌟");
 
     logger::init();
-    match config::Config::init() {
-        Ok(_) => {
+    let config = match config::Config::init() {
+        Ok(config) => {
             info!("✅ Configurações carregadas!");
+            config
         }
         Err(e) => {
             error!("Não foi possível carregar as configurações: {:?}", e);
             std::process::exit(1);
         }
-    }
-    server::run().await.unwrap();
+    };
+    server::run(config).await.unwrap();
 }
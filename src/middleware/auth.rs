@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use sha2::{Digest, Sha256};
+use tracing::error;
+
+use crate::{
+    config::Scope, extractors::Principal, middleware::read_only, models::database::AppState,
+};
+
+/// Header carrying the client's API key.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Checks the request's API key against the scope its route needs.
+///
+/// Authentication is disabled while `API_KEYS` is empty. Otherwise a missing
+/// or unknown key is answered with `401` and a key without the required scope
+/// with `403`. The probes stay open so orchestrators need no credentials.
+pub async fn require_scope(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let keys = &state.config.api_keys;
+    let path = request.uri().path();
+
+    if keys.is_empty() || matches!(path, "/api/v1/health" | "/api/v1/ready") {
+        return next.run(request).await;
+    }
+
+    let required = required_scope(request.method(), path);
+    let Some(key) = client_key(request.headers()).and_then(|presented| {
+        let presented: [u8; 32] = Sha256::digest(presented.as_bytes()).into();
+        keys.iter()
+            .find(|key| digests_match(&key.digest, &presented))
+    }) else {
+        error!(
+            "Missing or invalid API key on {} {}.",
+            request.method(),
+            path
+        );
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json("Missing or invalid API key."),
+        )
+            .into_response();
+    };
+
+    if key.scope < required {
+        error!(
+            "API key lacks the `{}` scope for {} {}.",
+            required.as_str(),
+            request.method(),
+            path
+        );
+        let message = match required {
+            Scope::Read => "API key lacks the `read` scope.",
+            Scope::Write => "API key lacks the `write` scope.",
+            Scope::Admin => "API key lacks the `admin` scope.",
+        };
+        return (StatusCode::FORBIDDEN, Json(message)).into_response();
+    }
+
+    let principal = Principal {
+        id: key.id.clone(),
+        scope: key.scope,
+    };
+    request.extensions_mut().insert(principal);
+
+    next.run(request).await
+}
+
+/// Admin endpoints need `admin`, mutations need `write` and everything else,
+/// including the `POST` routes that [`read_only::only_reads`], needs `read`.
+fn required_scope(method: &Method, path: &str) -> Scope {
+    if path.starts_with("/api/v1/admin/") {
+        Scope::Admin
+    } else if read_only::only_reads(method, path) {
+        Scope::Read
+    } else {
+        Scope::Write
+    }
+}
+
+/// Compares every byte whatever the first difference, so the time taken says
+/// nothing about how much of a guessed key was right. Hashing first keeps the
+/// key lengths out of it too.
+fn digests_match(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn client_key(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+}
@@ -0,0 +1,108 @@
+use axum::{
+    body::{to_bytes, Body, HttpBody},
+    extract::Request,
+    http::{header, response::Parts, HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::Value;
+use tracing::error;
+
+/// Rejects mutating requests whose body is not declared as JSON.
+///
+/// Every body accepted by the API is JSON, including the `DELETE` payloads,
+/// so instead of letting each extractor fail on its own we answer with a
+/// single, explicit `415 Unsupported Media Type`.
+pub async fn require_json(request: Request, next: Next) -> Response {
+    let is_mutation = matches!(
+        *request.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    );
+
+    if is_mutation && has_body(request.headers()) && !is_json(request.headers()) {
+        error!(
+            "Unsupported Content-Type on {} {}.",
+            request.method(),
+            request.uri().path()
+        );
+        return (
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Json("Request body must be sent with `Content-Type: application/json`."),
+        )
+            .into_response();
+    }
+
+    next.run(request).await
+}
+
+/// Whether `headers` announce a non-empty body.
+pub fn has_body(headers: &HeaderMap) -> bool {
+    if headers.contains_key(header::TRANSFER_ENCODING) {
+        return true;
+    }
+
+    headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .is_some_and(|length| length > 0)
+}
+
+/// Whether `headers` declare a JSON body, including `+json` types such as
+/// JSON:API.
+pub fn is_json(headers: &HeaderMap) -> bool {
+    let Some(content_type) = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    mime == "application/json" || (mime.starts_with("application/") && mime.ends_with("+json"))
+}
+
+/// Reads a JSON response body, for middleware that rewrites responses.
+/// Responses that are not JSON come back unchanged as the error, and so do
+/// streamed ones, such as the catalog export, which are never buffered.
+pub async fn read_json(response: Response) -> Result<(Parts, Value), Response> {
+    if !is_json(response.headers()) {
+        return Err(response);
+    }
+    // Only bodies already in memory know their length.
+    let Some(length) = response.body().size_hint().exact() else {
+        return Err(response);
+    };
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, length as usize).await {
+        Ok(body) => body,
+        Err(e) => {
+            error!("Error reading response body: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json("Error writing response."),
+            )
+                .into_response());
+        }
+    };
+
+    match serde_json::from_slice(&body) {
+        Ok(value) => Ok((parts, value)),
+        Err(_) => Err(Response::from_parts(parts, Body::from(body))),
+    }
+}
+
+/// Puts a rewritten JSON body back in the response it was read from.
+pub fn write_json(parts: Parts, body: String) -> Response {
+    let mut response = Response::from_parts(parts, Body::from(body));
+    response.headers_mut().remove(header::CONTENT_LENGTH);
+    response
+}
@@ -0,0 +1,58 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use tracing::error;
+
+use crate::models::database::AppState;
+
+/// Point in time after which the client is no longer waiting for a response.
+#[derive(Clone, Copy)]
+pub struct Deadline(Instant);
+
+impl Deadline {
+    /// Deadline `timeout` from now, for work not tied to a request.
+    pub fn after(timeout: Duration) -> Self {
+        Deadline(Instant::now() + timeout)
+    }
+
+    /// Time left before the deadline, or zero once it has passed.
+    pub fn remaining(&self) -> Duration {
+        self.0.saturating_duration_since(Instant::now())
+    }
+}
+
+/// Bounds every request by `REQUEST_TIMEOUT_SECONDS`.
+///
+/// The deadline is also stored in the request extensions, so handlers can
+/// hand it to Postgres and have abandoned queries cancelled along with the
+/// request instead of running to completion.
+pub async fn enforce_deadline(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let timeout = Duration::from_secs(state.config.request_timeout_seconds);
+    request
+        .extensions_mut()
+        .insert(Deadline(Instant::now() + timeout));
+
+    let method = request.method().clone();
+    let path = request.uri().path().to_owned();
+
+    match tokio::time::timeout(timeout, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            error!("Request timed out: {} {}.", method, path);
+            (StatusCode::REQUEST_TIMEOUT, Json("Request timed out.")).into_response()
+        }
+    }
+}
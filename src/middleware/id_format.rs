@@ -0,0 +1,173 @@
+use axum::{
+    extract::Request,
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use super::content_type;
+
+/// How ids are written in responses, chosen with an `ids` parameter on the
+/// `Accept` media type, such as `Accept: application/json; ids=simple`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum IdFormat {
+    /// `67e55044-10b1-426f-9247-bb680e5fe0c8`, the default.
+    Hyphenated,
+    /// `67e5504410b1426f9247bb680e5fe0c8`
+    Simple,
+    /// `{67e55044-10b1-426f-9247-bb680e5fe0c8}`
+    Braced,
+}
+
+impl IdFormat {
+    pub fn requested(headers: &HeaderMap) -> Option<Self> {
+        headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .flat_map(|media| media.split(';').skip(1))
+            .find_map(|param| {
+                let (name, value) = param.split_once('=')?;
+                if !name.trim().eq_ignore_ascii_case("ids") {
+                    return None;
+                }
+                match value.trim().to_ascii_lowercase().as_str() {
+                    "hyphenated" => Some(IdFormat::Hyphenated),
+                    "simple" => Some(IdFormat::Simple),
+                    "braced" => Some(IdFormat::Braced),
+                    _ => None,
+                }
+            })
+    }
+
+    fn write(self, id: Uuid) -> String {
+        match self {
+            IdFormat::Hyphenated => id.hyphenated().to_string(),
+            IdFormat::Simple => id.simple().to_string(),
+            IdFormat::Braced => id.braced().to_string(),
+        }
+    }
+}
+
+/// Fields of the response models holding an id, a list of ids or a map keyed
+/// by id.
+const ID_FIELDS: &[&str] = &[
+    "id",
+    "ids",
+    "alternatives",
+    "brand",
+    "brands",
+    "drum",
+    "drum_id",
+    "drums",
+    "from",
+    "into",
+    "not_found",
+    "other",
+    "printer",
+    "printer_id",
+    "printers_missing_brand",
+    "printers_missing_drum",
+    "printers_missing_toner",
+    "retired",
+    "root",
+    "schedule_id",
+    "source",
+    "target",
+    "to",
+    "toner",
+    "toner_id",
+    "toners",
+];
+
+/// Rewrites the ids of JSON responses in the format asked for in `Accept`.
+///
+/// Ids are accepted in any of the formats on input, so clients can send back
+/// what they received. A bare id body and the hyphenated UUIDs under the
+/// fields in `ID_FIELDS` are rewritten; other strings, such as names that
+/// happen to look like a UUID, are left alone.
+pub async fn format_ids(request: Request, next: Next) -> Response {
+    let format = IdFormat::requested(request.headers());
+    let response = next.run(request).await;
+
+    let Some(format) = format.filter(|format| *format != IdFormat::Hyphenated) else {
+        return response;
+    };
+    let (parts, mut value) = match content_type::read_json(response).await {
+        Ok(json) => json,
+        Err(response) => return response,
+    };
+
+    rewrite(&mut value, format);
+    content_type::write_json(parts, value.to_string())
+}
+
+/// Rewrites the ids of a response value, also used for the `GET /events`
+/// payloads, which this middleware cannot read.
+pub fn rewrite(value: &mut Value, format: IdFormat) {
+    match value {
+        // Creates answer with the bare id.
+        Value::String(_) => rewrite_ids(value, format),
+        _ => rewrite_fields(value, format),
+    }
+}
+
+/// Walks `value` for `ID_FIELDS`.
+fn rewrite_fields(value: &mut Value, format: IdFormat) {
+    match value {
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| rewrite_fields(item, format)),
+        Value::Object(object) => {
+            for (key, item) in object.iter_mut() {
+                if ID_FIELDS.contains(&key.as_str()) {
+                    rewrite_ids(item, format);
+                } else {
+                    rewrite_fields(item, format);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Rewrites the value of an id field: an id, a list of ids or a map keyed by
+/// id. Nested objects, such as the rows of a `brands` list, are walked for
+/// their own id fields.
+fn rewrite_ids(value: &mut Value, format: IdFormat) {
+    match value {
+        Value::String(text) => {
+            if let Some(id) = hyphenated_id(text) {
+                *text = format.write(id);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| match item {
+            Value::String(_) => rewrite_ids(item, format),
+            _ => rewrite_fields(item, format),
+        }),
+        Value::Object(object) => {
+            let entries = std::mem::take(object);
+            *object = entries
+                .into_iter()
+                .map(|(key, mut item)| {
+                    rewrite_fields(&mut item, format);
+                    let key = hyphenated_id(&key).map_or(key, |id| format.write(id));
+                    (key, item)
+                })
+                .collect::<Map<String, Value>>();
+        }
+        _ => {}
+    }
+}
+
+/// Only the canonical hyphenated form is taken for an id, so other strings
+/// that happen to parse as a UUID are left alone.
+fn hyphenated_id(text: &str) -> Option<Uuid> {
+    if text.len() != 36 {
+        return None;
+    }
+    Uuid::try_parse(text).ok()
+}
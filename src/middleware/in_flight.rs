@@ -0,0 +1,87 @@
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use http_body::{Frame, SizeHint};
+use tracing::warn;
+
+use crate::{in_flight::Admission, models::database::AppState};
+
+/// Probes and the status page are served whatever the load, so an overloaded
+/// instance is not restarted by its orchestrator and stays observable.
+const ALWAYS_SERVED: &[&str] = &["/api/v1/health", "/api/v1/ready", "/api/v1/status"];
+
+/// Sheds load with `503` once `MAX_CONCURRENT_REQUESTS` requests are in
+/// flight, rather than queueing them on the database pool. A request stays
+/// counted until its body is sent, so a streamed export counts while it runs.
+pub async fn limit_concurrency(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let admission = if ALWAYS_SERVED.contains(&request.uri().path()) {
+        state.in_flight.admit()
+    } else {
+        match state.in_flight.try_admit() {
+            Some(admission) => admission,
+            None => {
+                warn!(
+                    "Request shed: {} requests in flight.",
+                    state.in_flight.count()
+                );
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    [(header::RETRY_AFTER, "1")],
+                    Json("Server is busy, try again later."),
+                )
+                    .into_response();
+            }
+        }
+    };
+
+    let (parts, body) = next.run(request).await.into_parts();
+    Response::from_parts(
+        parts,
+        Body::new(CountedBody {
+            body,
+            _admission: admission,
+        }),
+    )
+}
+
+/// A response body that holds its request's admission until it is dropped,
+/// once sent or when the client goes away.
+struct CountedBody {
+    body: Body,
+    _admission: Admission,
+}
+
+impl HttpBody for CountedBody {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, axum::Error>>> {
+        Pin::new(&mut self.body).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
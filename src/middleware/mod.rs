@@ -0,0 +1,8 @@
+pub mod auth;
+pub mod content_type;
+pub mod deadline;
+pub mod id_format;
+pub mod in_flight;
+pub mod pretty;
+pub mod read_only;
+pub mod recent_ops;
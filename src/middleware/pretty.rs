@@ -0,0 +1,25 @@
+use axum::{extract::Request, middleware::Next, response::Response};
+use url::form_urlencoded;
+
+use super::content_type;
+
+/// Indents JSON responses when the request carries `?pretty=true`, for
+/// reading them by hand. Responses stay minified otherwise.
+pub async fn pretty_print(request: Request, next: Next) -> Response {
+    let pretty = request.uri().query().is_some_and(|query| {
+        form_urlencoded::parse(query.as_bytes())
+            .any(|(key, value)| key == "pretty" && matches!(value.as_ref(), "" | "true" | "1"))
+    });
+    let response = next.run(request).await;
+    if !pretty {
+        return response;
+    }
+
+    match content_type::read_json(response).await {
+        Ok((parts, value)) => {
+            let body = serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string());
+            content_type::write_json(parts, body)
+        }
+        Err(response) => response,
+    }
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use tracing::error;
+
+use crate::models::database::AppState;
+
+/// `POST` routes that only read, taking their input as a body: the `/exists`
+/// lookup and the printer compatibility check.
+const READ_POSTS: &[&str] = &["/api/v1/exists", "/api/v1/printers/validate-compatibility"];
+
+/// Whether a request only reads, which keeps it available in read-only mode
+/// and within reach of `read` API keys.
+pub fn only_reads(method: &Method, path: &str) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+        || (*method == Method::POST && READ_POSTS.contains(&path))
+}
+
+/// Refuses every mutation with `403` while `READ_ONLY` is set, for demo
+/// deployments that serve the data as it is. Requests that [`only_reads`]
+/// stay available.
+pub async fn reject_mutations(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !state.config.read_only || only_reads(request.method(), request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    error!(
+        "Read-only mode refused {} {}.",
+        request.method(),
+        request.uri().path()
+    );
+    (StatusCode::FORBIDDEN, Json("The API is in read-only mode.")).into_response()
+}
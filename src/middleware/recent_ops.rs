@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use chrono::Utc;
+
+use crate::{models::database::AppState, recent_ops::Operation};
+
+/// Records every mutating request with the status it was answered with,
+/// including the ones refused by authentication.
+pub async fn record_mutations(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    ) {
+        return next.run(request).await;
+    }
+
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let response = next.run(request).await;
+
+    state.recent_ops.record(Operation {
+        method,
+        path,
+        status: response.status().as_u16(),
+        at: Utc::now(),
+    });
+    response
+}
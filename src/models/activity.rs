@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use super::timestamp;
+
+#[derive(Deserialize)]
+pub struct ActivityParams {
+    pub since: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+/// A brand, printer, toner or drum that was recently created or updated.
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct ActivityEntry {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub id: Uuid,
+    pub name: String,
+    pub action: String,
+    #[serde(serialize_with = "timestamp::serialize")]
+    pub created_at: DateTime<Utc>,
+    #[serde(serialize_with = "timestamp::serialize")]
+    pub updated_at: DateTime<Utc>,
+}
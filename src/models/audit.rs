@@ -0,0 +1,42 @@
+use serde::Serialize;
+use sqlx::{postgres::PgRow, prelude::FromRow};
+
+use super::jsonapi::Resource;
+
+/// Keys that created and last changed an entity, `None` for changes made
+/// without authentication.
+#[derive(Serialize, FromRow)]
+pub struct Authorship {
+    pub created_by: Option<String>,
+    pub updated_by: Option<String>,
+}
+
+/// An entity with its authorship, which detail responses only include for
+/// admins.
+#[derive(Serialize)]
+pub struct Audited<T> {
+    #[serde(flatten)]
+    pub item: T,
+    #[serde(flatten)]
+    pub authorship: Option<Authorship>,
+}
+
+impl<T> Audited<T>
+where
+    T: for<'r> FromRow<'r, PgRow>,
+{
+    /// Reads the entity from `row`, with its authorship when `with_authorship`.
+    pub fn from_row(row: &PgRow, with_authorship: bool) -> Result<Self, sqlx::Error> {
+        Ok(Audited {
+            item: T::from_row(row)?,
+            authorship: with_authorship
+                .then(|| Authorship::from_row(row))
+                .transpose()?,
+        })
+    }
+}
+
+impl<T: Resource> Resource for Audited<T> {
+    const TYPE: &'static str = T::TYPE;
+    const RELATIONSHIPS: &'static [(&'static str, &'static str)] = T::RELATIONSHIPS;
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    brand::Brand,
+    printer::Printer,
+    supplies::{drum::Drum, toner::Toner},
+};
+
+/// Catalog snapshot, as written by `GET /admin/export`.
+#[derive(Deserialize, Serialize)]
+pub struct Backup {
+    #[serde(default)]
+    pub brands: Vec<Brand>,
+    #[serde(default)]
+    pub toners: Vec<Toner>,
+    #[serde(default)]
+    pub drums: Vec<Drum>,
+    #[serde(default)]
+    pub printers: Vec<Printer>,
+    /// Written last by an export that stopped halfway through.
+    #[serde(default, skip_serializing)]
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ImportParams {
+    /// Replace rows whose id already exists instead of skipping them.
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[derive(Default, Serialize)]
+pub struct ImportCounts {
+    pub imported: u64,
+    /// Rows left untouched because their id already exists.
+    pub skipped: u64,
+}
+
+/// A row the database refused, such as a brand whose name is already taken
+/// under another id.
+#[derive(Serialize)]
+pub struct ImportConflict {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub id: Uuid,
+    pub reason: String,
+}
+
+#[derive(Default, Serialize)]
+pub struct ImportReport {
+    pub brands: ImportCounts,
+    pub toners: ImportCounts,
+    pub drums: ImportCounts,
+    pub printers: ImportCounts,
+    pub conflicts: Vec<ImportConflict>,
+}
@@ -1,29 +1,164 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
 use uuid::Uuid;
 
+use crate::ids;
+
+use super::{
+    audit::Audited,
+    jsonapi::Resource,
+    printer::Printer,
+    supplies::{drum::Drum, toner::Toner},
+};
+
 #[derive(Deserialize, Serialize, FromRow)]
 pub struct Brand {
     pub id: Uuid,
     pub name: String,
+    pub logo_url: Option<String>,
+    pub printer_count: i32,
+    /// ISO 3166-1 alpha-2 code of the manufacturer's country.
+    pub country_code: Option<String>,
+    pub website_url: Option<String>,
+    pub support_email: Option<String>,
+    pub support_phone: Option<String>,
+}
+
+/// Fields that may be requested with `?fields=`.
+pub const BRAND_FIELDS: &[&str] = &[
+    "id",
+    "name",
+    "logo_url",
+    "printer_count",
+    "country_code",
+    "website_url",
+    "support_email",
+    "support_phone",
+    "created_by",
+    "updated_by",
+];
+
+impl Resource for Brand {
+    const TYPE: &'static str = "brands";
 }
 
 impl Brand {
-    pub fn new(name: &str) -> Self {
+    pub fn new(name: &str, logo_url: Option<&str>, country_code: Option<&str>) -> Self {
         Brand {
-            id: Uuid::new_v4(),
+            id: ids::new_id(),
             name: String::from(name),
+            logo_url: logo_url.map(String::from),
+            printer_count: 0,
+            country_code: country_code.map(normalize_country_code),
+            website_url: None,
+            support_email: None,
+            support_phone: None,
         }
     }
 }
 
-#[derive(Deserialize, Serialize)]
+/// Country codes are accepted in any case and stored in uppercase.
+pub fn normalize_country_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CreateBrandRequest {
+    #[schemars(length(
+        min = "crate::validations::NAME_MIN_LENGTH",
+        max = "crate::validations::NAME_MAX_LENGTH"
+    ))]
     pub name: String,
+    #[schemars(length(max = "crate::validations::MAX_URL_LENGTH"), url)]
+    pub logo_url: Option<String>,
+    /// ISO 3166-1 alpha-2 code, in any case.
+    #[schemars(length(equal = 2))]
+    pub country_code: Option<String>,
+    #[schemars(length(max = "crate::validations::MAX_URL_LENGTH"), url)]
+    pub website_url: Option<String>,
+    #[schemars(length(max = "crate::validations::MAX_EMAIL_LENGTH"), email)]
+    pub support_email: Option<String>,
+    /// 7 to 15 digits, optionally led by `+` and grouped with spaces, dots,
+    /// dashes or parentheses.
+    #[schemars(length(max = "crate::validations::MAX_PHONE_LENGTH"))]
+    pub support_phone: Option<String>,
 }
 
 #[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct UpdateBrandRequest {
+    #[serde(deserialize_with = "ids::deserialize")]
     pub id: Uuid,
     pub name: String,
+    pub logo_url: Option<String>,
+    pub country_code: Option<String>,
+    pub website_url: Option<String>,
+    pub support_email: Option<String>,
+    pub support_phone: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpsertBrandRequest {
+    pub name: String,
+    pub logo_url: Option<String>,
+    pub country_code: Option<String>,
+    pub website_url: Option<String>,
+    pub support_email: Option<String>,
+    pub support_phone: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct BrandCountryCount {
+    pub country_code: Option<String>,
+    pub count: i64,
+}
+
+#[derive(Deserialize)]
+pub struct NameAvailabilityParams {
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct NameAvailability {
+    pub available: bool,
+    pub normalized: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct MergeBrandsRequest {
+    #[serde(deserialize_with = "ids::deserialize")]
+    pub from: Uuid,
+    #[serde(deserialize_with = "ids::deserialize")]
+    pub into: Uuid,
+}
+
+/// Rows repointed from the merged brand to the one it was merged into.
+#[derive(Serialize)]
+pub struct MergeBrandsResult {
+    pub printers: u64,
+    pub toners: u64,
+}
+
+/// A brand with everything related to it, for the brand detail page.
+#[derive(Serialize)]
+pub struct BrandDetail {
+    #[serde(flatten)]
+    pub brand: Audited<Brand>,
+    pub printers: Vec<Printer>,
+    /// Toners of the brand and toners its printers use.
+    pub toners: Vec<Toner>,
+    /// Drums its printers use, as drums have no brand of their own.
+    pub drums: Vec<Drum>,
+    pub totals: BrandDetailTotals,
+}
+
+/// Full sizes of the lists of a `BrandDetail`, which are capped.
+#[derive(Serialize, FromRow)]
+pub struct BrandDetailTotals {
+    pub printers: i64,
+    pub toners: i64,
+    pub drums: i64,
 }
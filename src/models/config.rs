@@ -0,0 +1,57 @@
+use serde::Serialize;
+
+use crate::config::{IdFormat, PrinterNameScope, Scope, ValidationMode};
+
+/// API key as reported by `GET /admin/config`: its actor id and scope, never
+/// the key itself.
+#[derive(Serialize)]
+pub struct EffectiveApiKey {
+    pub id: String,
+    pub scope: Scope,
+}
+
+/// Configuration the running instance loaded, with secrets redacted.
+#[derive(Serialize)]
+pub struct EffectiveConfig {
+    pub bind_address: Option<String>,
+    /// `DATABASE_URL` with its password replaced by `REDACTED`.
+    pub database_url: Option<String>,
+    pub db_max_connections: u32,
+    pub db_ssl_mode: Option<&'static str>,
+    pub db_ssl_root_cert: Option<String>,
+    pub db_connect_max_attempts: u32,
+    pub db_connect_retry_delay_ms: u64,
+    pub slow_query_threshold_ms: u64,
+    pub default_page_size: i64,
+    pub max_page_size: i64,
+    pub max_offset: i64,
+    pub search_max_results: i64,
+    pub request_timeout_seconds: u64,
+    pub max_concurrent_requests: usize,
+    pub max_body_bytes: usize,
+    pub max_bulk_body_bytes: usize,
+    pub read_only: bool,
+    pub idempotent_deletes: bool,
+    pub base_path: Option<String>,
+    pub base_path_nest: bool,
+    pub id_format: IdFormat,
+    pub require_uuid_v4: bool,
+    pub toner_brand_validation: ValidationMode,
+    pub strict_validation: bool,
+    pub name_pattern: Option<String>,
+    pub printer_name_scope: PrinterNameScope,
+    pub printer_model_min_length: usize,
+    pub printer_model_max_length: usize,
+    pub printer_departments: Vec<String>,
+    pub printer_max_documents: i64,
+    pub printer_photo_max_bytes: usize,
+    pub printer_qr_base_url: Option<String>,
+    pub printer_draft_ttl_days: i32,
+    pub max_toners_per_brand: Option<i64>,
+    pub reorder_lookback_days: i32,
+    pub reorder_coverage_days: i32,
+    pub recent_ops_capacity: usize,
+    pub count_reconcile_interval_seconds: u64,
+    pub snapshot_interval_seconds: u64,
+    pub api_keys: Vec<EffectiveApiKey>,
+}
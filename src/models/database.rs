@@ -1,5 +1,36 @@
-use sqlx::PgPool;
+use std::sync::Arc;
+
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    cache::Cache, config::Config, events::Events, in_flight::InFlight,
+    middleware::deadline::Deadline, recent_ops::RecentOps,
+};
 
 pub struct AppState {
     pub db: PgPool,
+    pub config: Config,
+    pub cache: Cache,
+    pub recent_ops: RecentOps,
+    pub in_flight: Arc<InFlight>,
+    pub events: Events,
+}
+
+impl AppState {
+    /// Begins a transaction whose statements are cancelled by Postgres once
+    /// the request `deadline` passes.
+    pub async fn begin(
+        &self,
+        deadline: Deadline,
+    ) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        // `SET` does not accept bind parameters; a zero timeout would disable it.
+        let timeout = deadline.remaining().as_millis().max(1);
+        sqlx::query(&format!("SET LOCAL statement_timeout = {timeout};"))
+            .execute(&mut *tx)
+            .await?;
+
+        Ok(tx)
+    }
 }
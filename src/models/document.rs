@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use super::timestamp;
+
+/// A manual, driver or any other link attached to a printer.
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct PrinterDocument {
+    pub id: Uuid,
+    pub printer_id: Uuid,
+    pub label: String,
+    pub url: String,
+    #[serde(serialize_with = "timestamp::serialize")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DocumentRequest {
+    pub label: String,
+    pub url: String,
+}
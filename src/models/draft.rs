@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use super::timestamp;
+
+/// A printer being filled in over several steps. Every field is optional and
+/// unvalidated until the draft is finalized.
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct PrinterDraft {
+    pub id: Uuid,
+    pub name: Option<String>,
+    pub model: Option<String>,
+    pub brand: Option<String>,
+    pub toner: Option<String>,
+    pub drum: Option<String>,
+    pub owner_department: Option<String>,
+    /// Saving the draft again pushes its expiry back.
+    #[serde(serialize_with = "timestamp::serialize")]
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrinterDraftRequest {
+    pub name: Option<String>,
+    pub model: Option<String>,
+    pub brand: Option<String>,
+    pub toner: Option<String>,
+    pub drum: Option<String>,
+    pub owner_department: Option<String>,
+}
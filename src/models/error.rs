@@ -0,0 +1,113 @@
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+/// Stable identifiers for validation failures, which clients can map to
+/// localized messages.
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    NameEmpty,
+    NameTooShort,
+    NameTooLong,
+    NameNotAllowed,
+    DuplicateName,
+    BrandTonerLimit,
+    DuplicateToner,
+}
+
+/// Error body returned by the handlers.
+///
+/// Plain errors keep serializing as a bare string. Validation errors become
+/// an object with a `code`, the English `message` as a fallback and the
+/// `params` needed to render a localized message.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum ApiError {
+    Message(&'static str),
+    Validation {
+        code: ErrorCode,
+        message: &'static str,
+        #[serde(skip_serializing_if = "Map::is_empty")]
+        params: Map<String, Value>,
+    },
+}
+
+impl ApiError {
+    /// The English message, for logs and plain text reports.
+    pub fn message(&self) -> &'static str {
+        match self {
+            ApiError::Message(message) | ApiError::Validation { message, .. } => message,
+        }
+    }
+
+    fn validation(code: ErrorCode, message: &'static str, params: Value) -> Self {
+        let params = match params {
+            Value::Object(params) => params,
+            _ => Map::new(),
+        };
+        ApiError::Validation {
+            code,
+            message,
+            params,
+        }
+    }
+
+    pub fn name_empty(message: &'static str) -> Self {
+        Self::validation(ErrorCode::NameEmpty, message, Value::Null)
+    }
+
+    pub fn name_too_short(message: &'static str, min: usize, actual: usize) -> Self {
+        Self::validation(
+            ErrorCode::NameTooShort,
+            message,
+            json!({ "min": min, "actual": actual }),
+        )
+    }
+
+    pub fn name_too_long(message: &'static str, max: usize, actual: usize) -> Self {
+        Self::validation(
+            ErrorCode::NameTooLong,
+            message,
+            json!({ "max": max, "actual": actual }),
+        )
+    }
+
+    /// `pattern` is the configured expression names must match, if any.
+    pub fn name_not_allowed(message: &'static str, pattern: Option<&str>) -> Self {
+        let params = pattern.map_or(Value::Null, |pattern| json!({ "pattern": pattern }));
+        Self::validation(ErrorCode::NameNotAllowed, message, params)
+    }
+
+    pub fn duplicate_name(message: &'static str) -> Self {
+        Self::validation(ErrorCode::DuplicateName, message, Value::Null)
+    }
+
+    /// `brand`, `name` and `color` are those of the toner already stored.
+    pub fn duplicate_toner(
+        message: &'static str,
+        brand: Option<Uuid>,
+        name: &str,
+        color: Option<&str>,
+    ) -> Self {
+        Self::validation(
+            ErrorCode::DuplicateToner,
+            message,
+            json!({ "brand": brand, "name": name, "color": color }),
+        )
+    }
+
+    pub fn brand_toner_limit(message: &'static str, limit: i64, count: i64) -> Self {
+        Self::validation(
+            ErrorCode::BrandTonerLimit,
+            message,
+            json!({ "limit": limit, "count": count }),
+        )
+    }
+}
+
+impl From<&'static str> for ApiError {
+    fn from(message: &'static str) -> Self {
+        ApiError::Message(message)
+    }
+}
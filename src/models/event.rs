@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A brand, printer, toner or drum that was created, updated or deleted, as
+/// streamed by `GET /events`.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct ChangeEvent {
+    pub entity: String,
+    pub id: Uuid,
+    /// `create`, `update` or `delete`.
+    pub operation: String,
+}
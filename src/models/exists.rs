@@ -0,0 +1,25 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::ids;
+
+#[derive(Deserialize, Serialize)]
+pub struct ExistsRequest {
+    #[serde(default, deserialize_with = "ids::deserialize_all")]
+    pub brands: Vec<Uuid>,
+    #[serde(default, deserialize_with = "ids::deserialize_all")]
+    pub toners: Vec<Uuid>,
+    #[serde(default, deserialize_with = "ids::deserialize_all")]
+    pub drums: Vec<Uuid>,
+}
+
+/// Whether each requested id exists, per table. Ordered maps keep the output
+/// the same whatever order the ids were asked in.
+#[derive(Deserialize, Serialize)]
+pub struct ExistsResponse {
+    pub brands: BTreeMap<Uuid, bool>,
+    pub toners: BTreeMap<Uuid, bool>,
+    pub drums: BTreeMap<Uuid, bool>,
+}
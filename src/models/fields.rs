@@ -0,0 +1,58 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+/// `?fields=` query parameter: a comma-separated list of the fields to return.
+#[derive(Deserialize)]
+pub struct FieldsParams {
+    pub fields: Option<String>,
+}
+
+impl FieldsParams {
+    /// Checks the requested fields against the resource's allow-list.
+    /// `None` means every field was requested.
+    pub fn resolve(&self, allowed: &[&str]) -> Result<Option<FieldSet>, String> {
+        let Some(fields) = &self.fields else {
+            return Ok(None);
+        };
+
+        let fields: Vec<String> = fields
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .map(String::from)
+            .collect();
+
+        if fields.is_empty() {
+            return Err(String::from("No fields requested."));
+        }
+
+        if let Some(unknown) = fields
+            .iter()
+            .find(|field| !allowed.contains(&field.as_str()))
+        {
+            return Err(format!("Unknown field `{unknown}`."));
+        }
+
+        Ok(Some(FieldSet(fields)))
+    }
+}
+
+/// A validated sparse fieldset.
+pub struct FieldSet(Vec<String>);
+
+impl FieldSet {
+    /// Drops the fields that were not requested from a serialized resource.
+    /// JSON:API resources keep their `type` and `id` and have their
+    /// `attributes` filtered instead.
+    pub fn retain(&self, resource: &mut Value) {
+        let object = if resource.get("attributes").is_some() {
+            &mut resource["attributes"]
+        } else {
+            resource
+        };
+
+        if let Value::Object(object) = object {
+            object.retain(|key, _| self.0.iter().any(|field| field == key));
+        }
+    }
+}
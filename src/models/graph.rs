@@ -0,0 +1,47 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    Printer,
+    Brand,
+    Toner,
+    Drum,
+    Tag,
+}
+
+/// What an edge stands for: the brand, toner or drum a printer uses, a toner
+/// interchangeable with the printer's, or a tag on the printer.
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Relation {
+    Brand,
+    Toner,
+    Drum,
+    Compatible,
+    Tag,
+}
+
+#[derive(Serialize)]
+pub struct GraphNode {
+    pub id: Uuid,
+    pub kind: NodeKind,
+    pub label: String,
+}
+
+#[derive(Serialize)]
+pub struct GraphEdge {
+    pub source: Uuid,
+    pub target: Uuid,
+    pub relation: Relation,
+}
+
+/// A printer and what it is related to, as nodes and edges for a graph
+/// renderer. `root` is the printer's node.
+#[derive(Serialize)]
+pub struct PrinterGraph {
+    pub root: Uuid,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
@@ -0,0 +1,11 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Printers whose brand, toner or drum reference points to a missing row,
+/// grouped by the broken reference.
+#[derive(Serialize)]
+pub struct IntegrityReport {
+    pub printers_missing_brand: Vec<Uuid>,
+    pub printers_missing_toner: Vec<Uuid>,
+    pub printers_missing_drum: Vec<Uuid>,
+}
@@ -0,0 +1,122 @@
+use axum::{
+    http::{header, HeaderMap, HeaderValue},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use super::pagination::{Links, Page};
+
+/// Media type of the JSON:API specification.
+pub const MEDIA_TYPE: &str = "application/vnd.api+json";
+
+/// A model that can be rendered as a JSON:API resource object.
+///
+/// Every serialized field other than `id` becomes an attribute, except the
+/// foreign keys listed in `RELATIONSHIPS`, which become resource linkage.
+pub trait Resource: Serialize {
+    const TYPE: &'static str;
+    /// Foreign key fields, as `(field, related resource type)`.
+    const RELATIONSHIPS: &'static [(&'static str, &'static str)] = &[];
+}
+
+#[derive(Serialize)]
+pub struct ResourceObject {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub id: Value,
+    pub attributes: Map<String, Value>,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub relationships: Map<String, Value>,
+}
+
+impl ResourceObject {
+    pub fn new<T: Resource>(item: &T) -> Self {
+        let mut attributes = match serde_json::to_value(item) {
+            Ok(Value::Object(fields)) => fields,
+            _ => Map::new(),
+        };
+        let id = attributes.remove("id").unwrap_or(Value::Null);
+
+        let mut relationships = Map::new();
+        for (field, kind) in T::RELATIONSHIPS {
+            if let Some(related) = attributes.remove(*field) {
+                let data = match related {
+                    Value::Null => Value::Null,
+                    id => json!({ "type": kind, "id": id }),
+                };
+                relationships.insert(String::from(*field), json!({ "data": data }));
+            }
+        }
+
+        ResourceObject {
+            kind: T::TYPE,
+            id,
+            attributes,
+            relationships,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Document<D> {
+    pub data: D,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<Links>,
+}
+
+impl Document<ResourceObject> {
+    pub fn single<T: Resource>(item: &T) -> Self {
+        Document {
+            data: ResourceObject::new(item),
+            meta: None,
+            links: None,
+        }
+    }
+}
+
+impl Document<Vec<ResourceObject>> {
+    /// Converts a page of results, moving the totals into `meta`.
+    pub fn page<T: Resource>(page: Page<T>) -> Self {
+        Document {
+            data: page.data.iter().map(ResourceObject::new).collect(),
+            meta: Some(json!({
+                "total": page.total,
+                "limit": page.limit,
+                "offset": page.offset,
+            })),
+            links: Some(page.links),
+        }
+    }
+}
+
+/// Whether the client asked for JSON:API through the `Accept` header.
+pub fn requested(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|media| {
+            media
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .eq_ignore_ascii_case(MEDIA_TYPE)
+        })
+}
+
+/// Answers with `body`, labelled as JSON:API when the client asked for it.
+pub fn respond(json_api: bool, body: Value) -> Response {
+    let mut response = Json(body).into_response();
+    if json_api {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(MEDIA_TYPE));
+    }
+    response
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use crate::ids;
+
+use super::timestamp;
+
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct MaintenanceSchedule {
+    pub id: Uuid,
+    pub printer_id: Uuid,
+    pub interval_days: i32,
+    #[serde(serialize_with = "timestamp::option::serialize")]
+    pub last_done_at: Option<DateTime<Utc>>,
+}
+
+impl MaintenanceSchedule {
+    pub fn new(printer_id: Uuid, interval_days: i32, last_done_at: Option<DateTime<Utc>>) -> Self {
+        MaintenanceSchedule {
+            id: ids::new_id(),
+            printer_id,
+            interval_days,
+            last_done_at,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateMaintenanceScheduleRequest {
+    #[serde(deserialize_with = "ids::deserialize")]
+    pub printer_id: Uuid,
+    pub interval_days: i32,
+    pub last_done_at: Option<DateTime<Utc>>,
+}
+
+/// A schedule whose next maintenance date has passed. `due_at` is `None` for
+/// schedules that were never done.
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct DueMaintenance {
+    pub schedule_id: Uuid,
+    pub printer_id: Uuid,
+    pub printer_name: String,
+    pub interval_days: i32,
+    #[serde(serialize_with = "timestamp::option::serialize")]
+    pub last_done_at: Option<DateTime<Utc>>,
+    #[serde(serialize_with = "timestamp::option::serialize")]
+    pub due_at: Option<DateTime<Utc>>,
+}
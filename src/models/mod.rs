@@ -1,14 +1,45 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::ids;
+
+pub mod activity;
+pub mod audit;
+pub mod backup;
 pub mod brand;
+pub mod config;
 pub mod database;
+pub mod document;
+pub mod draft;
+pub mod error;
+pub mod event;
+pub mod exists;
+pub mod fields;
+pub mod graph;
+pub mod integrity;
+pub mod jsonapi;
+pub mod maintenance;
 pub mod movement;
+pub mod pagination;
+pub mod photo;
 pub mod printer;
+pub mod search;
+pub mod snapshot;
 pub mod status;
 pub mod supplies;
+pub mod tag;
+pub mod timestamp;
 
 #[derive(Deserialize, Serialize)]
 pub struct DeleteRequest {
+    #[serde(deserialize_with = "ids::deserialize")]
+    pub id: Uuid,
+}
+
+/// Id of a created or updated resource, along with any non-blocking
+/// validation warnings raised while saving it.
+#[derive(Deserialize, Serialize)]
+pub struct MutationResponse {
     pub id: Uuid,
+    pub warnings: Vec<String>,
 }
@@ -3,6 +3,10 @@ use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
 use uuid::Uuid;
 
+use crate::ids;
+
+use super::timestamp;
+
 #[derive(Deserialize, Serialize, FromRow)]
 pub struct Movement {
     pub id: Uuid,
@@ -10,6 +14,7 @@ pub struct Movement {
     pub toner_id: Option<Uuid>,
     pub drum_id: Option<Uuid>,
     pub quantity: i32,
+    #[serde(serialize_with = "timestamp::serialize")]
     pub created_at: DateTime<Utc>,
 }
 
@@ -21,7 +26,7 @@ impl Movement {
         quantity: i32,
     ) -> Self {
         Self {
-            id: Uuid::new_v4(),
+            id: ids::new_id(),
             printer_id,
             toner_id,
             drum_id,
@@ -32,22 +37,33 @@ impl Movement {
 }
 
 #[derive(Deserialize, Serialize, FromRow)]
+#[serde(deny_unknown_fields)]
 pub struct CreateTonerMovementRequest {
+    #[serde(default, deserialize_with = "ids::deserialize_option")]
     pub toner_id: Option<Uuid>,
     pub quantity: i32,
+    pub created_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Deserialize, Serialize, FromRow)]
+#[serde(deny_unknown_fields)]
 pub struct CreateDrumMovementRequest {
+    #[serde(default, deserialize_with = "ids::deserialize_option")]
     pub drum_id: Option<Uuid>,
     pub quantity: i32,
+    pub created_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Deserialize, Serialize, FromRow)]
+#[serde(deny_unknown_fields)]
 pub struct UpdateMovementRequest {
+    #[serde(deserialize_with = "ids::deserialize")]
     pub id: Uuid,
+    #[serde(deserialize_with = "ids::deserialize")]
     pub printer_id: Uuid,
+    #[serde(default, deserialize_with = "ids::deserialize_option")]
     pub toner_id: Option<Uuid>,
+    #[serde(default, deserialize_with = "ids::deserialize_option")]
     pub drum_id: Option<Uuid>,
     pub quantity: i32,
 }
@@ -0,0 +1,96 @@
+use axum::http::Uri;
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use crate::{base_path, config::Config};
+
+#[derive(Deserialize)]
+pub struct PaginationParams {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+impl PaginationParams {
+    /// Resolves the requested window into `(limit, offset)`, applying the
+    /// configured default page size and capping the limit at the maximum.
+    /// Offsets beyond `MAX_OFFSET` are rejected rather than scanned through,
+    /// pointing the client at cursor pagination. No list offers a cursor yet,
+    /// so filters are the way to reach those rows meanwhile.
+    pub fn resolve(&self, config: &Config) -> Result<(i64, i64), &'static str> {
+        let limit = self.limit.unwrap_or(config.default_page_size);
+        let offset = self.offset.unwrap_or(0);
+
+        if limit < 1 {
+            return Err("Limit must be greater than zero.");
+        }
+
+        if offset < 0 {
+            return Err("Offset cannot be negative.");
+        }
+
+        if offset > config.max_offset {
+            return Err(
+                "Offset is too large. Use cursor pagination instead of paging this deep; until it is available, narrow the results with filters.",
+            );
+        }
+
+        Ok((limit.min(config.max_page_size), offset))
+    }
+}
+
+#[derive(Serialize)]
+pub struct Links {
+    pub first: String,
+    pub prev: Option<String>,
+    pub next: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    #[serde(rename = "_links")]
+    pub links: Links,
+}
+
+impl<T> Page<T> {
+    /// Wraps a page of results, building the navigation links from the URI the
+    /// client actually requested so nested mounts and extra query parameters
+    /// are preserved, behind the configured `BASE_PATH`.
+    pub fn new(data: Vec<T>, total: i64, limit: i64, offset: i64, uri: &Uri) -> Self {
+        let prev = (offset > 0).then(|| page_link(uri, limit, (offset - limit).max(0)));
+        let next = (offset + limit < total).then(|| page_link(uri, limit, offset + limit));
+
+        Page {
+            data,
+            total,
+            limit,
+            offset,
+            links: Links {
+                first: page_link(uri, limit, 0),
+                prev,
+                next,
+            },
+        }
+    }
+}
+
+fn page_link(uri: &Uri, limit: i64, offset: i64) -> String {
+    let mut query = form_urlencoded::Serializer::new(String::new());
+
+    if let Some(current) = uri.query() {
+        for (key, value) in form_urlencoded::parse(current.as_bytes()) {
+            if key != "limit" && key != "offset" {
+                query.append_pair(&key, &value);
+            }
+        }
+    }
+
+    query
+        .append_pair("limit", &limit.to_string())
+        .append_pair("offset", &offset.to_string());
+
+    format!("{}?{}", base_path::public(uri.path()), query.finish())
+}
@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use super::timestamp;
+
+/// The stored photo of a printer, without its bytes.
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct PrinterPhoto {
+    pub printer_id: Uuid,
+    pub content_type: String,
+    pub size: i32,
+    #[serde(serialize_with = "timestamp::serialize")]
+    pub uploaded_at: DateTime<Utc>,
+}
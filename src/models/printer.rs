@@ -1,7 +1,19 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
 use uuid::Uuid;
 
+use crate::ids;
+
+use super::{
+    audit::Audited,
+    document::PrinterDocument,
+    jsonapi::Resource,
+    tag::{self, TagMatch},
+    timestamp,
+};
+
 #[derive(Deserialize, Serialize, FromRow)]
 pub struct Printer {
     pub id: Uuid,
@@ -10,36 +22,457 @@ pub struct Printer {
     pub brand: Uuid,
     pub toner: Uuid,
     pub drum: Uuid,
+    pub owner_department: Option<String>,
+    /// Lifetime pages reported by the printer's meter.
+    pub page_count: i64,
+    #[serde(default)]
+    pub status: PrinterStatus,
+    #[serde(default, serialize_with = "timestamp::option::serialize")]
+    pub decommissioned_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub decommission_reason: Option<String>,
+    #[serde(default)]
+    pub acquisition_type: AcquisitionType,
+    /// Set only for leased printers.
+    #[serde(default)]
+    pub lease_end_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub connection_type: Option<ConnectionType>,
+}
+
+/// Lifecycle of a device: in service, out for a service window, or retired
+/// through the decommission workflow.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, sqlx::Type,
+)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum PrinterStatus {
+    #[default]
+    Active,
+    Maintenance,
+    Retired,
+}
+
+/// Whether a printer belongs to the fleet or is leased until its
+/// `lease_end_date`.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, sqlx::Type,
+)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum AcquisitionType {
+    #[default]
+    Owned,
+    Leased,
+}
+
+/// How a printer connects to the computers it serves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum ConnectionType {
+    Usb,
+    Ethernet,
+    Wifi,
+    Bluetooth,
+}
+
+impl Resource for Printer {
+    const TYPE: &'static str = "printers";
+    const RELATIONSHIPS: &'static [(&'static str, &'static str)] =
+        &[("brand", "brands"), ("toner", "toners"), ("drum", "drums")];
 }
 
 impl Printer {
-    pub fn new(name: &str, model: &str, brand: Uuid, toner: Uuid, drum: Uuid) -> Self {
+    /// A new printer, with omitted optional attributes set to their defaults:
+    /// no owner department, the `active` status and owned.
+    pub fn new(
+        name: &str,
+        model: &str,
+        brand: Uuid,
+        toner: Uuid,
+        drum: Uuid,
+        owner_department: Option<&str>,
+        status: Option<PrinterStatus>,
+    ) -> Self {
         Printer {
-            id: Uuid::new_v4(),
+            id: ids::new_id(),
             name: String::from(name),
             model: String::from(model),
             brand,
             toner,
             drum,
+            owner_department: owner_department.map(String::from),
+            page_count: 0,
+            status: status.unwrap_or_default(),
+            decommissioned_at: None,
+            decommission_reason: None,
+            acquisition_type: AcquisitionType::Owned,
+            lease_end_date: None,
+            connection_type: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct BrandPrinterCount {
+    pub brand: Uuid,
+    pub brand_name: String,
+    pub count: i64,
+}
+
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct DepartmentPrinterCount {
+    pub department: Option<String>,
+    pub count: i64,
+}
+
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct ConnectionPrinterCount {
+    pub connection_type: Option<ConnectionType>,
+    pub count: i64,
+}
+
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct PrinterModelCount {
+    pub model: String,
+    pub count: i64,
+}
+
+#[derive(Deserialize)]
+pub struct ExpiringLeaseParams {
+    pub within_days: Option<i32>,
+}
+
+/// A leased printer whose lease ends soon or has already ended. `days_left`
+/// is negative once the lease is over.
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct ExpiringLease {
+    pub id: Uuid,
+    pub name: String,
+    pub model: String,
+    pub owner_department: Option<String>,
+    pub lease_end_date: NaiveDate,
+    pub days_left: i32,
+}
+
+#[derive(Deserialize)]
+pub struct NearDuplicateParams {
+    /// Lowest similarity, above `0` and up to `1`, for two names to be near
+    /// duplicates.
+    pub threshold: Option<f32>,
+}
+
+/// Two printers with similar names and their trigram similarity.
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct SimilarPrinters {
+    pub printer: Uuid,
+    pub other: Uuid,
+    pub similarity: f32,
+}
+
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct PrinterName {
+    pub id: Uuid,
+    pub name: String,
+}
+
+/// Printers linked by similar names, directly or through other printers of
+/// the group, with the similarity of each linked pair.
+#[derive(Deserialize, Serialize)]
+pub struct NearDuplicateGroup {
+    pub printers: Vec<PrinterName>,
+    pub pairs: Vec<SimilarPrinters>,
+}
+
+#[derive(Deserialize)]
+pub struct PrinterModelFilter {
+    #[serde(default, deserialize_with = "ids::deserialize_option")]
+    pub brand: Option<Uuid>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DecommissionRequest {
+    pub reason: String,
+    /// Puts the toner installed in the printer back into stock.
+    #[serde(default)]
+    pub return_toner: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BrandSwapRequest {
+    #[serde(deserialize_with = "ids::deserialize")]
+    pub brand: Uuid,
+}
+
+/// A printer after a brand change, along with any non-blocking validation
+/// warnings raised by the new brand.
+#[derive(Serialize)]
+pub struct BrandSwapResponse {
+    #[serde(flatten)]
+    pub printer: Audited<Printer>,
+    pub warnings: Vec<String>,
+}
+
+/// A printer with the documents attached to it, for the printer detail view.
+#[derive(Serialize)]
+pub struct PrinterDetail {
+    #[serde(flatten)]
+    pub printer: Audited<Printer>,
+    pub documents: Vec<PrinterDocument>,
+}
+
+/// References chosen for a printer that is not created yet.
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompatibilityRequest {
+    #[serde(deserialize_with = "ids::deserialize")]
+    pub brand: Uuid,
+    #[serde(deserialize_with = "ids::deserialize")]
+    pub toner: Uuid,
+    #[serde(deserialize_with = "ids::deserialize")]
+    pub drum: Uuid,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceStatus {
+    Ok,
+    NotFound,
+    /// The toner belongs to another brand than the printer.
+    BrandMismatch,
+}
+
+#[derive(Serialize)]
+pub struct ReferenceCheck {
+    pub status: ReferenceStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<&'static str>,
+    /// Toners interchangeable with a mismatched toner that belong to the
+    /// printer's brand.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub alternatives: Vec<Uuid>,
+}
+
+impl ReferenceCheck {
+    pub fn new(status: ReferenceStatus, message: Option<&'static str>) -> Self {
+        ReferenceCheck {
+            status,
+            message,
+            alternatives: Vec::new(),
         }
     }
 }
 
+/// Whether the brand, toner and drum of a tentative printer fit together,
+/// reference by reference.
+#[derive(Serialize)]
+pub struct CompatibilityReport {
+    pub compatible: bool,
+    pub brand: ReferenceCheck,
+    pub toner: ReferenceCheck,
+    pub drum: ReferenceCheck,
+}
+
 #[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BulkStatusRequest {
+    #[serde(deserialize_with = "ids::deserialize_all")]
+    pub ids: Vec<Uuid>,
+    pub status: PrinterStatus,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct BulkStatusResponse {
+    pub updated: u64,
+    pub not_found: Vec<Uuid>,
+    /// Retired printers are left as they are.
+    pub retired: Vec<Uuid>,
+}
+
+#[derive(Deserialize)]
+pub struct TonerGroupFilter {
+    #[serde(default, deserialize_with = "ids::deserialize_option")]
+    pub toner: Option<Uuid>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct TonerSummary {
+    pub id: Uuid,
+    pub name: String,
+}
+
+/// The printers using one toner.
+#[derive(Deserialize, Serialize)]
+pub struct TonerPrinters {
+    pub toner: TonerSummary,
+    pub printers: Vec<Printer>,
+}
+
+/// A printer joined with the toner it uses.
+#[derive(FromRow)]
+pub struct PrinterWithToner {
+    pub toner_name: String,
+    #[sqlx(flatten)]
+    pub printer: Printer,
+}
+
+#[derive(Deserialize)]
+pub struct PrinterFilter {
+    pub department: Option<String>,
+    pub connection_type: Option<ConnectionType>,
+    /// Comma-separated tags, combined according to `tag_match`.
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub tag_match: TagMatch,
+}
+
+impl PrinterFilter {
+    /// The requested tags, normalized and without duplicates.
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .tag
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(tag::normalize)
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CreatePrinterRequest {
+    #[schemars(length(
+        min = "crate::validations::NAME_MIN_LENGTH",
+        max = "crate::validations::NAME_MAX_LENGTH"
+    ))]
     pub name: String,
+    // Length limits come from the configuration and are filled in when the
+    // schema is served.
     pub model: String,
+    /// Brand id.
     pub brand: String,
+    /// Toner id.
     pub toner: String,
+    /// Drum id.
     pub drum: String,
+    #[schemars(length(min = 1, max = 50))]
+    pub owner_department: Option<String>,
+    /// `active` when omitted.
+    pub status: Option<PrinterStatus>,
+    /// `owned` when omitted.
+    pub acquisition_type: Option<AcquisitionType>,
+    /// Required for leased printers, rejected for owned ones.
+    pub lease_end_date: Option<NaiveDate>,
+    /// Unknown when omitted.
+    pub connection_type: Option<ConnectionType>,
 }
 
 #[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct UpdatePrinterRequest {
+    #[serde(deserialize_with = "ids::deserialize")]
     pub id: Uuid,
     pub name: String,
     pub model: String,
     pub brand: String,
     pub toner: String,
     pub drum: String,
+    pub owner_department: Option<String>,
+    /// Kept as is, along with `lease_end_date`, when omitted.
+    pub acquisition_type: Option<AcquisitionType>,
+    pub lease_end_date: Option<NaiveDate>,
+    /// Kept as is when omitted.
+    pub connection_type: Option<ConnectionType>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ReportPagesRequest {
+    pub page_count: i64,
+}
+
+#[derive(Deserialize)]
+pub struct ReportPagesParams {
+    /// Accepts a lower count after the printer's meter was reset.
+    #[serde(default)]
+    pub reset: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ClonePrinterRequest {
+    pub name: Option<String>,
+}
+
+/// A printer row as read from a CSV import, referencing related entities by name.
+#[derive(Deserialize)]
+pub struct PrinterCsvRow {
+    pub name: String,
+    pub model: String,
+    pub brand: String,
+    pub toner: String,
+    pub drum: String,
+    #[serde(default)]
+    pub owner_department: Option<String>,
+    #[serde(default)]
+    pub acquisition_type: Option<AcquisitionType>,
+    #[serde(default)]
+    pub lease_end_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub connection_type: Option<ConnectionType>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportRowStatus {
+    Created,
+    Failed,
+}
+
+#[derive(Serialize)]
+pub struct ImportRowResult {
+    pub line: u64,
+    pub status: ImportRowStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl ImportRowResult {
+    pub fn created(line: u64, id: Uuid, warnings: Vec<String>) -> Self {
+        ImportRowResult {
+            line,
+            status: ImportRowStatus::Created,
+            id: Some(id),
+            error: None,
+            warnings,
+        }
+    }
+
+    pub fn failed(line: u64, error: String) -> Self {
+        ImportRowResult {
+            line,
+            status: ImportRowStatus::Failed,
+            id: None,
+            error: Some(error),
+            warnings: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ImportReport {
+    pub created: usize,
+    pub failed: usize,
+    pub rows: Vec<ImportRowResult>,
 }
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct SearchParams {
+    pub q: String,
+}
+
+/// Search matches, capped at `SEARCH_MAX_RESULTS`. `truncated` tells the
+/// client that more rows matched than were returned.
+#[derive(Serialize)]
+pub struct SearchResults<T> {
+    pub data: Vec<T>,
+    pub truncated: bool,
+}
+
+impl<T> SearchResults<T> {
+    /// Builds the results from up to `cap + 1` rows, the extra row only
+    /// signalling that the cap was exceeded.
+    pub fn capped(mut data: Vec<T>, cap: i64) -> Self {
+        let cap = cap as usize;
+        let truncated = data.len() > cap;
+        data.truncate(cap);
+        SearchResults { data, truncated }
+    }
+}
@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+use super::timestamp;
+
+/// Headline counts of the catalog at `taken_at`.
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct Snapshot {
+    pub id: i64,
+    #[serde(serialize_with = "timestamp::serialize")]
+    pub taken_at: DateTime<Utc>,
+    pub printers: i64,
+    pub active_printers: i64,
+    pub brands: i64,
+    pub toners: i64,
+    pub drums: i64,
+    /// Toners whose stock is below their `min_stock`.
+    pub low_stock_toners: i64,
+    pub toner_stock: i64,
+    pub drum_stock: i64,
+}
+
+/// Keeps snapshots taken between `from` and `to`, both inclusive.
+#[derive(Deserialize)]
+pub struct SnapshotFilter {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
@@ -1,5 +1,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+use super::timestamp;
 
 type Version = String;
 
@@ -15,8 +18,40 @@ pub struct Dependencies {
     pub database: Database,
 }
 
+/// Requests being served by this instance, against `MAX_CONCURRENT_REQUESTS`.
+#[derive(Deserialize, Serialize)]
+pub struct Requests {
+    pub in_flight: usize,
+    pub limit: Option<usize>,
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct Status {
+    #[serde(serialize_with = "timestamp::serialize")]
     pub updated_at: DateTime<Utc>,
     pub dependencies: Dependencies,
+    pub requests: Requests,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ReadinessChecks {
+    pub database: bool,
+    pub migrations: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub checks: ReadinessChecks,
+    /// Migrations bundled with this build that the database has not applied.
+    pub pending_migrations: Vec<i64>,
+}
+
+/// Exact row count of a table and its approximate size on disk, indexes and
+/// TOAST included.
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct TableStats {
+    pub table: String,
+    pub rows: i64,
+    pub total_bytes: i64,
 }
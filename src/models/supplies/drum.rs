@@ -1,7 +1,10 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
 use uuid::Uuid;
 
+use crate::ids;
+
 #[derive(Deserialize, Serialize, FromRow)]
 pub struct Drum {
     pub id: Uuid,
@@ -12,20 +15,27 @@ pub struct Drum {
 impl Drum {
     pub fn new(name: &str) -> Self {
         Drum {
-            id: Uuid::new_v4(),
+            id: ids::new_id(),
             name: String::from(name),
             stock: 0,
         }
     }
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CreateDrumRequest {
+    #[schemars(length(
+        min = "crate::validations::NAME_MIN_LENGTH",
+        max = "crate::validations::NAME_MAX_LENGTH"
+    ))]
     pub name: String,
 }
 
 #[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct UpdateDrumRequest {
+    #[serde(deserialize_with = "ids::deserialize")]
     pub id: Uuid,
     pub name: String,
 }
@@ -1,31 +1,116 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
 use uuid::Uuid;
 
+use crate::ids;
+
 #[derive(Deserialize, Serialize, FromRow)]
 pub struct Toner {
     pub id: Uuid,
     pub name: String,
     pub stock: i32,
+    pub brand: Option<Uuid>,
+    pub min_stock: i32,
+    pub color: Option<String>,
+    /// Pages the manufacturer rates a cartridge for.
+    pub page_yield: Option<i32>,
 }
 
 impl Toner {
-    pub fn new(name: &str) -> Self {
+    pub fn new(
+        name: &str,
+        brand: Option<Uuid>,
+        min_stock: i32,
+        color: Option<&str>,
+        page_yield: Option<i32>,
+    ) -> Self {
         Toner {
-            id: Uuid::new_v4(),
+            id: ids::new_id(),
             name: String::from(name),
             stock: 0,
+            brand,
+            min_stock,
+            color: color.map(String::from),
+            page_yield,
         }
     }
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CreateTonerRequest {
+    #[schemars(length(
+        min = "crate::validations::NAME_MIN_LENGTH",
+        max = "crate::validations::NAME_MAX_LENGTH"
+    ))]
     pub name: String,
+    #[serde(default, deserialize_with = "ids::deserialize_option")]
+    pub brand: Option<Uuid>,
+    #[serde(default)]
+    #[schemars(range(min = 0))]
+    pub min_stock: i32,
+    #[schemars(length(min = 1, max = 20))]
+    pub color: Option<String>,
+    #[schemars(range(min = 1))]
+    pub page_yield: Option<i32>,
 }
 
 #[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct UpdateTonerRequest {
+    #[serde(deserialize_with = "ids::deserialize")]
+    pub id: Uuid,
+    pub name: String,
+    #[serde(default, deserialize_with = "ids::deserialize_option")]
+    pub brand: Option<Uuid>,
+    /// Kept as is when omitted, as are `color` and `page_yield`.
+    pub min_stock: Option<i32>,
+    pub color: Option<String>,
+    pub page_yield: Option<i32>,
+}
+
+/// Printers moved from a discontinued toner to its replacement.
+#[derive(Serialize)]
+pub struct ReplaceTonerResult {
+    pub from: Uuid,
+    pub to: Uuid,
+    pub printers: u64,
+    /// Non-blocking validation warnings, such as a replacement of another
+    /// brand than some of the printers.
+    pub warnings: Vec<String>,
+}
+
+/// A toner below its `min_stock`, with the quantity to order so it reaches
+/// `min_stock` plus the expected consumption over the coverage window.
+/// Links a toner to an interchangeable one.
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlternativeRequest {
+    #[serde(deserialize_with = "ids::deserialize")]
+    pub toner: Uuid,
+}
+
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct ReorderSuggestion {
     pub id: Uuid,
     pub name: String,
+    pub stock: i32,
+    pub min_stock: i32,
+    /// Average units consumed per day over the lookback window.
+    pub daily_consumption: f64,
+    /// Days until the stock runs out at the current rate; `None` without
+    /// recent consumption.
+    pub days_of_stock: Option<f64>,
+    pub suggested_quantity: i32,
+}
+
+/// Toners of one brand sharing a color and yield, which are usually the same
+/// cartridge entered twice.
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct DuplicateToners {
+    pub brand: Uuid,
+    pub color: String,
+    pub page_yield: i32,
+    pub toners: Vec<Uuid>,
 }
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+#[derive(Deserialize, Serialize)]
+pub struct TagRequest {
+    pub tag: String,
+}
+
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct TagCount {
+    pub name: String,
+    pub count: i64,
+}
+
+/// How a printer list filtered by several tags combines them.
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+    /// Printers carrying every listed tag.
+    #[default]
+    All,
+    /// Printers carrying at least one of the listed tags.
+    Any,
+}
+
+/// Tags are compared case-insensitively, so they are stored trimmed and in
+/// lowercase.
+pub fn normalize(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
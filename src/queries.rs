@@ -0,0 +1,81 @@
+//! Statements issued by the hot count, list and lookup paths.
+//!
+//! SQLx prepares each statement once per connection and caches it by its SQL
+//! text, so handlers running the same statement share a cache slot only when
+//! the text matches byte for byte. Keeping the statements here instead of
+//! repeating literals keeps them from drifting apart.
+
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+// Brands
+pub const COUNT_BRANDS: &str = "SELECT COUNT(*) FROM brands;";
+pub const LIST_BRANDS: &str = "SELECT * FROM brands ORDER BY id LIMIT $1 OFFSET $2;";
+pub const BRAND_BY_ID: &str = "SELECT * FROM brands WHERE id = $1;";
+pub const BRAND_EXISTS: &str = "SELECT id FROM brands WHERE id = $1;";
+
+// Printers
+pub const COUNT_PRINTERS: &str = "SELECT COUNT(*) FROM printers;";
+pub const PRINTER_BY_ID: &str = "SELECT * FROM printers WHERE id = $1;";
+pub const PRINTER_EXISTS: &str = "SELECT id FROM printers WHERE id = $1;";
+
+// Toners
+pub const COUNT_TONERS: &str = "SELECT COUNT(*) FROM toners;";
+pub const LIST_TONERS: &str = "SELECT * FROM toners ORDER BY id LIMIT $1 OFFSET $2;";
+pub const TONER_BY_ID: &str = "SELECT * FROM toners WHERE id = $1;";
+pub const TONER_EXISTS: &str = "SELECT id FROM toners WHERE id = $1;";
+
+// Drums
+pub const COUNT_DRUMS: &str = "SELECT COUNT(*) FROM drums;";
+pub const LIST_DRUMS: &str = "SELECT * FROM drums ORDER BY id LIMIT $1 OFFSET $2;";
+pub const DRUM_BY_ID: &str = "SELECT * FROM drums WHERE id = $1;";
+pub const DRUM_EXISTS: &str = "SELECT id FROM drums WHERE id = $1;";
+
+// Movements
+pub const COUNT_MOVEMENTS: &str = "SELECT COUNT(*) FROM movements;";
+pub const COUNT_TONER_MOVEMENTS: &str =
+    "SELECT COUNT(*) FROM movements WHERE toner_id IS NOT NULL;";
+pub const COUNT_DRUM_MOVEMENTS: &str = "SELECT COUNT(*) FROM movements WHERE drum_id IS NOT NULL;";
+pub const LIST_MOVEMENTS: &str = "SELECT * FROM movements ORDER BY id LIMIT $1 OFFSET $2;";
+pub const LIST_TONER_MOVEMENTS: &str =
+    "SELECT * FROM movements WHERE toner_id IS NOT NULL ORDER BY id LIMIT $1 OFFSET $2;";
+pub const LIST_DRUM_MOVEMENTS: &str =
+    "SELECT * FROM movements WHERE drum_id IS NOT NULL ORDER BY id LIMIT $1 OFFSET $2;";
+pub const MOVEMENT_EXISTS: &str = "SELECT id FROM movements WHERE id = $1;";
+
+/// Tables [`exists`] can look ids up in. Each maps to one of the fixed
+/// statements above, so no table name is ever built from input.
+#[derive(Clone, Copy, Debug)]
+pub enum Table {
+    Brands,
+    Printers,
+    Toners,
+    Drums,
+    Movements,
+}
+
+impl Table {
+    fn exists_statement(self) -> &'static str {
+        match self {
+            Table::Brands => BRAND_EXISTS,
+            Table::Printers => PRINTER_EXISTS,
+            Table::Toners => TONER_EXISTS,
+            Table::Drums => DRUM_EXISTS,
+            Table::Movements => MOVEMENT_EXISTS,
+        }
+    }
+}
+
+/// Whether `table` has a row with `id`, the check behind every `404` on an
+/// unknown id.
+pub async fn exists<'e>(
+    executor: impl PgExecutor<'e>,
+    table: Table,
+    id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query(table.exists_statement())
+        .bind(id)
+        .fetch_optional(executor)
+        .await
+        .map(|row| row.is_some())
+}
@@ -0,0 +1,48 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::models::timestamp;
+
+/// A mutation handled by the API, as kept for `GET /admin/recent-ops`.
+#[derive(Clone, Serialize)]
+pub struct Operation {
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    #[serde(serialize_with = "timestamp::serialize")]
+    pub at: DateTime<Utc>,
+}
+
+/// The last mutations handled, oldest dropped first once `capacity` is
+/// reached, so memory stays bounded however long the server runs.
+pub struct RecentOps {
+    capacity: usize,
+    entries: Mutex<VecDeque<Operation>>,
+}
+
+impl RecentOps {
+    pub fn new(capacity: usize) -> Self {
+        RecentOps {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub fn record(&self, operation: Operation) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(operation);
+    }
+
+    /// The recorded operations, most recent first.
+    pub fn snapshot(&self) -> Vec<Operation> {
+        self.entries.lock().unwrap().iter().rev().cloned().collect()
+    }
+}
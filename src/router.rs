@@ -1,13 +1,27 @@
 use crate::{
     handlers::{
-        brand, movement, printer, status,
+        activity, admin, backup, brand, document, draft, events, exists,
+        fallback::method_not_allowed,
+        graph, maintenance, movement, photo, printer, schema, snapshot, status,
         supplies::{drum, toner},
+        tag,
+    },
+    middleware::{
+        auth, content_type, deadline, id_format, in_flight, pretty, read_only, recent_ops,
     },
     models::database::AppState,
 };
-use axum::{routing::get, Router};
+use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{delete, get, patch, post, put},
+    Router,
+};
 use std::sync::Arc;
 
+/// Bytes of a photo upload allowed for the multipart boundaries and headers.
+const PHOTO_BODY_OVERHEAD: usize = 16 * 1024;
+
 pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
         .nest(
@@ -17,70 +31,402 @@ pub fn routes(state: Arc<AppState>) -> Router {
                     "/supplies",
                     Router::new()
                         // Toners
-                        .route("/toner-count", get(toner::count_toners))
-                        .route("/toner/:id", get(toner::search_toner))
+                        .route(
+                            "/toner-count",
+                            get(toner::count_toners).fallback(method_not_allowed),
+                        )
+                        .route(
+                            "/toner/:id",
+                            get(toner::search_toner).fallback(method_not_allowed),
+                        )
                         .route(
                             "/toners",
                             get(toner::show_toners)
                                 .post(toner::create_toner)
                                 .put(toner::update_toner)
-                                .delete(toner::delete_toner),
+                                .delete(toner::delete_toner)
+                                .fallback(method_not_allowed),
+                        )
+                        .route(
+                            "/toners/duplicates",
+                            get(toner::show_duplicate_toners).fallback(method_not_allowed),
+                        )
+                        .route(
+                            "/toners/unused",
+                            get(toner::show_unused_toners).fallback(method_not_allowed),
+                        )
+                        .route(
+                            "/toners/:id/alternatives",
+                            get(toner::show_toner_alternatives)
+                                .post(toner::add_toner_alternative)
+                                .fallback(method_not_allowed),
+                        )
+                        .route(
+                            "/toners/:id/alternatives/:alternative",
+                            delete(toner::remove_toner_alternative).fallback(method_not_allowed),
+                        )
+                        .route(
+                            "/toners/reorder-suggestions",
+                            get(toner::show_reorder_suggestions).fallback(method_not_allowed),
                         )
                         // Drums
-                        .route("/drum-count", get(drum::count_drums))
-                        .route("/drum/:id", get(drum::search_drum))
+                        .route(
+                            "/drum-count",
+                            get(drum::count_drums).fallback(method_not_allowed),
+                        )
+                        .route(
+                            "/drum/:id",
+                            get(drum::search_drum).fallback(method_not_allowed),
+                        )
                         .route(
                             "/drums",
                             get(drum::show_drums)
                                 .post(drum::create_drum)
                                 .put(drum::update_drum)
-                                .delete(drum::delete_drum),
+                                .delete(drum::delete_drum)
+                                .fallback(method_not_allowed),
+                        )
+                        .route(
+                            "/drums/unused",
+                            get(drum::show_unused_drums).fallback(method_not_allowed),
                         ),
                 )
                 // Printers
-                .route("/printer-count", get(printer::count_printers))
-                .route("/printer/:id", get(printer::search_printer))
+                .route(
+                    "/printer-count",
+                    get(printer::count_printers).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printer-count/by-brand",
+                    get(printer::count_printers_by_brand).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printer-count/by-department",
+                    get(printer::count_printers_by_department).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printer-count/by-connection",
+                    get(printer::count_printers_by_connection).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printer/:id",
+                    get(printer::search_printer).fallback(method_not_allowed),
+                )
                 .route(
                     "/printers",
                     get(printer::show_printers)
                         .post(printer::create_printer)
                         .put(printer::update_printer)
-                        .delete(printer::delete_printer),
+                        .delete(printer::delete_printer)
+                        .fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/search",
+                    get(printer::search_printers).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/grouped-by-toner",
+                    get(printer::show_printers_grouped_by_toner).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/expiring-leases",
+                    get(printer::show_expiring_leases).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/models",
+                    get(printer::show_printer_models).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/by-usage",
+                    get(printer::show_printers_by_usage).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/drafts",
+                    post(draft::create_printer_draft).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/drafts/:id",
+                    get(draft::show_printer_draft)
+                        .put(draft::update_printer_draft)
+                        .fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/drafts/:id/finalize",
+                    post(draft::finalize_printer_draft).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/:id/report-pages",
+                    post(printer::report_printer_pages).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/:id/tags",
+                    get(tag::show_printer_tags)
+                        .post(tag::add_printer_tag)
+                        .fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/:id/tags/:tag",
+                    delete(tag::remove_printer_tag).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/:id/documents",
+                    get(document::show_printer_documents)
+                        .post(document::add_printer_document)
+                        .fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/:id/documents/:document",
+                    delete(document::remove_printer_document).fallback(method_not_allowed),
+                )
+                // Tags
+                .route("/tags", get(tag::show_tags).fallback(method_not_allowed))
+                // Request schemas
+                .route(
+                    "/schema/:kind",
+                    get(schema::show_schema).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/:id/graph",
+                    get(graph::show_printer_graph).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/:id/qr",
+                    get(printer::printer_qr_code).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/validate-compatibility",
+                    post(printer::validate_printer_compatibility).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/bulk-status",
+                    post(printer::bulk_update_printer_status)
+                        .fallback(method_not_allowed)
+                        .layer(DefaultBodyLimit::max(state.config.max_bulk_body_bytes)),
+                )
+                .route(
+                    "/printers/:id/brand",
+                    patch(printer::swap_printer_brand).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/:id/decommission",
+                    post(printer::decommission_printer).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/:id/clone",
+                    post(printer::clone_printer).fallback(method_not_allowed),
+                )
+                .route(
+                    "/printers/:id/maintenance-done",
+                    post(maintenance::mark_maintenance_done).fallback(method_not_allowed),
+                )
+                // Maintenance
+                .route(
+                    "/maintenance-schedules",
+                    get(maintenance::show_maintenance_schedules)
+                        .post(maintenance::create_maintenance_schedule)
+                        .fallback(method_not_allowed),
+                )
+                .route(
+                    "/maintenance-schedules/due",
+                    get(maintenance::show_due_maintenance).fallback(method_not_allowed),
                 )
                 // Brands
-                .route("/brand-count", get(brand::count_brands))
-                .route("/brand/:id", get(brand::search_brand))
+                .route(
+                    "/brand-count",
+                    get(brand::count_brands).fallback(method_not_allowed),
+                )
+                .route(
+                    "/brand-count/by-country",
+                    get(brand::count_brands_by_country).fallback(method_not_allowed),
+                )
+                .route(
+                    "/brand/:id",
+                    get(brand::search_brand).fallback(method_not_allowed),
+                )
                 .route(
                     "/brands",
                     get(brand::show_brands)
                         .post(brand::create_brand)
                         .put(brand::update_brand)
-                        .delete(brand::delete_brand),
+                        .delete(brand::delete_brand)
+                        .fallback(method_not_allowed),
+                )
+                .route(
+                    "/brands/name-available",
+                    get(brand::check_brand_name).fallback(method_not_allowed),
+                )
+                .route(
+                    "/brands/:id/detail",
+                    get(brand::show_brand_detail).fallback(method_not_allowed),
+                )
+                .route(
+                    "/brands/unused",
+                    get(brand::show_unused_brands).fallback(method_not_allowed),
+                )
+                .route(
+                    "/brands/upsert",
+                    put(brand::upsert_brand).fallback(method_not_allowed),
                 )
                 // Movements
-                .route("/movement-count", get(movement::count_all_movements))
+                .route(
+                    "/movement-count",
+                    get(movement::count_all_movements).fallback(method_not_allowed),
+                )
                 .route(
                     "/movement-count/toner",
-                    get(movement::count_toner_movements),
+                    get(movement::count_toner_movements).fallback(method_not_allowed),
+                )
+                .route(
+                    "/movement-count/drum",
+                    get(movement::count_drum_movements).fallback(method_not_allowed),
+                )
+                .route(
+                    "/movements/:id",
+                    get(movement::search_movement).fallback(method_not_allowed),
                 )
-                .route("/movement-count/drum", get(movement::count_drum_movements))
-                .route("/movements/:id", get(movement::search_movement))
                 .route(
                     "/movements",
                     get(movement::show_all_movements)
                         .put(movement::update_movement)
-                        .delete(movement::delete_movement),
+                        .delete(movement::delete_movement)
+                        .fallback(method_not_allowed),
                 )
                 .route(
                     "/movements/toner",
-                    get(movement::show_toner_movements).post(movement::create_toner_movement),
+                    get(movement::show_toner_movements)
+                        .post(movement::create_toner_movement)
+                        .fallback(method_not_allowed),
                 )
                 .route(
                     "/movements/drum",
-                    get(movement::show_drum_movements).post(movement::create_drum_movement),
+                    get(movement::show_drum_movements)
+                        .post(movement::create_drum_movement)
+                        .fallback(method_not_allowed),
+                )
+                // Existence
+                .route(
+                    "/exists",
+                    post(exists::check_exists).fallback(method_not_allowed),
+                )
+                // Activity
+                .route(
+                    "/activity",
+                    get(activity::show_activity).fallback(method_not_allowed),
+                )
+                // Change stream
+                .route(
+                    "/events",
+                    get(events::stream_events).fallback(method_not_allowed),
+                )
+                // Snapshots
+                .route(
+                    "/snapshots",
+                    get(snapshot::show_snapshots).fallback(method_not_allowed),
+                )
+                // Admin
+                .route(
+                    "/admin/snapshots",
+                    post(snapshot::create_snapshot).fallback(method_not_allowed),
+                )
+                .route(
+                    "/admin/recompute-counts",
+                    post(admin::recompute_counts).fallback(method_not_allowed),
+                )
+                .route(
+                    "/admin/brands/merge",
+                    post(admin::merge_brands).fallback(method_not_allowed),
+                )
+                .route(
+                    "/admin/toners/:from/replace-with/:to",
+                    post(admin::replace_toner).fallback(method_not_allowed),
+                )
+                .route(
+                    "/admin/integrity-check",
+                    get(admin::check_integrity).fallback(method_not_allowed),
+                )
+                .route(
+                    "/admin/recent-ops",
+                    get(admin::show_recent_ops).fallback(method_not_allowed),
+                )
+                .route(
+                    "/admin/near-duplicate-printers",
+                    get(admin::show_near_duplicate_printers).fallback(method_not_allowed),
+                )
+                .route(
+                    "/admin/config",
+                    get(admin::show_config).fallback(method_not_allowed),
+                )
+                .route(
+                    "/admin/table-stats",
+                    get(admin::show_table_stats).fallback(method_not_allowed),
+                )
+                .route(
+                    "/admin/export",
+                    get(backup::export_catalog).fallback(method_not_allowed),
+                )
+                .route(
+                    "/admin/import",
+                    post(backup::import_catalog)
+                        .fallback(method_not_allowed)
+                        .layer(DefaultBodyLimit::max(state.config.max_bulk_body_bytes)),
                 )
                 // Status
-                .route("/status", get(status::show_status)),
+                .route(
+                    "/status",
+                    get(status::show_status).fallback(method_not_allowed),
+                )
+                .route(
+                    "/health",
+                    get(status::show_health).fallback(method_not_allowed),
+                )
+                .route(
+                    "/ready",
+                    get(status::show_ready).fallback(method_not_allowed),
+                ),
+        )
+        .layer(middleware::from_fn(content_type::require_json))
+        // CSV import takes a `text/csv` body, so it sits outside the JSON layer.
+        .route(
+            "/api/v1/printers/import",
+            post(printer::import_printers_csv)
+                .fallback(method_not_allowed)
+                .layer(DefaultBodyLimit::max(state.config.max_bulk_body_bytes)),
+        )
+        // Photos are uploaded as `multipart/form-data`, with room past the limit
+        // for the multipart framing so oversized photos get a 413 of our own.
+        .route(
+            "/api/v1/printers/:id/photo",
+            get(photo::show_printer_photo)
+                .post(photo::upload_printer_photo)
+                .fallback(method_not_allowed)
+                .layer(DefaultBodyLimit::max(
+                    state.config.printer_photo_max_bytes + PHOTO_BODY_OVERHEAD,
+                )),
         )
+        // Bulk, import and photo routes set their own limit above, which
+        // takes precedence over this one.
+        .layer(DefaultBodyLimit::max(state.config.max_body_bytes))
+        .layer(middleware::from_fn(id_format::format_ids))
+        .layer(middleware::from_fn(pretty::pretty_print))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            deadline::enforce_deadline,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            read_only::reject_mutations,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::require_scope,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            recent_ops::record_mutations,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            in_flight::limit_concurrency,
+        ))
         .with_state(state)
 }
@@ -1,13 +1,33 @@
-use std::{env, sync::Arc};
+use std::{env, str::FromStr, sync::Arc, time::Duration};
 
-use sqlx::PgPool;
-use tracing::{error, info};
+use axum::Router;
+use log::LevelFilter;
+use sqlx::{postgres::PgConnectOptions, ConnectOptions, Connection, PgConnection, PgPool};
+use tracing::{error, info, warn};
 
-use crate::{models::database::AppState, router};
+use crate::{
+    base_path,
+    cache::Cache,
+    config::{Config, PrinterNameScope},
+    events::Events,
+    ids,
+    in_flight::InFlight,
+    jobs, listener,
+    models::database::AppState,
+    recent_ops::RecentOps,
+    router,
+};
 
-pub async fn run() -> Result<(), axum::Error> {
+pub async fn run(config: Config) -> Result<(), axum::Error> {
     let database_url = std::env::var("DATABASE_URL").unwrap();
-    let pool = match PgPool::connect(&database_url).await {
+    let options = match connect_options(&database_url, &config) {
+        Ok(options) => options,
+        Err(e) => {
+            error!("❌ DATABASE_URL inválida: {e}");
+            std::process::exit(1);
+        }
+    };
+    let pool = match connect_with_retry(&options, &config).await {
         Ok(pool) => {
             info!("✅ Conectado ao banco de dados");
             pool
@@ -18,7 +38,61 @@ pub async fn run() -> Result<(), axum::Error> {
         }
     };
 
-    let app = router::routes(Arc::new(AppState { db: pool.clone() }));
+    ids::init(config.id_format);
+    base_path::init(
+        config.base_path.as_deref().unwrap_or_default(),
+        config.base_path_nest,
+    );
+    match check_printer_name_index(&pool, config.printer_name_scope).await {
+        Ok(()) => info!(
+            "✅ Unicidade de nomes de impressoras: {:?}",
+            config.printer_name_scope
+        ),
+        Err(e) => {
+            error!("❌ Índice de nomes de impressoras incompatível com PRINTER_NAME_SCOPE: {e}");
+            std::process::exit(1);
+        }
+    }
+
+    let state = Arc::new(AppState {
+        db: pool.clone(),
+        recent_ops: RecentOps::new(config.recent_ops_capacity),
+        in_flight: Arc::new(InFlight::new(config.max_concurrent_requests)),
+        config,
+        cache: Cache::default(),
+        events: Events::default(),
+    });
+
+    let listener_state = state.clone();
+    tokio::spawn(async move {
+        listener::listen_for_notifications(
+            listener_state.db.clone(),
+            &listener_state.cache,
+            &listener_state.events,
+        )
+        .await;
+    });
+
+    // Zero disables the periodic reconciliation; the admin trigger still works.
+    if state.config.count_reconcile_interval_seconds > 0 {
+        tokio::spawn(jobs::reconcile_counts_periodically(state.clone()));
+    }
+
+    if state.config.snapshot_interval_seconds > 0 {
+        tokio::spawn(jobs::take_snapshots_periodically(state.clone()));
+    }
+
+    if state.config.read_only {
+        warn!("⚠️ Modo somente leitura: alterações serão recusadas");
+    }
+
+    let app = match state.config.base_path.clone() {
+        Some(prefix) if state.config.base_path_nest => {
+            info!("✅ Rotas servidas em {prefix}");
+            Router::new().nest(&prefix, router::routes(state))
+        }
+        _ => router::routes(state),
+    };
 
     let addr = env::var("HOST").expect("Erro ao carregar env HOST");
     let listener = match tokio::net::TcpListener::bind(&addr).await {
@@ -35,3 +109,257 @@ pub async fn run() -> Result<(), axum::Error> {
     axum::serve(listener, app).await.unwrap();
     Ok(())
 }
+
+/// Applies the TLS and slow statement log settings on top of the options
+/// parsed from `DATABASE_URL`.
+fn connect_options(database_url: &str, config: &Config) -> Result<PgConnectOptions, sqlx::Error> {
+    let mut options = PgConnectOptions::from_str(database_url)?;
+
+    // Slow statements are logged with their summary, elapsed time and the
+    // threshold; every other statement stays at `debug`.
+    options = match config.slow_query_threshold_ms {
+        0 => options.log_slow_statements(LevelFilter::Off, Duration::MAX),
+        ms => options.log_slow_statements(LevelFilter::Warn, Duration::from_millis(ms)),
+    };
+
+    if let Some(mode) = config.db_ssl_mode {
+        options = options.ssl_mode(mode);
+    }
+    if let Some(cert) = &config.db_ssl_root_cert {
+        options = options.ssl_root_cert(cert);
+    }
+
+    Ok(options)
+}
+
+/// Longest wait between two connection attempts, however many failed.
+const MAX_CONNECT_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Delay before the attempt following one that waited `delay`: doubled, up to
+/// `MAX_CONNECT_RETRY_DELAY`.
+fn next_retry_delay(delay: Duration) -> Duration {
+    delay.saturating_mul(2).min(MAX_CONNECT_RETRY_DELAY)
+}
+
+/// Creates the pool, retrying with exponential backoff while Postgres is not
+/// reachable yet, so the API can start alongside the database container.
+///
+/// Each attempt probes with a single connection: the pool itself keeps
+/// retrying internally until its acquire timeout, which would make every
+/// attempt take half a minute.
+async fn connect_with_retry(
+    options: &PgConnectOptions,
+    config: &Config,
+) -> Result<PgPool, sqlx::Error> {
+    let probe = retry_transient(
+        config.db_connect_max_attempts,
+        Duration::from_millis(config.db_connect_retry_delay_ms),
+        || PgConnection::connect_with(options),
+    )
+    .await?;
+    let _ = probe.close().await;
+    PgPool::connect_with(options.clone()).await
+}
+
+/// Runs `connect` until it succeeds, fails with a non transient error or
+/// `max_attempts` attempts have been made, sleeping between attempts.
+async fn retry_transient<T, F, Fut>(
+    max_attempts: u32,
+    delay: Duration,
+    mut connect: F,
+) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, sqlx::Error>>,
+{
+    let mut delay = delay.min(MAX_CONNECT_RETRY_DELAY);
+    let mut attempt = 1;
+
+    loop {
+        match connect().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < max_attempts && is_transient(&e) => {
+                warn!(
+                    "⏳ Tentativa {}/{} de conexão ao banco de dados falhou: {e}. Nova tentativa em {:?}",
+                    attempt, max_attempts, delay
+                );
+                tokio::time::sleep(delay).await;
+                delay = next_retry_delay(delay);
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Errors worth retrying: the server is unreachable or still starting up.
+/// Bad credentials or a missing database fail immediately.
+fn is_transient(e: &sqlx::Error) -> bool {
+    match e {
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => true,
+        // `cannot_connect_now`: the server is starting up or shutting down.
+        sqlx::Error::Database(e) => e.code().as_deref() == Some("57P03"),
+        _ => false,
+    }
+}
+
+/// Checks that the printer name indexes created by the migrations match the
+/// configured scope: `printers_name_key` enforces global uniqueness, so it
+/// must exist under the `global` scope and be dropped for the `brand` one.
+async fn check_printer_name_index(pool: &PgPool, scope: PrinterNameScope) -> Result<(), String> {
+    let global_index: bool =
+        sqlx::query_scalar("SELECT to_regclass('printers_name_key') IS NOT NULL;")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    match (scope, global_index) {
+        (PrinterNameScope::Global, false) => Err(String::from(
+            "o índice printers_name_key não existe; crie-o com \
+             CREATE UNIQUE INDEX printers_name_key ON printers (name)",
+        )),
+        (PrinterNameScope::Brand, true) => Err(String::from(
+            "o índice printers_name_key existe; remova-o com DROP INDEX printers_name_key",
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_cap() {
+        let delays: Vec<Duration> = std::iter::successors(Some(Duration::from_secs(1)), |delay| {
+            Some(next_retry_delay(*delay))
+        })
+        .take(8)
+        .collect();
+
+        assert_eq!(
+            delays,
+            [1, 2, 4, 8, 16, 30, 30, 30].map(Duration::from_secs),
+        );
+    }
+
+    #[test]
+    fn retry_delay_does_not_overflow() {
+        assert_eq!(next_retry_delay(Duration::MAX), MAX_CONNECT_RETRY_DELAY);
+    }
+
+    /// Collects everything the subscriber writes.
+    #[derive(Clone, Default)]
+    struct Captured(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for Captured {
+        type Writer = Captured;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    /// Runs a fast and a slow statement through the server's connect options
+    /// for `threshold_ms`, returning what was logged at `warn`.
+    async fn statement_log(threshold_ms: u64) -> String {
+        let mut config = Config::init().unwrap();
+        config.slow_query_threshold_ms = threshold_ms;
+        let options = connect_options(&env::var("DATABASE_URL").unwrap(), &config).unwrap();
+
+        let captured = Captured::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(captured.clone())
+            .with_max_level(tracing::Level::WARN)
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let mut conn = PgConnection::connect_with(&options).await.unwrap();
+        sqlx::query("SELECT 'fast statement';")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        sqlx::query("SELECT pg_sleep(0.3);")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let output = captured.0.lock().unwrap().clone();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[tokio::test]
+    async fn slow_statements_are_logged() {
+        let output = statement_log(200).await;
+
+        assert!(output.contains("WARN"));
+        assert!(output.contains("slow statement"));
+        assert!(output.contains("pg_sleep"));
+        assert!(output.contains("slow_threshold=200ms"));
+        assert!(!output.contains("fast statement"));
+    }
+
+    #[tokio::test]
+    async fn zero_threshold_disables_the_slow_statement_log() {
+        assert_eq!(statement_log(0).await, "");
+    }
+
+    fn refused() -> sqlx::Error {
+        sqlx::Error::Io(std::io::ErrorKind::ConnectionRefused.into())
+    }
+
+    #[tokio::test]
+    async fn retry_stops_after_max_attempts() {
+        let mut attempts = 0;
+        let result: Result<(), _> = retry_transient(3, Duration::from_millis(1), || {
+            attempts += 1;
+            async { Err(refused()) }
+        })
+        .await;
+
+        assert!(matches!(result, Err(sqlx::Error::Io(_))));
+        assert_eq!(attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn retry_returns_first_success() {
+        let mut attempts = 0;
+        let result = retry_transient(5, Duration::from_millis(1), || {
+            attempts += 1;
+            let outcome = if attempts < 3 {
+                Err(refused())
+            } else {
+                Ok(attempts)
+            };
+            async move { outcome }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn retry_gives_up_on_permanent_errors() {
+        let mut attempts = 0;
+        let result: Result<(), _> = retry_transient(5, Duration::from_millis(1), || {
+            attempts += 1;
+            async { Err(sqlx::Error::RowNotFound) }
+        })
+        .await;
+
+        assert!(matches!(result, Err(sqlx::Error::RowNotFound)));
+        assert_eq!(attempts, 1);
+    }
+}
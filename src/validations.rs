@@ -0,0 +1,163 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, Utc};
+use regex::Regex;
+use tracing::{error, warn};
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    config::{Config, ValidationMode},
+    models::error::ApiError,
+};
+
+/// Length limits of brand, printer, toner and drum names, in characters.
+pub const NAME_MIN_LENGTH: usize = 4;
+pub const NAME_MAX_LENGTH: usize = 20;
+
+pub const MAX_URL_LENGTH: usize = 255;
+
+/// Longest email address deliverable over SMTP.
+pub const MAX_EMAIL_LENGTH: usize = 254;
+
+/// Width of the phone number columns.
+pub const MAX_PHONE_LENGTH: usize = 32;
+
+/// Checks that `value` looks like an email address: a local part and a dotted
+/// domain around a single `@`, without spaces.
+pub fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && !value.chars().any(char::is_whitespace)
+        && domain
+            .split('.')
+            .all(|label| !label.is_empty() && !label.starts_with('-') && !label.ends_with('-'))
+        && domain.contains('.')
+}
+
+/// Checks that `value` is a phone number of 7 to 15 digits, the E.164 range,
+/// optionally led by `+` and grouped with spaces, dots, dashes or parentheses.
+pub fn is_phone(value: &str) -> bool {
+    let number = value.strip_prefix('+').unwrap_or(value);
+    let digits = number.chars().filter(char::is_ascii_digit).count();
+    (7..=15).contains(&digits)
+        && number
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, ' ' | '.' | '-' | '(' | ')'))
+}
+
+/// Checks that `value` is an absolute `http` or `https` URL with a host.
+pub fn is_http_url(value: &str) -> bool {
+    match Url::parse(value) {
+        Ok(url) => matches!(url.scheme(), "http" | "https") && url.host().is_some(),
+        Err(_) => false,
+    }
+}
+
+/// Checks client-supplied ids before any lookup: the nil UUID never names a
+/// row, and with `REQUIRE_UUID_V4` only version 4 UUIDs are accepted.
+pub fn check_ids(
+    ids: impl IntoIterator<Item = Uuid>,
+    require_v4: bool,
+) -> Result<(), &'static str> {
+    ids.into_iter().try_for_each(|id| {
+        if id.is_nil() {
+            Err("Id cannot be the nil UUID.")
+        } else if require_v4 && id.get_version_num() != 4 {
+            Err("Id must be a version 4 UUID.")
+        } else {
+            Ok(())
+        }
+    })
+}
+
+/// How far ahead of the server clock a client-supplied timestamp may be.
+pub const MAX_CLOCK_SKEW_SECONDS: i64 = 300;
+
+/// Checks whether `timestamp` lies in the future beyond the allowed clock skew.
+pub fn is_in_future(timestamp: DateTime<Utc>) -> bool {
+    timestamp > Utc::now() + Duration::seconds(MAX_CLOCK_SKEW_SECONDS)
+}
+
+/// Officially assigned ISO 3166-1 alpha-2 country codes.
+const COUNTRY_CODES: &[&str] = &[
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+/// Checks that `code` is an assigned ISO 3166-1 alpha-2 code, in uppercase.
+pub fn is_country_code(code: &str) -> bool {
+    COUNTRY_CODES.binary_search(&code).is_ok()
+}
+
+/// Applies a soft rule the request violates: `warn` mode returns `message` as
+/// a warning to send back with the saved resource, `strict` mode rejects the
+/// request with it, and `off` ignores the rule.
+pub fn soft_violation(
+    mode: ValidationMode,
+    message: &'static str,
+) -> Result<Option<&'static str>, (StatusCode, &'static str)> {
+    match mode {
+        ValidationMode::Off => Ok(None),
+        ValidationMode::Warn => {
+            warn!("{message}");
+            Ok(Some(message))
+        }
+        ValidationMode::Strict => {
+            error!("{message}");
+            Err((StatusCode::BAD_REQUEST, message))
+        }
+    }
+}
+
+/// Names never contain control characters and, when `NAME_PATTERN` is set,
+/// must match it.
+pub fn is_name_allowed(pattern: Option<&Regex>, name: &str) -> bool {
+    !name.chars().any(char::is_control) && pattern.is_none_or(|pattern| pattern.is_match(name))
+}
+
+/// Resources whose names are checked against `NAME_PATTERN`.
+#[derive(Clone, Copy)]
+pub enum Named {
+    Brand,
+    Printer,
+}
+
+/// Rejects `name` unless [`is_name_allowed`], reporting the configured
+/// pattern so clients can tell what is expected.
+pub fn check_name_pattern(config: &Config, entity: Named, name: &str) -> Result<(), ApiError> {
+    if is_name_allowed(
+        config.name_pattern.as_ref().map(|pattern| &pattern.regex),
+        name,
+    ) {
+        return Ok(());
+    }
+
+    let message = match entity {
+        Named::Brand => "Brand name contains characters that are not allowed.",
+        Named::Printer => "Printer name contains characters that are not allowed.",
+    };
+    Err(ApiError::name_not_allowed(
+        message,
+        config
+            .name_pattern
+            .as_ref()
+            .map(|pattern| pattern.source.as_str()),
+    ))
+}
@@ -0,0 +1,206 @@
+//! Endpoints reporting catalog wide counts. Cargo runs one test binary at a
+//! time, so keeping these out of `endpoints.rs` means no other test changes
+//! the counts while they run, and `SERIAL` keeps the ones in here apart.
+pub mod tests {
+    use reqwest::StatusCode;
+    use std::env::var;
+    use tokio::sync::Mutex;
+
+    static SERIAL: Mutex<()> = Mutex::const_new(());
+
+    pub fn setup() {
+        dotenvy::from_filename(".env.test").ok();
+        dotenvy::dotenv().ok();
+    }
+
+    /// Client sending the first `admin` key in `API_KEYS`, if any.
+    pub fn client() -> reqwest::Client {
+        let admin_key = var("API_KEYS").ok().and_then(|keys| {
+            keys.split(',').find_map(|entry| {
+                let mut parts = entry.trim().splitn(3, ':');
+                let key = parts.next()?;
+                (parts.next()?.trim() == "admin").then(|| key.trim().to_string())
+            })
+        });
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(key) = admin_key {
+            headers.insert("X-API-Key", key.parse().unwrap());
+        }
+        reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap()
+    }
+
+    pub fn unique_name(prefix: &str) -> String {
+        format!(
+            "{prefix} {}",
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        )
+    }
+
+    pub async fn create_fixture(client: &reqwest::Client, path: &str, body: String) -> String {
+        let endpoint = format!("http://{}/api/v1/{path}", var("HOST").unwrap());
+        let response = client
+            .post(endpoint)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body: serde_json::Value = response.json().await.unwrap();
+        body.get("id")
+            .unwrap_or(&body)
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    /// A printer with a new brand, toner and drum, returning the toner and
+    /// drum ids.
+    pub async fn create_printer_with_supplies(
+        client: &reqwest::Client,
+        toner_body: String,
+    ) -> (String, String) {
+        let brand = create_fixture(
+            client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("CNB")),
+        )
+        .await;
+        let toner = create_fixture(client, "supplies/toners", toner_body).await;
+        let drum = create_fixture(
+            client,
+            "supplies/drums",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("CND")),
+        )
+        .await;
+        create_fixture(
+            client,
+            "printers",
+            format!(
+                r#"{{
+                    "name": "{}",
+                    "model": "TEST model",
+                    "brand": "{brand}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"
+                }}"#,
+                unique_name("CNP")
+            ),
+        )
+        .await;
+
+        (toner, drum)
+    }
+
+    async fn count(client: &reqwest::Client, path: &str) -> i64 {
+        let endpoint = format!("http://{}/api/v1/{path}", var("HOST").unwrap());
+        let response = client.get(endpoint).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "{path}");
+
+        response.json().await.unwrap()
+    }
+
+    async fn take_snapshot(client: &reqwest::Client) -> serde_json::Value {
+        let response = client
+            .post(format!(
+                "http://{}/api/v1/admin/snapshots",
+                var("HOST").unwrap()
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        response.json().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn count_endpoints_i64_endpoint() {
+        setup();
+        let _serial = SERIAL.lock().await;
+        let client = client();
+        let paths = [
+            "brand-count",
+            "printer-count",
+            "supplies/toner-count",
+            "supplies/drum-count",
+            "movement-count",
+            "movement-count/toner",
+            "movement-count/drum",
+        ];
+
+        let mut before = Vec::new();
+        for path in paths {
+            before.push(count(&client, path).await);
+        }
+
+        let (toner, drum) = create_printer_with_supplies(
+            &client,
+            format!(r#"{{ "name": "{}" }}"#, unique_name("CNT")),
+        )
+        .await;
+        create_fixture(
+            &client,
+            "movements/toner",
+            format!(r#"{{ "toner_id": "{toner}", "quantity": 1 }}"#),
+        )
+        .await;
+        create_fixture(
+            &client,
+            "movements/drum",
+            format!(r#"{{ "drum_id": "{drum}", "quantity": 1 }}"#),
+        )
+        .await;
+
+        // One brand, printer, toner and drum, and a movement of each supply.
+        let expected = [1, 1, 1, 1, 2, 1, 1];
+        for ((path, before), delta) in paths.into_iter().zip(before).zip(expected) {
+            assert_eq!(count(&client, path).await - before, delta, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn snapshot_counts_endpoint() {
+        setup();
+        let _serial = SERIAL.lock().await;
+        let client = client();
+
+        let before = take_snapshot(&client).await;
+
+        // Two units in stock stay below the minimum of five.
+        let (toner, _) = create_printer_with_supplies(
+            &client,
+            format!(r#"{{ "name": "{}", "min_stock": 5 }}"#, unique_name("CNT")),
+        )
+        .await;
+        create_fixture(
+            &client,
+            "movements/toner",
+            format!(r#"{{ "toner_id": "{toner}", "quantity": 2 }}"#),
+        )
+        .await;
+
+        let after = take_snapshot(&client).await;
+        for (field, delta) in [
+            ("printers", 1),
+            ("active_printers", 1),
+            ("brands", 1),
+            ("toners", 1),
+            ("drums", 1),
+            ("low_stock_toners", 1),
+            ("toner_stock", 2),
+            ("drum_stock", 0),
+        ] {
+            assert_eq!(
+                after[field].as_i64().unwrap() - before[field].as_i64().unwrap(),
+                delta,
+                "{field}"
+            );
+        }
+    }
+}
@@ -2,18 +2,376 @@ pub mod tests {
     use reqwest::StatusCode;
     use std::env::var;
 
+    /// Merges, imports, toner replacements and snapshots each take a lock
+    /// shared by every server on the database and answer `409` while it is
+    /// held, so the tests running them take turns.
+    pub static ADMIN_OPERATIONS: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    /// Loads the test settings first: variables already set are kept, so
+    /// `.env.test` wins over `.env`.
     pub fn setup() {
+        dotenvy::from_filename(".env.test").ok();
         dotenvy::dotenv().ok();
     }
 
+    /// First key in `API_KEYS` granted `scope`, if authentication is enabled.
+    pub fn api_key(scope: &str) -> Option<String> {
+        var("API_KEYS").ok()?.split(',').find_map(|entry| {
+            let mut parts = entry.trim().splitn(3, ':');
+            let key = parts.next()?;
+            (parts.next()?.trim() == scope).then(|| key.trim().to_string())
+        })
+    }
+
+    /// Client sending the `admin` key, so tests reach every endpoint.
+    pub fn client() -> reqwest::Client {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(key) = api_key("admin") {
+            headers.insert("X-API-Key", key.parse().unwrap());
+        }
+        reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap()
+    }
+
+    pub fn unique_name(prefix: &str) -> String {
+        format!(
+            "{prefix} {}",
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        )
+    }
+
+    pub async fn create_fixture(client: &reqwest::Client, path: &str, body: String) -> String {
+        let endpoint = format!("http://{}/api/v1/{path}", var("HOST").unwrap());
+        let response = client
+            .post(endpoint)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        // Some resources answer with a bare id, others with `{ id, warnings }`.
+        let body: serde_json::Value = response.json().await.unwrap();
+        body.get("id")
+            .unwrap_or(&body)
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    pub async fn create_printer_fixtures(client: &reqwest::Client) -> (String, String, String) {
+        let brand = create_fixture(
+            client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("FXB")),
+        )
+        .await;
+        let toner = create_fixture(
+            client,
+            "supplies/toners",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("FXT")),
+        )
+        .await;
+        let drum = create_fixture(
+            client,
+            "supplies/drums",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("FXD")),
+        )
+        .await;
+
+        (brand, toner, drum)
+    }
+
+    /// Waits until some session waits on a lock held by the session `holder`,
+    /// as a request does once it reaches a write that conflicts with it.
+    pub async fn wait_until_blocked(pool: &sqlx::PgPool, holder: i32) {
+        for _ in 0..100 {
+            let (count,): (i64,) = sqlx::query_as(
+                "SELECT count(*) FROM pg_stat_activity WHERE $1 = ANY(pg_blocking_pids(pid));",
+            )
+            .bind(holder)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+            if count > 0 {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        panic!("no request waited on session {holder}");
+    }
+
+    pub async fn create_printer_fixture(client: &reqwest::Client) -> String {
+        let (brand, toner, drum) = create_printer_fixtures(client).await;
+        create_fixture(
+            client,
+            "printers",
+            format!(
+                r#"{{
+                    "name": "{}",
+                    "model": "TEST model",
+                    "brand": "{brand}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"
+                }}"#,
+                unique_name("FXP")
+            ),
+        )
+        .await
+    }
+
+    /// A server of its own, started with `settings` on top of the test ones,
+    /// for behaviour the shared server's configuration leaves out. It listens
+    /// on a free port and is stopped when dropped.
+    pub struct TestServer {
+        child: std::process::Child,
+        host: String,
+    }
+
+    impl TestServer {
+        pub async fn start(settings: &[(&str, &str)]) -> TestServer {
+            setup();
+            let host = std::net::TcpListener::bind("127.0.0.1:0")
+                .unwrap()
+                .local_addr()
+                .unwrap()
+                .to_string();
+            // Snapshots are left to the shared server.
+            let child = std::process::Command::new(env!("CARGO_BIN_EXE_printer-supplies-api"))
+                .env("HOST", &host)
+                .env("SNAPSHOT_INTERVAL_SECONDS", "0")
+                .envs(settings.iter().copied())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .spawn()
+                .unwrap();
+            let server = TestServer { child, host };
+
+            let health = server.url("health");
+            for _ in 0..100 {
+                if let Ok(response) = reqwest::get(&health).await {
+                    if response.status() == StatusCode::OK {
+                        return server;
+                    }
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            }
+            panic!("Test server with {settings:?} did not start.");
+        }
+
+        /// URL of `path` under `/api/v1`.
+        pub fn url(&self, path: &str) -> String {
+            format!("http://{}/api/v1/{path}", self.host)
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
     #[tokio::test]
     pub async fn status_endpoint() {
         setup();
         let endpoint = format!("http://{}/api/v1/status", var("HOST").unwrap());
-        let client = reqwest::Client::new();
+        let client = client();
+        let response = client.get(endpoint).send().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let status: serde_json::Value = response.json().await.unwrap();
+        // The status request itself is in flight.
+        assert!(status["requests"]["in_flight"].as_u64().unwrap() >= 1);
+        let limit = var("MAX_CONCURRENT_REQUESTS")
+            .ok()
+            .and_then(|limit| limit.parse::<u64>().ok())
+            .filter(|limit| *limit > 0);
+        assert_eq!(status["requests"]["limit"].as_u64(), limit);
+    }
+
+    #[tokio::test]
+    pub async fn health_endpoint() {
+        setup();
+        let endpoint = format!("http://{}/api/v1/health", var("HOST").unwrap());
+        let client = client();
+        let response = client.get(endpoint).send().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    pub async fn ready_endpoint() {
+        setup();
+        let endpoint = format!("http://{}/api/v1/ready", var("HOST").unwrap());
+        let client = client();
         let response = client.get(endpoint).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["ready"], true);
+        assert_eq!(body["checks"]["database"], true);
+        assert_eq!(body["checks"]["migrations"], true);
+        assert_eq!(body["pending_migrations"], serde_json::json!([]));
+    }
+
+    // Authentication
+
+    #[tokio::test]
+    async fn missing_api_key_endpoint() {
+        setup();
+        let endpoint = format!("http://{}/api/v1/brands", var("HOST").unwrap());
+        let response = reqwest::Client::new().get(endpoint).send().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn probes_without_api_key_endpoint() {
+        setup();
+        let client = reqwest::Client::new();
+        for probe in ["health", "ready"] {
+            let endpoint = format!("http://{}/api/v1/{probe}", var("HOST").unwrap());
+            let response = client.get(endpoint).send().await.unwrap();
+
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    }
+
+    #[tokio::test]
+    async fn api_key_scopes_endpoint() {
+        setup();
+        let client = reqwest::Client::new();
+        let brands = format!("http://{}/api/v1/brands", var("HOST").unwrap());
+        let admin = format!(
+            "http://{}/api/v1/admin/recompute-counts",
+            var("HOST").unwrap()
+        );
+        let read = api_key("read").unwrap();
+        let write = api_key("write").unwrap();
+
+        let response = client
+            .get(&brands)
+            .header("X-API-Key", &read)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // A read-only key cannot mutate.
+        let body = format!(r#"{{ "name": "{}" }}"#, unique_name("SCP"));
+        let response = client
+            .post(&brands)
+            .header("X-API-Key", &read)
+            .header("Content-Type", "application/json")
+            .body(body.clone())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = client
+            .post(&brands)
+            .header("X-API-Key", &write)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        // Admin endpoints need the `admin` scope.
+        let response = client
+            .post(&admin)
+            .header("X-API-Key", &write)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
 
+    #[tokio::test]
+    async fn mutation_actor_endpoint() {
+        setup();
+        let client = reqwest::Client::new();
+        let admin = api_key("admin").unwrap();
+        let write = api_key("write").unwrap();
+        let read = api_key("read").unwrap();
+        let endpoint = format!("http://{}/api/v1/brands", var("HOST").unwrap());
+
+        let response = client
+            .post(&endpoint)
+            .header("X-API-Key", &write)
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "name": "{}" }}"#, unique_name("AUD")))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let brand: String = response.json().await.unwrap();
+
+        let response = client
+            .put(&endpoint)
+            .header("X-API-Key", &admin)
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{ "id": "{brand}", "name": "{}" }}"#,
+                unique_name("AUD")
+            ))
+            .send()
+            .await
+            .unwrap();
         assert_eq!(response.status(), StatusCode::OK);
+
+        let detail = format!("http://{}/api/v1/brand/{brand}", var("HOST").unwrap());
+        let fetch = |key: String| {
+            let request = client.get(&detail).header("X-API-Key", key).send();
+            async move {
+                request
+                    .await
+                    .unwrap()
+                    .json::<serde_json::Value>()
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // Only admins see who changed an entity.
+        let shown = fetch(admin.clone()).await;
+        assert_eq!(shown["created_by"], "local-writer");
+        assert_eq!(shown["updated_by"], "local-admin");
+        let hidden = fetch(read).await;
+        assert!(hidden.get("created_by").is_none());
+
+        let response = client
+            .delete(&endpoint)
+            .header("X-API-Key", &write)
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "id": "{brand}" }}"#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let pool = sqlx::PgPool::connect(&var("DATABASE_URL").unwrap())
+            .await
+            .unwrap();
+        let log: Vec<(String, Option<String>)> =
+            sqlx::query_as("SELECT action, actor FROM audit_log WHERE entity_id = $1 ORDER BY id;")
+                .bind(uuid::Uuid::parse_str(&brand).unwrap())
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        let expected = [
+            ("create", "local-writer"),
+            ("update", "local-admin"),
+            ("delete", "local-writer"),
+        ]
+        .map(|(action, actor)| (action.to_string(), Some(actor.to_string())));
+        assert_eq!(log, expected);
     }
 
     // Supplies/Toner
@@ -25,7 +383,7 @@ pub mod tests {
             "http://{}/api/v1/supplies/toner-count",
             var("HOST").unwrap()
         );
-        let client = reqwest::Client::new();
+        let client = client();
         let response = client.get(endpoint).send().await.unwrap();
 
         assert_eq!(response.status(), StatusCode::OK);
@@ -38,7 +396,7 @@ pub mod tests {
             "http://{}/api/v1/supplies/toner/4340c4a2-eac5-4b51-9baa-40b498605a8c",
             var("HOST").unwrap()
         );
-        let client = reqwest::Client::new();
+        let client = client();
         let response = client.get(endpoint).send().await.unwrap();
 
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
@@ -48,7 +406,7 @@ pub mod tests {
     pub async fn show_toners_endpoint() {
         setup();
         let endpoint = format!("http://{}/api/v1/supplies/toners", var("HOST").unwrap());
-        let client = reqwest::Client::new();
+        let client = client();
         let response = client.get(endpoint).send().await.unwrap();
 
         assert_eq!(response.status(), StatusCode::OK);
@@ -63,7 +421,7 @@ pub mod tests {
 
         let endpoint = format!("http://{}/api/v1/supplies/toners", var("HOST").unwrap());
 
-        let client = reqwest::Client::new();
+        let client = client();
 
         let response = client
             .post(endpoint)
@@ -76,18 +434,103 @@ pub mod tests {
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 
+    #[tokio::test]
+    async fn create_toner_unknown_brand_endpoint() {
+        setup();
+        let body = format!(
+            r#"{{
+                "name": "{}",
+                "brand": "4340c4a2-eac5-4b51-9baa-40b498605a8c"
+            }}"#,
+            unique_name("UBT")
+        );
+
+        let endpoint = format!("http://{}/api/v1/supplies/toners", var("HOST").unwrap());
+
+        let client = client();
+
+        let response = client
+            .post(endpoint)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn unknown_field_endpoint() {
+        setup();
+        let endpoint = format!("http://{}/api/v1/supplies/toners", var("HOST").unwrap());
+        let client = client();
+
+        let response = client
+            .post(endpoint)
+            .header("Content-Type", "application/json")
+            .body(r#"{ "nam": "TEST Toner" }"#)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"], "unknown field");
+        assert_eq!(body["field"], "nam");
+    }
+
+    #[tokio::test]
+    async fn invalid_type_endpoint() {
+        setup();
+        let client = client();
+
+        let cases = [
+            ("brands", r#"{ "name": 123 }"#, "name", "a string"),
+            (
+                "supplies/toners",
+                r#"{ "name": "TEST Toner", "min_stock": "10" }"#,
+                "min_stock",
+                "i32",
+            ),
+            (
+                "printers/bulk-status",
+                r#"{ "ids": [1], "status": "active" }"#,
+                "ids[0]",
+                "a UUID or ULID string",
+            ),
+        ];
+        for (path, body, field, expected) in cases {
+            let endpoint = format!("http://{}/api/v1/{path}", var("HOST").unwrap());
+            let response = client
+                .post(endpoint)
+                .header("Content-Type", "application/json")
+                .body(body)
+                .send()
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["error"], "invalid type");
+            assert_eq!(body["field"], field);
+            assert_eq!(body["expected"], expected);
+        }
+    }
+
     #[tokio::test]
     async fn update_toner_endpoint() {
         setup();
         let body = r#"{
         "id": "4340c4a2-eac5-4b51-9baa-40b398605a8c",
-        "name": "TEST Toner",
-        "color": "black"
+        "name": "TEST Toner"
     }"#;
 
         let endpoint = format!("http://{}/api/v1/supplies/toners", var("HOST").unwrap());
 
-        let client = reqwest::Client::new();
+        let client = client();
 
         let response = client
             .put(endpoint)
@@ -109,7 +552,7 @@ pub mod tests {
 
         let endpoint = format!("http://{}/api/v1/supplies/toners", var("HOST").unwrap());
 
-        let client = reqwest::Client::new();
+        let client = client();
 
         let response = client
             .delete(endpoint)
@@ -122,13 +565,89 @@ pub mod tests {
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
+    #[tokio::test]
+    async fn toner_reorder_suggestions_endpoint() {
+        setup();
+        let client = client();
+        let (brand, _, drum) = create_printer_fixtures(&client).await;
+        let consumed = create_fixture(
+            &client,
+            "supplies/toners",
+            format!(r#"{{ "name": "{}", "min_stock": 10 }}"#, unique_name("RST")),
+        )
+        .await;
+        let idle = create_fixture(
+            &client,
+            "supplies/toners",
+            format!(r#"{{ "name": "{}", "min_stock": 5 }}"#, unique_name("RSI")),
+        )
+        .await;
+
+        // Movements are recorded against a printer using the toner.
+        create_fixture(
+            &client,
+            "printers",
+            format!(
+                r#"{{
+                    "name": "{}",
+                    "model": "TEST model",
+                    "brand": "{brand}",
+                    "toner": "{consumed}",
+                    "drum": "{drum}"
+                }}"#,
+                unique_name("RSP")
+            ),
+        )
+        .await;
+        for quantity in [3, -2] {
+            create_fixture(
+                &client,
+                "movements/toner",
+                format!(r#"{{ "toner_id": "{consumed}", "quantity": {quantity} }}"#),
+            )
+            .await;
+        }
+
+        let response = client
+            .get(format!(
+                "http://{}/api/v1/supplies/toners/reorder-suggestions",
+                var("HOST").unwrap()
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let suggestions: Vec<serde_json::Value> = response.json().await.unwrap();
+        let position = |toner: &str| {
+            suggestions
+                .iter()
+                .position(|suggestion| suggestion["id"] == toner)
+                .unwrap()
+        };
+
+        // 2 units over the 90-day window leave the single unit for 45 days.
+        let suggestion = &suggestions[position(&consumed)];
+        assert_eq!(suggestion["stock"], 1);
+        assert_eq!(suggestion["days_of_stock"].as_f64().unwrap().round(), 45.0);
+        // Back to the minimum plus 30 days of consumption, rounded up.
+        assert_eq!(suggestion["suggested_quantity"], 10);
+
+        let suggestion = &suggestions[position(&idle)];
+        assert!(suggestion["days_of_stock"].is_null());
+        assert_eq!(suggestion["suggested_quantity"], 5);
+
+        // A toner that is running out comes before one without consumption.
+        assert!(position(&consumed) < position(&idle));
+    }
+
     // Supplies/Drum
 
     #[tokio::test]
     pub async fn drum_count_endpoint() {
         setup();
         let endpoint = format!("http://{}/api/v1/supplies/drum-count", var("HOST").unwrap());
-        let client = reqwest::Client::new();
+        let client = client();
         let response = client.get(endpoint).send().await.unwrap();
 
         assert_eq!(response.status(), StatusCode::OK);
@@ -141,7 +660,7 @@ pub mod tests {
             "http://{}/api/v1/supplies/drum/4340c4a2-eac5-4b51-9baa-40b498605a8c",
             var("HOST").unwrap()
         );
-        let client = reqwest::Client::new();
+        let client = client();
         let response = client.get(endpoint).send().await.unwrap();
 
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
@@ -151,7 +670,7 @@ pub mod tests {
     pub async fn show_drums_endpoint() {
         setup();
         let endpoint = format!("http://{}/api/v1/supplies/drums", var("HOST").unwrap());
-        let client = reqwest::Client::new();
+        let client = client();
         let response = client.get(endpoint).send().await.unwrap();
 
         assert_eq!(response.status(), StatusCode::OK);
@@ -166,7 +685,7 @@ pub mod tests {
 
         let endpoint = format!("http://{}/api/v1/supplies/drums", var("HOST").unwrap());
 
-        let client = reqwest::Client::new();
+        let client = client();
 
         let response = client
             .post(endpoint)
@@ -189,7 +708,7 @@ pub mod tests {
 
         let endpoint = format!("http://{}/api/v1/supplies/drums", var("HOST").unwrap());
 
-        let client = reqwest::Client::new();
+        let client = client();
 
         let response = client
             .put(endpoint)
@@ -211,7 +730,7 @@ pub mod tests {
 
         let endpoint = format!("http://{}/api/v1/supplies/drums", var("HOST").unwrap());
 
-        let client = reqwest::Client::new();
+        let client = client();
 
         let response = client
             .delete(endpoint)
@@ -230,12 +749,57 @@ pub mod tests {
     pub async fn brand_count_endpoint() {
         setup();
         let endpoint = format!("http://{}/api/v1/brand-count", var("HOST").unwrap());
-        let client = reqwest::Client::new();
+        let client = client();
         let response = client.get(endpoint).send().await.unwrap();
 
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    pub async fn brand_count_cache_invalidation_endpoint() {
+        setup();
+        let endpoint = format!("http://{}/api/v1/brand-count", var("HOST").unwrap());
+        let client = client();
+        let before: i64 = client
+            .get(&endpoint)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        // Change the table behind the API's back.
+        let pool = sqlx::PgPool::connect(&var("DATABASE_URL").unwrap())
+            .await
+            .unwrap();
+        let id = uuid::Uuid::new_v4();
+        sqlx::query("INSERT INTO brands (id, name) VALUES ($1, $2);")
+            .bind(id)
+            .bind(unique_name("OOB"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+        let after: i64 = client
+            .get(&endpoint)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        sqlx::query("DELETE FROM brands WHERE id = $1;")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(after > before);
+    }
+
     #[tokio::test]
     pub async fn brand_search_endpoint() {
         setup();
@@ -243,138 +807,309 @@ pub mod tests {
             "http://{}/api/v1/brand/4340c4a2-eac5-4b51-9baa-40b498605a8c",
             var("HOST").unwrap()
         );
-        let client = reqwest::Client::new();
+        let client = client();
         let response = client.get(endpoint).send().await.unwrap();
 
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
-    pub async fn show_brands_endpoint() {
+    async fn create_brand_validation_code_endpoint() {
         setup();
         let endpoint = format!("http://{}/api/v1/brands", var("HOST").unwrap());
-        let client = reqwest::Client::new();
-        let response = client.get(endpoint).send().await.unwrap();
+        let client = client();
+        let response = client
+            .post(endpoint)
+            .header("Content-Type", "application/json")
+            .body(r#"{ "name": "HP" }"#)
+            .send()
+            .await
+            .unwrap();
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let error: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(error["code"], "NAME_TOO_SHORT");
+        assert_eq!(error["message"], "Brand name is too short.");
+        assert_eq!(error["params"]["min"], 4);
+        assert_eq!(error["params"]["actual"], 2);
     }
 
     #[tokio::test]
-    async fn create_brand_endpoint() {
+    async fn create_brand_too_long_params_endpoint() {
         setup();
-        let body = r#"{
-            "name": ""
-        }"#;
-
         let endpoint = format!("http://{}/api/v1/brands", var("HOST").unwrap());
-
-        let client = reqwest::Client::new();
-
+        let client = client();
+        // Lengths are counted in characters, not bytes.
+        let name = "Ñ".repeat(21);
         let response = client
             .post(endpoint)
             .header("Content-Type", "application/json")
-            .body(body)
+            .body(format!(r#"{{ "name": "{name}" }}"#))
             .send()
             .await
             .unwrap();
 
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let error: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(error["code"], "NAME_TOO_LONG");
+        assert_eq!(error["params"]["max"], 20);
+        assert_eq!(error["params"]["actual"], 21);
     }
 
     #[tokio::test]
-    async fn update_brand_endpoint() {
+    async fn brand_name_available_endpoint() {
         setup();
-        let body = r#"{
-        "id": "4340c4a2-eac5-4b51-9baa-40b398605a8c",
-        "name": "TEST brand"
-    }"#;
-
-        let endpoint = format!("http://{}/api/v1/brands", var("HOST").unwrap());
-
-        let client = reqwest::Client::new();
+        let client = client();
+        let name = unique_name("Éps");
+        create_fixture(&client, "brands", format!(r#"{{ "name": "{name}" }}"#)).await;
 
+        // Differs only in case, accents and surrounding whitespace.
+        let variant = format!("  {} ", name.replace('É', "e"));
+        let endpoint = format!(
+            "http://{}/api/v1/brands/name-available",
+            var("HOST").unwrap()
+        );
         let response = client
-            .put(endpoint)
-            .header("Content-Type", "application/json")
-            .body(body)
+            .get(&endpoint)
+            .query(&[("name", &variant)])
             .send()
             .await
             .unwrap();
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
-    }
+        assert_eq!(response.status(), StatusCode::OK);
 
-    #[tokio::test]
-    async fn delete_brand_endpoint() {
-        setup();
-        let body = r#"{
-        "id": "4340c4a2-eac5-4b51-9baa-40b498605a8c"
-    }"#;
-
-        let endpoint = format!("http://{}/api/v1/brands", var("HOST").unwrap());
-
-        let client = reqwest::Client::new();
+        let availability: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(availability["available"], false);
+        assert_eq!(
+            availability["normalized"],
+            name.replace('É', "e").to_lowercase()
+        );
 
         let response = client
-            .delete(endpoint)
+            .post(format!("http://{}/api/v1/brands", var("HOST").unwrap()))
             .header("Content-Type", "application/json")
-            .body(body)
+            .body(format!(r#"{{ "name": "{}" }}"#, variant.to_uppercase()))
             .send()
             .await
             .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let availability: serde_json::Value = client
+            .get(&endpoint)
+            .query(&[("name", unique_name("Free"))])
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(availability["available"], true);
     }
 
-    // Printer
+    #[tokio::test]
+    async fn show_unused_brands_endpoint() {
+        setup();
+        let client = client();
+        let (used, toner, drum) = create_printer_fixtures(&client).await;
+        create_fixture(
+            &client,
+            "printers",
+            format!(
+                r#"{{
+                    "name": "{}",
+                    "model": "TEST model",
+                    "brand": "{used}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"
+                }}"#,
+                unique_name("UNP")
+            ),
+        )
+        .await;
+        let unused = create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("UNB")),
+        )
+        .await;
+
+        let endpoint = format!("http://{}/api/v1/brands/unused", var("HOST").unwrap());
+        let response = client.get(endpoint).send().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let brands: Vec<serde_json::Value> = response.json().await.unwrap();
+        assert!(brands.iter().any(|brand| brand["id"] == unused.as_str()));
+        assert!(!brands.iter().any(|brand| brand["id"] == used.as_str()));
+    }
 
     #[tokio::test]
-    pub async fn printer_count_endpoint() {
+    async fn brand_search_invalid_uuid_endpoint() {
         setup();
-        let endpoint = format!("http://{}/api/v1/printer-count", var("HOST").unwrap());
-        let client = reqwest::Client::new();
+        let endpoint = format!("http://{}/api/v1/brand/not-a-uuid", var("HOST").unwrap());
+        let client = client();
+        let response = client.get(endpoint).send().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"], "invalid uuid in path");
+    }
+
+    #[tokio::test]
+    pub async fn show_brands_endpoint() {
+        setup();
+        let endpoint = format!("http://{}/api/v1/brands", var("HOST").unwrap());
+        let client = client();
         let response = client.get(endpoint).send().await.unwrap();
 
         assert_eq!(response.status(), StatusCode::OK);
     }
 
     #[tokio::test]
-    pub async fn printer_search_endpoint() {
+    pub async fn show_brands_pagination_endpoint() {
         setup();
         let endpoint = format!(
-            "http://{}/api/v1/printer/4340c4a2-eac5-4b51-9baa-40b498605a8c",
+            "http://{}/api/v1/brands?limit=1&offset=0",
             var("HOST").unwrap()
         );
-        let client = reqwest::Client::new();
+        let client = client();
         let response = client.get(endpoint).send().await.unwrap();
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let page: serde_json::Value = response.json().await.unwrap();
+        assert!(page["data"].as_array().unwrap().len() <= 1);
+        let base = var("BASE_PATH").unwrap_or_default();
+        assert_eq!(
+            page["_links"]["first"],
+            format!("{base}/api/v1/brands?limit=1&offset=0")
+        );
+        assert!(page["_links"]["prev"].is_null());
     }
 
     #[tokio::test]
-    pub async fn show_printers_endpoint() {
+    pub async fn show_brands_invalid_pagination_endpoint() {
         setup();
-        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
-        let client = reqwest::Client::new();
+        let endpoint = format!("http://{}/api/v1/brands?limit=0", var("HOST").unwrap());
+        let client = client();
         let response = client.get(endpoint).send().await.unwrap();
 
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn brand_fields_endpoint() {
+        setup();
+        let client = client();
+        let brand = create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("FLD")),
+        )
+        .await;
+        let host = var("HOST").unwrap();
+
+        let response = client
+            .get(format!("http://{host}/api/v1/brand/{brand}?fields=id,name"))
+            .send()
+            .await
+            .unwrap();
         assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        let mut keys: Vec<&String> = body.as_object().unwrap().keys().collect();
+        keys.sort();
+        assert_eq!(keys, ["id", "name"]);
+
+        let page: serde_json::Value = client
+            .get(format!("http://{host}/api/v1/brands?fields=name&limit=1"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let listed = page["data"][0].as_object().unwrap();
+        assert_eq!(listed.keys().collect::<Vec<_>>(), ["name"]);
+
+        // Only known fields can be requested.
+        let response = client
+            .get(format!("http://{host}/api/v1/brands?fields=name,secret"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response.json::<String>().await.unwrap(),
+            "Unknown field `secret`."
+        );
     }
 
     #[tokio::test]
-    async fn create_printer_endpoint() {
+    async fn brand_country_code_endpoint() {
+        setup();
+        let client = client();
+        let host = var("HOST").unwrap();
+
+        let response = client
+            .post(format!("http://{host}/api/v1/brands"))
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{ "name": "{}", "country_code": "XX" }}"#,
+                unique_name("CTX")
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Codes are stored in uppercase.
+        let brand = create_fixture(
+            &client,
+            "brands",
+            format!(
+                r#"{{ "name": "{}", "country_code": "jp" }}"#,
+                unique_name("CTY")
+            ),
+        )
+        .await;
+        let found: serde_json::Value = client
+            .get(format!("http://{host}/api/v1/brand/{brand}"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(found["country_code"], "JP");
+
+        let counts: Vec<serde_json::Value> = client
+            .get(format!("http://{host}/api/v1/brand-count/by-country"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let japan = counts
+            .iter()
+            .find(|row| row["country_code"] == "JP")
+            .unwrap();
+        assert!(japan["count"].as_i64().unwrap() >= 1);
+    }
+
+    #[tokio::test]
+    async fn create_brand_endpoint() {
         setup();
         let body = r#"{
-        "name": "",
-        "model": "TEST model",
-        "brand": "wrong_id",
-        "toner": "wrong_id",
-        "drum": "wrong_id",
-    }"#;
+            "name": ""
+        }"#;
 
-        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
+        let endpoint = format!("http://{}/api/v1/brands", var("HOST").unwrap());
 
-        let client = reqwest::Client::new();
+        let client = client();
 
         let response = client
             .post(endpoint)
@@ -388,20 +1123,61 @@ pub mod tests {
     }
 
     #[tokio::test]
-    async fn update_printer_endpoint() {
+    async fn create_brand_invalid_logo_url_endpoint() {
         setup();
         let body = r#"{
-            "id": "4340c4a2-eac5-4b51-9baa-40b398605a8c",
-            "name": "TEST printer",
-            "model": "TEST model",
-            "brand": "4340c4a2-eac5-4b51-9baa-40b498605a8c",
-            "toner": "4340c4a2-eac5-4b51-9baa-40b498605a8c",
-            "drum": "4340c4a2-eac5-4b51-9baa-40b498605a8c"
+            "name": "TEST brand",
+            "logo_url": "ftp://example.com/logo.png"
         }"#;
 
-        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
+        let endpoint = format!("http://{}/api/v1/brands", var("HOST").unwrap());
 
-        let client = reqwest::Client::new();
+        let client = client();
+
+        let response = client
+            .post(endpoint)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn create_brand_wrong_content_type_endpoint() {
+        setup();
+        let body = r#"{
+            "name": "TEST brand"
+        }"#;
+
+        let endpoint = format!("http://{}/api/v1/brands", var("HOST").unwrap());
+
+        let client = client();
+
+        let response = client
+            .post(endpoint)
+            .header("Content-Type", "text/plain")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+
+    #[tokio::test]
+    async fn update_brand_endpoint() {
+        setup();
+        let body = r#"{
+        "id": "4340c4a2-eac5-4b51-9baa-40b398605a8c",
+        "name": "TEST brand"
+    }"#;
+
+        let endpoint = format!("http://{}/api/v1/brands", var("HOST").unwrap());
+
+        let client = client();
 
         let response = client
             .put(endpoint)
@@ -415,15 +1191,100 @@ pub mod tests {
     }
 
     #[tokio::test]
-    async fn delete_printer_endpoint() {
+    async fn upsert_brand_endpoint() {
+        setup();
+        let name = unique_name("UPS");
+        let body = format!(r#"{{ "name": "  {name}  " }}"#);
+
+        let endpoint = format!("http://{}/api/v1/brands/upsert", var("HOST").unwrap());
+
+        let client = client();
+
+        let created = client
+            .put(&endpoint)
+            .header("Content-Type", "application/json")
+            .body(body.clone())
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(created.status(), StatusCode::CREATED);
+        let id: String = created.json().await.unwrap();
+
+        // The name is stored trimmed, as create stores it.
+        let stored: serde_json::Value = client
+            .get(format!("http://{}/api/v1/brand/{id}", var("HOST").unwrap()))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(stored["name"], name.as_str());
+
+        let updated = client
+            .put(&endpoint)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(updated.status(), StatusCode::OK);
+        assert_eq!(updated.json::<String>().await.unwrap(), id);
+
+        // Another spelling of the same name updates the stored brand too.
+        let respelled = client
+            .put(&endpoint)
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{ "name": "{}" }}"#,
+                name.replacen("UPS", " úps", 1)
+            ))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(respelled.status(), StatusCode::OK);
+        assert_eq!(respelled.json::<String>().await.unwrap(), id);
+
+        client
+            .delete(format!("http://{}/api/v1/brands", var("HOST").unwrap()))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "id": "{id}" }}"#))
+            .send()
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn brands_method_not_allowed_endpoint() {
+        setup();
+        let endpoint = format!("http://{}/api/v1/brands", var("HOST").unwrap());
+        let client = client();
+        let response = client.patch(endpoint).send().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let allow = response.headers()["allow"].to_str().unwrap().to_owned();
+        for method in ["GET", "POST", "PUT", "DELETE"] {
+            assert!(allow.contains(method));
+        }
+
+        let body: String = response.json().await.unwrap();
+        assert!(body.contains("Allow"));
+    }
+
+    #[tokio::test]
+    async fn delete_brand_endpoint() {
         setup();
         let body = r#"{
         "id": "4340c4a2-eac5-4b51-9baa-40b498605a8c"
     }"#;
 
-        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
+        let endpoint = format!("http://{}/api/v1/brands", var("HOST").unwrap());
 
-        let client = reqwest::Client::new();
+        let client = client();
 
         let response = client
             .delete(endpoint)
@@ -436,119 +1297,4838 @@ pub mod tests {
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
-    // Movement
-
-    // Count
+    // Printer
 
     #[tokio::test]
-    pub async fn count_all_movements_endpoint() {
+    pub async fn printer_count_endpoint() {
         setup();
-        let endpoint = format!("http://{}/api/v1/movement-count", var("HOST").unwrap());
-        let client = reqwest::Client::new();
+        let endpoint = format!("http://{}/api/v1/printer-count", var("HOST").unwrap());
+        let client = client();
         let response = client.get(endpoint).send().await.unwrap();
 
         assert_eq!(response.status(), StatusCode::OK);
     }
 
     #[tokio::test]
-    pub async fn count_toner_movements_endpoint() {
+    async fn printer_count_by_brand_endpoint() {
         setup();
+        let client = client();
+        create_printer_fixture(&client).await;
+
         let endpoint = format!(
-            "http://{}/api/v1/movement-count/toner",
+            "http://{}/api/v1/printer-count/by-brand",
             var("HOST").unwrap()
         );
-        let client = reqwest::Client::new();
         let response = client.get(endpoint).send().await.unwrap();
 
         assert_eq!(response.status(), StatusCode::OK);
+
+        let counts: Vec<serde_json::Value> = response.json().await.unwrap();
+        assert!(!counts.is_empty());
+
+        // Highest count first, then alphabetical by brand name.
+        let keys: Vec<(i64, String)> = counts
+            .iter()
+            .map(|row| {
+                (
+                    -row["count"].as_i64().unwrap(),
+                    row["brand_name"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(keys, sorted);
     }
 
     #[tokio::test]
-    pub async fn count_drum_movements_endpoint() {
+    async fn printer_models_endpoint() {
         setup();
-        let endpoint = format!("http://{}/api/v1/movement-count/drum", var("HOST").unwrap());
-        let client = reqwest::Client::new();
-        let response = client.get(endpoint).send().await.unwrap();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let model = unique_name("MDL");
+        for _ in 0..2 {
+            create_fixture(
+                &client,
+                "printers",
+                format!(
+                    r#"{{
+                        "name": "{}",
+                        "model": "{model}",
+                        "brand": "{brand}",
+                        "toner": "{toner}",
+                        "drum": "{drum}"
+                    }}"#,
+                    unique_name("MDP")
+                ),
+            )
+            .await;
+        }
 
+        let endpoint = format!("http://{}/api/v1/printers/models", var("HOST").unwrap());
+        let response = client
+            .get(&endpoint)
+            .query(&[("brand", brand.as_str())])
+            .send()
+            .await
+            .unwrap();
         assert_eq!(response.status(), StatusCode::OK);
-    }
 
-    // Show
-
-    #[tokio::test]
-    pub async fn show_all_movements_endpoint() {
-        setup();
-        let endpoint = format!("http://{}/api/v1/movements", var("HOST").unwrap());
-        let client = reqwest::Client::new();
-        let response = client.get(endpoint).send().await.unwrap();
+        let models: Vec<serde_json::Value> = response.json().await.unwrap();
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0]["model"], model.as_str());
+        assert_eq!(models[0]["count"], 2);
 
+        let response = client.get(&endpoint).send().await.unwrap();
         assert_eq!(response.status(), StatusCode::OK);
+
+        let models: Vec<serde_json::Value> = response.json().await.unwrap();
+        let names: Vec<&str> = models
+            .iter()
+            .map(|row| row["model"].as_str().unwrap())
+            .collect();
+        assert!(names.contains(&model.as_str()));
     }
 
     #[tokio::test]
-    pub async fn show_toner_movements_endpoint() {
+    pub async fn printer_search_endpoint() {
         setup();
-        let endpoint = format!("http://{}/api/v1/movements/toner", var("HOST").unwrap());
-        let client = reqwest::Client::new();
+        let endpoint = format!(
+            "http://{}/api/v1/printer/4340c4a2-eac5-4b51-9baa-40b498605a8c",
+            var("HOST").unwrap()
+        );
+        let client = client();
         let response = client.get(endpoint).send().await.unwrap();
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
-    pub async fn show_drum_movements_endpoint() {
+    pub async fn show_printers_endpoint() {
         setup();
-        let endpoint = format!("http://{}/api/v1/movements/drum", var("HOST").unwrap());
-        let client = reqwest::Client::new();
+        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
+        let client = client();
         let response = client.get(endpoint).send().await.unwrap();
 
         assert_eq!(response.status(), StatusCode::OK);
     }
 
     #[tokio::test]
-    async fn update_movement_endpoint() {
+    async fn create_printer_endpoint() {
         setup();
         let body = r#"{
-            "id": "4340c4a2-eac5-4b51-9baa-40b398605a8c",
-            "printer_id": "4340c4a2-eac5-4b51-9baa-40b398605a8c",
-            "toner_id": "4340c4a2-eac5-4b51-9baa-40b498605a8c",
-            "quantity": 7
-        }"#;
+        "name": "",
+        "model": "TEST model",
+        "brand": "wrong_id",
+        "toner": "wrong_id",
+        "drum": "wrong_id",
+    }"#;
 
-        let endpoint = format!("http://{}/api/v1/movements", var("HOST").unwrap());
+        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
 
-        let client = reqwest::Client::new();
+        let client = client();
 
         let response = client
-            .put(endpoint)
+            .post(endpoint)
             .header("Content-Type", "application/json")
             .body(body)
             .send()
             .await
             .unwrap();
 
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn create_printer_model_length_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
+
+        // Lengths are counted in characters, so 40 two-byte characters fit.
+        for (model, expected) in [
+            (String::new(), StatusCode::BAD_REQUEST),
+            ("é".repeat(41), StatusCode::BAD_REQUEST),
+            ("é".repeat(40), StatusCode::CREATED),
+            (String::from("X"), StatusCode::CREATED),
+        ] {
+            let body = format!(
+                r#"{{
+                    "name": "{}",
+                    "model": "{model}",
+                    "brand": "{brand}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"
+                }}"#,
+                unique_name("MDL")
+            );
+
+            let response = client
+                .post(&endpoint)
+                .header("Content-Type", "application/json")
+                .body(body)
+                .send()
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn create_printer_model_whitespace_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
+        let body = |model: &str| {
+            format!(
+                r#"{{
+                    "name": "{}",
+                    "model": "{model}",
+                    "brand": "{brand}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"
+                }}"#,
+                unique_name("WSP")
+            )
+        };
+
+        let response = client
+            .post(&endpoint)
+            .header("Content-Type", "application/json")
+            .body(body("   "))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let id = create_fixture(&client, "printers", body("  TEST model  ")).await;
+        let printer: serde_json::Value = client
+            .get(format!(
+                "http://{}/api/v1/printer/{id}",
+                var("HOST").unwrap()
+            ))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(printer["model"], "TEST model");
+    }
+
+    #[tokio::test]
+    async fn printer_department_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
+
+        // The test environment allows `Finance`, `IT` and `Operations`.
+        for (department, expected) in [
+            ("Marketing", StatusCode::BAD_REQUEST),
+            ("Finance", StatusCode::CREATED),
+        ] {
+            let response = client
+                .post(&endpoint)
+                .header("Content-Type", "application/json")
+                .body(format!(
+                    r#"{{
+                        "name": "{}",
+                        "model": "TEST model",
+                        "brand": "{brand}",
+                        "toner": "{toner}",
+                        "drum": "{drum}",
+                        "owner_department": "{department}"
+                    }}"#,
+                    unique_name("DEP")
+                ))
+                .send()
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), expected);
+        }
+
+        let response = client
+            .get(format!("{endpoint}?department=Finance"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let page: serde_json::Value = response.json().await.unwrap();
+        let printers = page["data"].as_array().unwrap();
+        assert!(!printers.is_empty());
+        assert!(printers
+            .iter()
+            .all(|printer| printer["owner_department"] == "Finance"));
+
+        let response = client
+            .get(format!(
+                "http://{}/api/v1/printer-count/by-department",
+                var("HOST").unwrap()
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let counts: Vec<serde_json::Value> = response.json().await.unwrap();
+        assert!(counts.iter().any(|row| row["department"] == "Finance"));
+    }
+
+    #[tokio::test]
+    async fn create_printer_ulid_references_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let ulid = |id: &str| ulid::Ulid::from(uuid::Uuid::parse_str(id).unwrap()).to_string();
+
+        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
+
+        let response = client
+            .post(endpoint)
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{
+                    "name": "{}",
+                    "model": "TEST model",
+                    "brand": "{}",
+                    "toner": "{}",
+                    "drum": "{}"
+                }}"#,
+                unique_name("ULD"),
+                ulid(&brand),
+                ulid(&toner),
+                ulid(&drum)
+            ))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body: serde_json::Value = response.json().await.unwrap();
+        let printer = body
+            .get("id")
+            .unwrap_or(&body)
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        // Lookups take either form of the id in the path.
+        let response = client
+            .get(format!(
+                "http://{}/api/v1/printer/{}",
+                var("HOST").unwrap(),
+                ulid(&printer)
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["id"], printer.as_str());
+
+        // And updates in the body.
+        let response = client
+            .put(format!("http://{}/api/v1/brands", var("HOST").unwrap()))
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{ "id": "{}", "name": "{}" }}"#,
+                ulid(&brand),
+                unique_name("ULD")
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = client
+            .post(format!("http://{}/api/v1/exists", var("HOST").unwrap()))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "brands": ["{}"] }}"#, ulid(&brand)))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["brands"][&brand], true);
+
+        // A value that is neither is still refused.
+        let response = client
+            .get(format!(
+                "http://{}/api/v1/printer/not-an-id",
+                var("HOST").unwrap()
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn create_printer_toner_brand_mismatch_endpoint() {
+        setup();
+        let client = client();
+        let (brand, _, drum) = create_printer_fixtures(&client).await;
+        let other_brand = create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("TBM")),
+        )
+        .await;
+        let toner = create_fixture(
+            &client,
+            "supplies/toners",
+            format!(
+                r#"{{ "name": "{}", "brand": "{other_brand}" }}"#,
+                unique_name("TBM")
+            ),
+        )
+        .await;
+
+        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
+
+        let response = client
+            .post(endpoint)
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{
+                    "name": "{}",
+                    "model": "TEST model",
+                    "brand": "{brand}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"
+                }}"#,
+                unique_name("TBM")
+            ))
+            .send()
+            .await
+            .unwrap();
+
+        // The test environment runs with `TONER_BRAND_VALIDATION=warn`.
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(
+            body["warnings"][0],
+            "Toner brand does not match the printer brand."
+        );
+    }
+
+    #[tokio::test]
+    async fn search_printer_json_api_endpoint() {
+        setup();
+        let client = client();
+        let id = create_printer_fixture(&client).await;
+
+        let endpoint = format!("http://{}/api/v1/printer/{id}", var("HOST").unwrap());
+        let response = client
+            .get(endpoint)
+            .header("Accept", "application/vnd.api+json")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()["content-type"],
+            "application/vnd.api+json"
+        );
+
+        let document: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(document["data"]["type"], "printers");
+        assert_eq!(document["data"]["id"], id.as_str());
+        assert_eq!(document["data"]["attributes"]["model"], "TEST model");
+        assert!(document["data"]["attributes"].get("brand").is_none());
+        assert_eq!(
+            document["data"]["relationships"]["brand"]["data"]["type"],
+            "brands"
+        );
+    }
+
+    #[tokio::test]
+    async fn show_brands_json_api_endpoint() {
+        setup();
+        let endpoint = format!("http://{}/api/v1/brands?limit=1", var("HOST").unwrap());
+        let client = client();
+        let response = client
+            .get(endpoint)
+            .header("Accept", "application/vnd.api+json")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let document: serde_json::Value = response.json().await.unwrap();
+        assert!(document["data"].is_array());
+        assert_eq!(document["meta"]["limit"], 1);
+        assert!(document["links"]["first"].is_string());
+    }
+
+    #[tokio::test]
+    async fn printer_name_scope_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let other_brand = create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("SCB")),
+        )
+        .await;
+        let name = unique_name("SCP");
+        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
+
+        // The test environment runs with `PRINTER_NAME_SCOPE=brand`.
+        for (brand, expected) in [
+            (&brand, StatusCode::CREATED),
+            (&other_brand, StatusCode::CREATED),
+            (&brand, StatusCode::CONFLICT),
+        ] {
+            let response = client
+                .post(&endpoint)
+                .header("Content-Type", "application/json")
+                .body(format!(
+                    r#"{{
+                        "name": "{name}",
+                        "model": "TEST model",
+                        "brand": "{brand}",
+                        "toner": "{toner}",
+                        "drum": "{drum}"
+                    }}"#
+                ))
+                .send()
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), expected);
+            if expected == StatusCode::CONFLICT {
+                let error: serde_json::Value = response.json().await.unwrap();
+                assert_eq!(error["code"], "DUPLICATE_NAME");
+                assert_eq!(error["message"], "Printer already exists for this brand.");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn printer_qr_code_endpoint() {
+        setup();
+        let client = client();
+        let id = create_printer_fixture(&client).await;
+
+        let endpoint = format!("http://{}/api/v1/printers/{id}/qr", var("HOST").unwrap());
+        let response = client.get(endpoint).send().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "image/svg+xml");
+        assert!(response.text().await.unwrap().contains("<svg"));
+
+        let endpoint = format!(
+            "http://{}/api/v1/printers/4340c4a2-eac5-4b51-9baa-40b498605a8c/qr",
+            var("HOST").unwrap()
+        );
+        let response = client.get(endpoint).send().await.unwrap();
+
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
-    async fn delete_movement_endpoint() {
+    async fn clone_printer_endpoint() {
         setup();
-        let body = r#"{
-        "id": "4340c4a2-eac5-4b51-9baa-40b498605a8c"
-    }"#;
+        let client = client();
+        let source = create_printer_fixture(&client).await;
+        let name = unique_name("CLN");
 
-        let endpoint = format!("http://{}/api/v1/movements", var("HOST").unwrap());
+        let endpoint = format!(
+            "http://{}/api/v1/printers/{source}/clone",
+            var("HOST").unwrap()
+        );
 
-        let client = reqwest::Client::new();
+        let response = client
+            .post(endpoint)
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "name": "{name}" }}"#))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let clone: serde_json::Value = response.json().await.unwrap();
+        assert_ne!(clone["id"], source.as_str());
+        assert_eq!(clone["name"], name.as_str());
+        assert_eq!(clone["model"], "TEST model");
+    }
+
+    #[tokio::test]
+    async fn clone_printer_default_name_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        // As long as a name may be, so the suffix has to make room.
+        let name = format!(
+            "CLS {}-LONGNAM",
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        );
+        let source = create_fixture(
+            &client,
+            "printers",
+            format!(
+                r#"{{
+                    "name": "{name}",
+                    "model": "TEST model",
+                    "brand": "{brand}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"
+                }}"#
+            ),
+        )
+        .await;
+        let endpoint = format!(
+            "http://{}/api/v1/printers/{source}/clone",
+            var("HOST").unwrap()
+        );
 
+        let response = client.post(&endpoint).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let clone: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(clone["name"], format!("{} (copy)", &name[..13]));
+
+        // A body is optional, but a malformed one is not ignored.
         let response = client
-            .delete(endpoint)
+            .post(&endpoint)
             .header("Content-Type", "application/json")
-            .body(body)
+            .body(r#"{ "name": "#)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn clone_missing_printer_endpoint() {
+        setup();
+        let endpoint = format!(
+            "http://{}/api/v1/printers/4340c4a2-eac5-4b51-9baa-40b498605a8c/clone",
+            var("HOST").unwrap()
+        );
+        let client = client();
+        let response = client.post(endpoint).send().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn report_printer_pages_endpoint() {
+        setup();
+        let client = client();
+        let printer = create_printer_fixture(&client).await;
+        let endpoint = format!(
+            "http://{}/api/v1/printers/{printer}/report-pages",
+            var("HOST").unwrap()
+        );
+
+        let report = |url: String, count: i64| {
+            client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .body(format!(r#"{{ "page_count": {count} }}"#))
+                .send()
+        };
+
+        let response = report(endpoint.clone(), 1200).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["page_count"], 1200);
+
+        // Counters never go down on their own.
+        let response = report(endpoint.clone(), 900).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = report(format!("{endpoint}?reset=true"), 900).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["page_count"], 900);
+
+        let response = report(endpoint, -1).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn report_missing_printer_pages_endpoint() {
+        setup();
+        let endpoint = format!(
+            "http://{}/api/v1/printers/4340c4a2-eac5-4b51-9baa-40b498605a8c/report-pages",
+            var("HOST").unwrap()
+        );
+        let client = client();
+        let response = client
+            .post(endpoint)
+            .header("Content-Type", "application/json")
+            .body(r#"{ "page_count": 10 }"#)
             .send()
             .await
             .unwrap();
 
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
+
+    #[tokio::test]
+    async fn printers_by_usage_endpoint() {
+        setup();
+        let client = client();
+        let printer = create_printer_fixture(&client).await;
+        let response = client
+            .post(format!(
+                "http://{}/api/v1/printers/{printer}/report-pages",
+                var("HOST").unwrap()
+            ))
+            .header("Content-Type", "application/json")
+            .body(r#"{ "page_count": 9000000000 }"#)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let endpoint = format!("http://{}/api/v1/printers/by-usage", var("HOST").unwrap());
+        let response = client.get(endpoint).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let page: serde_json::Value = response.json().await.unwrap();
+        let counts: Vec<i64> = page["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|printer| printer["page_count"].as_i64().unwrap())
+            .collect();
+        assert!(counts[0] >= 9000000000);
+        assert!(counts.windows(2).all(|pair| pair[0] >= pair[1]));
+    }
+
+    #[tokio::test]
+    async fn printer_tags_endpoint() {
+        setup();
+        let client = client();
+        let first = create_printer_fixture(&client).await;
+        let second = create_printer_fixture(&client).await;
+        let leased = unique_name("leased").replace(' ', "-");
+        let color = unique_name("color").replace(' ', "-");
+
+        let tag = |printer: &str, tag: &str| {
+            client
+                .post(format!(
+                    "http://{}/api/v1/printers/{printer}/tags",
+                    var("HOST").unwrap()
+                ))
+                .header("Content-Type", "application/json")
+                .body(format!(r#"{{ "tag": "{tag}" }}"#))
+                .send()
+        };
+
+        // Tags are stored in lowercase.
+        let response = tag(&first, &leased.to_uppercase()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.json::<Vec<String>>().await.unwrap(),
+            vec![leased.clone()]
+        );
+        tag(&first, &color).await.unwrap();
+        tag(&second, &leased).await.unwrap();
+
+        let response = tag(&first, " ").await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
+        let filtered = |mode: &'static str| {
+            let query = [
+                ("tag", format!("{leased},{color}")),
+                ("tag_match", mode.into()),
+            ];
+            let request = client.get(&endpoint).query(&query).send();
+            async move {
+                let page: serde_json::Value = request.await.unwrap().json().await.unwrap();
+                let mut ids: Vec<String> = page["data"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|printer| printer["id"].as_str().unwrap().to_string())
+                    .collect();
+                ids.sort();
+                ids
+            }
+        };
+
+        assert_eq!(filtered("all").await, vec![first.clone()]);
+        let mut both = vec![first.clone(), second.clone()];
+        both.sort();
+        assert_eq!(filtered("any").await, both);
+
+        let untag = format!(
+            "http://{}/api/v1/printers/{first}/tags/{color}",
+            var("HOST").unwrap()
+        );
+        let response = client.delete(&untag).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = client.delete(&untag).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = client
+            .get(format!(
+                "http://{}/api/v1/printers/{first}/tags",
+                var("HOST").unwrap()
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            response.json::<Vec<String>>().await.unwrap(),
+            vec![leased.clone()]
+        );
+    }
+
+    #[tokio::test]
+    async fn printer_draft_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let host = var("HOST").unwrap();
+        let name = unique_name("DRF");
+
+        // Drafts accept incomplete payloads.
+        let response = client
+            .post(format!("http://{host}/api/v1/printers/drafts"))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "name": "{name}" }}"#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let draft: serde_json::Value = response.json().await.unwrap();
+        let id = draft["id"].as_str().unwrap().to_string();
+        assert!(draft["model"].is_null());
+        assert!(
+            chrono::DateTime::parse_from_rfc3339(draft["expires_at"].as_str().unwrap()).is_ok()
+        );
+
+        let finalize = format!("http://{host}/api/v1/printers/drafts/{id}/finalize");
+        let response = client.post(&finalize).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response.json::<String>().await.unwrap(),
+            "Draft is missing: model, brand, toner, drum."
+        );
+
+        let response = client
+            .put(format!("http://{host}/api/v1/printers/drafts/{id}"))
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{
+                    "name": "{name}",
+                    "model": "TEST model",
+                    "brand": "{brand}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"
+                }}"#
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = client.post(&finalize).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let created: serde_json::Value = response.json().await.unwrap();
+        let printer: serde_json::Value = client
+            .get(format!(
+                "http://{host}/api/v1/printer/{}",
+                created["id"].as_str().unwrap()
+            ))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(printer["name"], name.as_str());
+
+        // The draft is gone once promoted.
+        let response = client
+            .get(format!("http://{host}/api/v1/printers/drafts/{id}"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn stable_pagination_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        // A tag of its own keeps the dataset apart from concurrent tests.
+        let batch = unique_name("page").replace(' ', "-");
+        let mut created = Vec::new();
+        for _ in 0..5 {
+            let printer = create_fixture(
+                &client,
+                "printers",
+                format!(
+                    r#"{{
+                        "name": "{}",
+                        "model": "TEST model",
+                        "brand": "{brand}",
+                        "toner": "{toner}",
+                        "drum": "{drum}"
+                    }}"#,
+                    unique_name("PGP")
+                ),
+            )
+            .await;
+            client
+                .post(format!(
+                    "http://{}/api/v1/printers/{printer}/tags",
+                    var("HOST").unwrap()
+                ))
+                .header("Content-Type", "application/json")
+                .body(format!(r#"{{ "tag": "{batch}" }}"#))
+                .send()
+                .await
+                .unwrap();
+            created.push(printer);
+        }
+
+        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
+        let mut seen = Vec::new();
+        for offset in [0, 2, 4] {
+            let page: serde_json::Value = client
+                .get(&endpoint)
+                .query(&[
+                    ("tag", batch.clone()),
+                    ("limit", "2".into()),
+                    ("offset", offset.to_string()),
+                ])
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            for printer in page["data"].as_array().unwrap() {
+                seen.push(printer["id"].as_str().unwrap().to_string());
+            }
+        }
+
+        // Every printer shows up exactly once, in id order.
+        created.sort();
+        assert_eq!(seen, created);
+    }
+
+    #[tokio::test]
+    async fn search_printers_ranking_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        // Letters only, as the parser splits hex such as `12e45xya` into a
+        // number and a word.
+        let token: String = uuid::Uuid::new_v4().simple().to_string()[..8]
+            .chars()
+            .map(|c| char::from(b'g' + c.to_digit(16).unwrap() as u8))
+            .collect();
+        let token = token.as_str();
+        let mut ids = Vec::new();
+        for (name, model) in [
+            (format!("RNK model {token}"), String::from("Generic")),
+            (format!("RNK name {token}"), format!("{token} Pro")),
+            (format!("{token}xya"), String::from("Generic")),
+        ] {
+            ids.push(
+                create_fixture(
+                    &client,
+                    "printers",
+                    format!(
+                        r#"{{ "name": "{name}", "model": "{model}", "brand": "{brand}", "toner": "{toner}", "drum": "{drum}" }}"#
+                    ),
+                )
+                .await,
+            );
+        }
+
+        let endpoint = format!("http://{}/api/v1/printers/search", var("HOST").unwrap());
+        let found = |q: String| {
+            let request = client.get(&endpoint).query(&[("q", q)]).send();
+            async move {
+                let response = request.await.unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                let results: serde_json::Value = response.json().await.unwrap();
+                results["data"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|printer| printer["id"].as_str().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        // Matching both the name and the model ranks first.
+        assert_eq!(found(token.to_string()).await, [ids[1].as_str(), &ids[0]]);
+
+        // Every word has to match.
+        assert_eq!(found(format!("{token} pro")).await, [ids[1].as_str()]);
+
+        // Words match whole, and operators are plain text.
+        assert_eq!(found(format!("{token}xya")).await, [ids[2].as_str()]);
+        assert_eq!(found(format!("{token}xy")).await, Vec::<String>::new());
+        assert_eq!(found(format!("{token} & !")).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn search_printers_endpoint() {
+        setup();
+        let client = client();
+        let cap: usize = var("SEARCH_MAX_RESULTS").unwrap().parse().unwrap();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let token = &uuid::Uuid::new_v4().simple().to_string()[..8];
+        for i in 0..=cap {
+            create_fixture(
+                &client,
+                "printers",
+                format!(
+                    r#"{{
+                        "name": "SRC {token} {i}",
+                        "model": "TEST model",
+                        "brand": "{brand}",
+                        "toner": "{toner}",
+                        "drum": "{drum}"
+                    }}"#
+                ),
+            )
+            .await;
+        }
+
+        let endpoint = format!("http://{}/api/v1/printers/search", var("HOST").unwrap());
+        let search = |q: String| {
+            let request = client.get(&endpoint).query(&[("q", q)]).send();
+            async move {
+                let response = request.await.unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                response.json::<serde_json::Value>().await.unwrap()
+            }
+        };
+
+        // More matches than the cap.
+        let results = search(token.to_uppercase()).await;
+        assert_eq!(results["data"].as_array().unwrap().len(), cap);
+        assert_eq!(results["truncated"], true);
+
+        let results = search(format!("{token} 0")).await;
+        assert_eq!(results["data"].as_array().unwrap().len(), 1);
+        assert_eq!(results["truncated"], false);
+
+        // Words match whole.
+        let results = search(token[..6].to_string()).await;
+        assert_eq!(results["data"].as_array().unwrap().len(), 0);
+
+        let response = client.get(&endpoint).query(&[("q", " ")]).send().await;
+        assert_eq!(response.unwrap().status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn update_printer_endpoint() {
+        setup();
+        let body = r#"{
+            "id": "4340c4a2-eac5-4b51-9baa-40b398605a8c",
+            "name": "TEST printer",
+            "model": "TEST model",
+            "brand": "4340c4a2-eac5-4b51-9baa-40b498605a8c",
+            "toner": "4340c4a2-eac5-4b51-9baa-40b498605a8c",
+            "drum": "4340c4a2-eac5-4b51-9baa-40b498605a8c"
+        }"#;
+
+        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
+
+        let client = client();
+
+        let response = client
+            .put(endpoint)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn delete_printer_endpoint() {
+        setup();
+        let body = r#"{
+        "id": "4340c4a2-eac5-4b51-9baa-40b498605a8c"
+    }"#;
+
+        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
+
+        let client = client();
+
+        let response = client
+            .delete(endpoint)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn import_printers_csv_endpoint() {
+        setup();
+        let client = client();
+        let brand = unique_name("IMB");
+        let toner = unique_name("IMT");
+        let drum = unique_name("IMD");
+        create_fixture(&client, "brands", format!(r#"{{ "name": "{brand}" }}"#)).await;
+        create_fixture(
+            &client,
+            "supplies/toners",
+            format!(r#"{{ "name": "{toner}" }}"#),
+        )
+        .await;
+        create_fixture(
+            &client,
+            "supplies/drums",
+            format!(r#"{{ "name": "{drum}" }}"#),
+        )
+        .await;
+
+        let printer = unique_name("IMP");
+        let body = format!(
+            "name,model,brand,toner,drum\n\
+             {printer},TEST model,{brand},{toner},{drum}\n\
+             malformed,row\n\
+             {},TEST model,Unknown brand,{toner},{drum}\n",
+            unique_name("IMP")
+        );
+
+        let endpoint = format!("http://{}/api/v1/printers/import", var("HOST").unwrap());
+
+        let response = client
+            .post(endpoint)
+            .header("Content-Type", "text/csv")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let report: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(report["created"], 1);
+        assert_eq!(report["failed"], 2);
+        assert_eq!(report["rows"][0]["status"], "created");
+        assert_eq!(report["rows"][1]["line"], 3);
+    }
+
+    // Movement
+
+    // Count
+
+    #[tokio::test]
+    pub async fn count_all_movements_endpoint() {
+        setup();
+        let endpoint = format!("http://{}/api/v1/movement-count", var("HOST").unwrap());
+        let client = client();
+        let response = client.get(endpoint).send().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    pub async fn count_toner_movements_endpoint() {
+        setup();
+        let endpoint = format!(
+            "http://{}/api/v1/movement-count/toner",
+            var("HOST").unwrap()
+        );
+        let client = client();
+        let response = client.get(endpoint).send().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    pub async fn count_drum_movements_endpoint() {
+        setup();
+        let endpoint = format!("http://{}/api/v1/movement-count/drum", var("HOST").unwrap());
+        let client = client();
+        let response = client.get(endpoint).send().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // Show
+
+    #[tokio::test]
+    pub async fn show_all_movements_endpoint() {
+        setup();
+        let endpoint = format!("http://{}/api/v1/movements", var("HOST").unwrap());
+        let client = client();
+        let response = client.get(endpoint).send().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    pub async fn show_toner_movements_endpoint() {
+        setup();
+        let endpoint = format!("http://{}/api/v1/movements/toner", var("HOST").unwrap());
+        let client = client();
+        let response = client.get(endpoint).send().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    pub async fn show_drum_movements_endpoint() {
+        setup();
+        let endpoint = format!("http://{}/api/v1/movements/drum", var("HOST").unwrap());
+        let client = client();
+        let response = client.get(endpoint).send().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn create_toner_movement_future_timestamp_endpoint() {
+        setup();
+        let body = r#"{
+            "toner_id": "4340c4a2-eac5-4b51-9baa-40b498605a8c",
+            "quantity": 1,
+            "created_at": "2999-01-01T00:00:00Z"
+        }"#;
+
+        let endpoint = format!("http://{}/api/v1/movements/toner", var("HOST").unwrap());
+
+        let client = client();
+
+        let response = client
+            .post(endpoint)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn update_movement_endpoint() {
+        setup();
+        let body = r#"{
+            "id": "4340c4a2-eac5-4b51-9baa-40b398605a8c",
+            "printer_id": "4340c4a2-eac5-4b51-9baa-40b398605a8c",
+            "toner_id": "4340c4a2-eac5-4b51-9baa-40b498605a8c",
+            "quantity": 7
+        }"#;
+
+        let endpoint = format!("http://{}/api/v1/movements", var("HOST").unwrap());
+
+        let client = client();
+
+        let response = client
+            .put(endpoint)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn delete_movement_endpoint() {
+        setup();
+        let body = r#"{
+        "id": "4340c4a2-eac5-4b51-9baa-40b498605a8c"
+    }"#;
+
+        let endpoint = format!("http://{}/api/v1/movements", var("HOST").unwrap());
+
+        let client = client();
+
+        let response = client
+            .delete(endpoint)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    // Activity
+
+    #[tokio::test]
+    async fn activity_endpoint() {
+        // Pages large enough to hold every change since `since`, whatever
+        // the tests running alongside change.
+        let server = TestServer::start(&[("MAX_PAGE_SIZE", "1000000")]).await;
+        let client = client();
+        let since = (chrono::Utc::now() - chrono::Duration::seconds(5)).to_rfc3339();
+        let brand = create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("ACT")),
+        )
+        .await;
+
+        let endpoint = server.url("activity");
+        let fetch = || async {
+            let response = client
+                .get(&endpoint)
+                .query(&[("since", since.as_str()), ("limit", "1000000")])
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let activity: Vec<serde_json::Value> = response.json().await.unwrap();
+            activity
+                .into_iter()
+                .find(|entry| entry["id"] == brand.as_str())
+                .unwrap()
+        };
+
+        let entry = fetch().await;
+        assert_eq!(entry["type"], "brand");
+        assert_eq!(entry["action"], "created");
+
+        let response = client
+            .put(format!("http://{}/api/v1/brands", var("HOST").unwrap()))
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{ "id": "{brand}", "name": "{}" }}"#,
+                unique_name("ACT")
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        assert_eq!(fetch().await["action"], "updated");
+    }
+
+    #[tokio::test]
+    async fn timestamp_format_endpoint() {
+        setup();
+        let client = client();
+        create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("TSF")),
+        )
+        .await;
+
+        let is_utc_seconds = |value: &serde_json::Value| {
+            let value = value.as_str().unwrap();
+            // `2024-01-02T03:04:05Z`
+            value.len() == 20
+                && value.ends_with('Z')
+                && chrono::DateTime::parse_from_rfc3339(value).is_ok()
+        };
+
+        let endpoint = format!("http://{}/api/v1/activity", var("HOST").unwrap());
+        let activity: Vec<serde_json::Value> = client
+            .get(endpoint)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(!activity.is_empty());
+        for entry in &activity {
+            assert!(is_utc_seconds(&entry["created_at"]), "{entry}");
+            assert!(is_utc_seconds(&entry["updated_at"]), "{entry}");
+        }
+
+        let endpoint = format!("http://{}/api/v1/status", var("HOST").unwrap());
+        let status: serde_json::Value = client
+            .get(endpoint)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(is_utc_seconds(&status["updated_at"]));
+    }
+
+    // Admin
+
+    #[tokio::test]
+    async fn recompute_counts_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        create_fixture(
+            &client,
+            "printers",
+            format!(
+                r#"{{
+                    "name": "{}",
+                    "model": "TEST model",
+                    "brand": "{brand}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"
+                }}"#,
+                unique_name("RCP")
+            ),
+        )
+        .await;
+
+        // Make the stored count drift behind the API's back.
+        let pool = sqlx::PgPool::connect(&var("DATABASE_URL").unwrap())
+            .await
+            .unwrap();
+        sqlx::query("UPDATE brands SET printer_count = 99 WHERE id = $1;")
+            .bind(uuid::Uuid::parse_str(&brand).unwrap())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let endpoint = format!(
+            "http://{}/api/v1/admin/recompute-counts",
+            var("HOST").unwrap()
+        );
+
+        // While another run holds the operation's lock the request is refused.
+        // Kept in this test so no other test races for the same lock.
+        let mut running = pool.begin().await.unwrap();
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('recompute-counts'));")
+            .execute(&mut *running)
+            .await
+            .unwrap();
+        let response = client.post(&endpoint).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        running.rollback().await.unwrap();
+
+        let response = client.post(&endpoint).send().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let fixed: Vec<serde_json::Value> = response.json().await.unwrap();
+        let discrepancy = fixed
+            .iter()
+            .find(|row| row["brand"] == brand.as_str())
+            .unwrap();
+        assert_eq!(discrepancy["stored"], 99);
+        assert_eq!(discrepancy["actual"], 1);
+    }
+
+    #[tokio::test]
+    async fn merge_brands_endpoint() {
+        setup();
+        let _turn = ADMIN_OPERATIONS.lock().await;
+        let client = client();
+        let (from, toner, drum) = create_printer_fixtures(&client).await;
+        let into = create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("MRG")),
+        )
+        .await;
+        let printer = create_fixture(
+            &client,
+            "printers",
+            format!(
+                r#"{{
+                    "name": "{}",
+                    "model": "TEST model",
+                    "brand": "{from}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"
+                }}"#,
+                unique_name("MRP")
+            ),
+        )
+        .await;
+        create_fixture(
+            &client,
+            "supplies/toners",
+            format!(
+                r#"{{ "name": "{}", "brand": "{from}" }}"#,
+                unique_name("MRT")
+            ),
+        )
+        .await;
+
+        let endpoint = format!("http://{}/api/v1/admin/brands/merge", var("HOST").unwrap());
+
+        // A brand cannot be merged into itself.
+        let response = client
+            .post(&endpoint)
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "from": "{from}", "into": "{from}" }}"#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Both brands must exist.
+        let response = client
+            .post(&endpoint)
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{ "from": "{from}", "into": "4340c4a2-eac5-4b51-9baa-40b498605a8c" }}"#
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = client
+            .post(&endpoint)
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "from": "{from}", "into": "{into}" }}"#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let merged: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(merged["printers"], 1);
+        assert_eq!(merged["toners"], 1);
+
+        let host = var("HOST").unwrap();
+        let response = client
+            .get(format!("http://{host}/api/v1/brand/{from}"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let printer: serde_json::Value = client
+            .get(format!("http://{host}/api/v1/printer/{printer}"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(printer["brand"], into.as_str());
+
+        // Toners of both brands with the same name and color
+        let name = unique_name("MRS");
+        let mut brands = Vec::new();
+        for _ in 0..2 {
+            let brand = create_fixture(
+                &client,
+                "brands",
+                format!(r#"{{ "name": "{}" }}"#, unique_name("MRC")),
+            )
+            .await;
+            create_fixture(
+                &client,
+                "supplies/toners",
+                format!(r#"{{ "name": "{name}", "brand": "{brand}", "color": "Black" }}"#),
+            )
+            .await;
+            brands.push(brand);
+        }
+        let response = client
+            .post(&endpoint)
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{ "from": "{}", "into": "{}" }}"#,
+                brands[0], brands[1]
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let message: String = response.json().await.unwrap();
+        assert_eq!(
+            message,
+            "Both brands have toners with the same name and color."
+        );
+    }
+
+    #[tokio::test]
+    async fn integrity_check_endpoint() {
+        setup();
+        let client = client();
+        let (_, toner, drum) = create_printer_fixtures(&client).await;
+
+        // Leave a printer pointing to a missing brand, as a manual edit would.
+        // Re-adding the foreign key as `NOT VALID` skips the rows already
+        // there, needing only ownership of the table; other writes stay
+        // checked throughout.
+        let pool = sqlx::PgPool::connect(&var("DATABASE_URL").unwrap())
+            .await
+            .unwrap();
+        let printer = uuid::Uuid::new_v4();
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query("ALTER TABLE printers DROP CONSTRAINT printers_brand_fkey;")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO printers (id, name, model, brand, toner, drum) VALUES ($1, $2, 'TEST model', $3, $4, $5);",
+        )
+        .bind(printer)
+        .bind(unique_name("DNG"))
+        .bind(uuid::Uuid::new_v4())
+        .bind(uuid::Uuid::parse_str(&toner).unwrap())
+        .bind(uuid::Uuid::parse_str(&drum).unwrap())
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+        sqlx::query(
+            "ALTER TABLE printers ADD CONSTRAINT printers_brand_fkey FOREIGN KEY (brand) REFERENCES brands(id) NOT VALID;",
+        )
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+        tx.commit().await.unwrap();
+
+        let response = client
+            .get(format!(
+                "http://{}/api/v1/admin/integrity-check",
+                var("HOST").unwrap()
+            ))
+            .send()
+            .await
+            .unwrap();
+
+        sqlx::query("DELETE FROM printers WHERE id = $1;")
+            .bind(printer)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("ALTER TABLE printers VALIDATE CONSTRAINT printers_brand_fkey;")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let report: serde_json::Value = response.json().await.unwrap();
+        let listed = |problem: &str| {
+            report[problem]
+                .as_array()
+                .unwrap()
+                .contains(&serde_json::json!(printer))
+        };
+        assert!(listed("printers_missing_brand"));
+        assert!(!listed("printers_missing_toner"));
+        assert!(!listed("printers_missing_drum"));
+    }
+
+    #[tokio::test]
+    async fn export_in_flight_endpoint() {
+        // Its own server, so the count is this test's requests alone.
+        let server = TestServer::start(&[]).await;
+        let client = client();
+        let pool = sqlx::PgPool::connect(&var("DATABASE_URL").unwrap())
+            .await
+            .unwrap();
+        let in_flight = || async {
+            let status: serde_json::Value = client
+                .get(server.url("status"))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            status["requests"]["in_flight"].as_u64().unwrap()
+        };
+
+        // The export answers before reading its first section, which waits
+        // on this lock while the body is still being streamed.
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query("LOCK TABLE brands IN ACCESS EXCLUSIVE MODE;")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        let (holder,): (i32,) = sqlx::query_as("SELECT pg_backend_pid();")
+            .fetch_one(&mut *tx)
+            .await
+            .unwrap();
+
+        let response = client.get(server.url("admin/export")).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        wait_until_blocked(&pool, holder).await;
+
+        // The export and the status request itself.
+        assert_eq!(in_flight().await, 2);
+
+        tx.commit().await.unwrap();
+        let backup: serde_json::Value = response.json().await.unwrap();
+        assert!(backup["brands"].is_array());
+        assert_eq!(in_flight().await, 1);
+    }
+
+    #[tokio::test]
+    async fn export_catalog_endpoint() {
+        setup();
+        let client = client();
+        let printer = create_printer_fixture(&client).await;
+
+        let response = client
+            .get(format!(
+                "http://{}/api/v1/admin/export",
+                var("HOST").unwrap()
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let backup: serde_json::Value = response.json().await.unwrap();
+        for section in ["brands", "toners", "drums", "printers"] {
+            assert!(backup[section].is_array(), "{section}");
+        }
+        let exported = backup["printers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|row| row["id"] == printer.as_str())
+            .unwrap();
+        assert!(backup["brands"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|row| row["id"] == exported["brand"]));
+    }
+
+    #[tokio::test]
+    async fn import_incomplete_export_endpoint() {
+        setup();
+        let client = client();
+
+        // As written by an export that failed after the brands.
+        let response = client
+            .post(format!(
+                "http://{}/api/v1/admin/import",
+                var("HOST").unwrap()
+            ))
+            .header("Content-Type", "application/json")
+            .body(r#"{"brands":[],"error":"Catalog export timed out."}"#)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn import_catalog_endpoint() {
+        setup();
+        let _turn = ADMIN_OPERATIONS.lock().await;
+        let client = client();
+        let endpoint = format!("http://{}/api/v1/admin/import", var("HOST").unwrap());
+
+        // While another import runs the request is refused.
+        let pool = sqlx::PgPool::connect(&var("DATABASE_URL").unwrap())
+            .await
+            .unwrap();
+        let mut running = pool.begin().await.unwrap();
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('import-catalog'));")
+            .execute(&mut *running)
+            .await
+            .unwrap();
+        let response = client
+            .post(&endpoint)
+            .header("Content-Type", "application/json")
+            .body("{}")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        running.rollback().await.unwrap();
+        let (brand, toner, drum, printer) = (
+            uuid::Uuid::new_v4(),
+            uuid::Uuid::new_v4(),
+            uuid::Uuid::new_v4(),
+            uuid::Uuid::new_v4(),
+        );
+        let brand_name = unique_name("IMB");
+        let backup = |model: &str| {
+            serde_json::json!({
+                "brands": [
+                    { "id": brand, "name": brand_name, "logo_url": null, "printer_count": 0 },
+                    // Same name under another id.
+                    { "id": uuid::Uuid::new_v4(), "name": brand_name, "logo_url": null, "printer_count": 0 }
+                ],
+                "toners": [
+                    { "id": toner, "name": unique_name("IMT"), "stock": 3, "brand": brand, "min_stock": 1 }
+                ],
+                "drums": [{ "id": drum, "name": unique_name("IMD"), "stock": 2 }],
+                "printers": [{
+                    "id": printer,
+                    "name": unique_name("IMP"),
+                    "model": model,
+                    "brand": brand,
+                    "toner": toner,
+                    "drum": drum,
+                    "owner_department": null,
+                    "page_count": 10
+                }]
+            })
+        };
+        let import = |query: &'static str, body: serde_json::Value| {
+            client
+                .post(format!("{endpoint}{query}"))
+                .header("Content-Type", "application/json")
+                .body(body.to_string())
+                .send()
+        };
+
+        let response = import("", backup("TEST model")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let report: serde_json::Value = response.json().await.unwrap();
+        for section in ["brands", "toners", "drums", "printers"] {
+            assert_eq!(report[section]["imported"], 1, "{section}");
+        }
+        let conflicts = report["conflicts"].as_array().unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0]["type"], "brand");
+
+        // Existing ids are skipped by default...
+        let report: serde_json::Value = import("", backup("Other model"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(report["printers"]["skipped"], 1);
+
+        // ...and replaced when overwriting.
+        let report: serde_json::Value = import("?overwrite=true", backup("Other model"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(report["printers"]["imported"], 1);
+
+        let restored: serde_json::Value = client
+            .get(format!(
+                "http://{}/api/v1/printer/{printer}",
+                var("HOST").unwrap()
+            ))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(restored["model"], "Other model");
+        assert_eq!(restored["page_count"], 10);
+    }
+
+    // Existence
+
+    #[tokio::test]
+    async fn exists_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let missing = "4340c4a2-eac5-4b51-9baa-40b498605a8c";
+
+        let endpoint = format!("http://{}/api/v1/exists", var("HOST").unwrap());
+        let response = client
+            .post(endpoint)
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{
+                    "brands": ["{brand}", "{missing}"],
+                    "toners": ["{toner}"],
+                    "drums": ["{drum}"]
+                }}"#
+            ))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["brands"][&brand], true);
+        assert_eq!(body["brands"][missing], false);
+        assert_eq!(body["toners"][&toner], true);
+        assert_eq!(body["drums"][&drum], true);
+    }
+
+    // Maintenance
+
+    #[tokio::test]
+    async fn maintenance_schedule_endpoint() {
+        setup();
+        let client = client();
+        let printer = create_printer_fixture(&client).await;
+        let endpoint = format!(
+            "http://{}/api/v1/maintenance-schedules",
+            var("HOST").unwrap()
+        );
+
+        let response = client
+            .post(&endpoint)
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{ "printer_id": "{printer}", "interval_days": 0 }}"#
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let last_done_at = (chrono::Utc::now() - chrono::Duration::days(10)).to_rfc3339();
+        let schedule = create_fixture(
+            &client,
+            "maintenance-schedules",
+            format!(
+                r#"{{
+                    "printer_id": "{printer}",
+                    "interval_days": 7,
+                    "last_done_at": "{last_done_at}"
+                }}"#
+            ),
+        )
+        .await;
+
+        let is_due = |due: &[serde_json::Value]| {
+            due.iter()
+                .any(|row| row["schedule_id"] == schedule.as_str())
+        };
+
+        let due: Vec<serde_json::Value> = client
+            .get(format!("{endpoint}/due"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(is_due(&due));
+
+        let response = client
+            .post(format!(
+                "http://{}/api/v1/printers/{printer}/maintenance-done",
+                var("HOST").unwrap()
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let due: Vec<serde_json::Value> = client
+            .get(format!("{endpoint}/due"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(!is_due(&due));
+    }
+
+    #[tokio::test]
+    async fn id_format_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let simple = |id: &str| uuid::Uuid::parse_str(id).unwrap().simple().to_string();
+
+        // Ids are accepted without hyphens on input.
+        let printer = create_fixture(
+            &client,
+            "printers",
+            format!(
+                r#"{{ "name": "{}", "model": "Simple", "brand": "{}", "toner": "{}", "drum": "{}" }}"#,
+                unique_name("IDF"),
+                simple(&brand),
+                simple(&toner),
+                simple(&drum)
+            ),
+        )
+        .await;
+        assert_eq!(printer.len(), 36);
+
+        let endpoint = format!(
+            "http://{}/api/v1/printer/{}",
+            var("HOST").unwrap(),
+            simple(&printer)
+        );
+
+        // Hyphenated by default.
+        let body: serde_json::Value = client
+            .get(&endpoint)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body["id"], printer);
+        assert_eq!(body["brand"], brand);
+
+        // Simple on request, and the ids round-trip.
+        let response = client
+            .get(&endpoint)
+            .header("Accept", "application/json; ids=simple")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["id"], simple(&printer));
+        assert_eq!(body["brand"], simple(&brand));
+        assert_eq!(body["toner"], simple(&toner));
+        assert_eq!(body["drum"], simple(&drum));
+
+        let id = body["id"].as_str().unwrap();
+        let response = client
+            .get(format!(
+                "http://{}/api/v1/printer/{id}",
+                var("HOST").unwrap()
+            ))
+            .header("Accept", "application/json; ids=simple")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["id"], id);
+
+        // Braced ids round-trip as well.
+        let body: serde_json::Value = client
+            .get(&endpoint)
+            .header("Accept", "application/json; ids=braced")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let braced = body["id"].as_str().unwrap().to_string();
+        assert_eq!(braced, format!("{{{printer}}}"));
+        let response = client
+            .get(format!(
+                "http://{}/api/v1/printer/{}",
+                var("HOST").unwrap(),
+                braced.replace('{', "%7B").replace('}', "%7D")
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn id_format_fields_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let model = uuid::Uuid::new_v4().to_string();
+        let printer = create_fixture(
+            &client,
+            "printers",
+            format!(
+                r#"{{
+                    "name": "{}",
+                    "model": "{model}",
+                    "brand": "{brand}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"
+                }}"#,
+                unique_name("IDF")
+            ),
+        )
+        .await;
+
+        // Only id fields are rewritten, not text that looks like one.
+        let body: serde_json::Value = client
+            .get(format!(
+                "http://{}/api/v1/printer/{printer}",
+                var("HOST").unwrap()
+            ))
+            .header("Accept", "application/json; ids=simple")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body["id"], printer.replace('-', ""));
+        assert_eq!(body["model"], model);
+
+        // The streamed export is passed through untouched.
+        let response = client
+            .get(format!(
+                "http://{}/api/v1/admin/export",
+                var("HOST").unwrap()
+            ))
+            .header("Accept", "application/json; ids=simple")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let backup: serde_json::Value = response.json().await.unwrap();
+        assert!(backup["printers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|row| row["id"] == printer));
+    }
+
+    #[tokio::test]
+    async fn brand_detail_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let own_toner = create_fixture(
+            &client,
+            "supplies/toners",
+            format!(
+                r#"{{ "name": "{}", "brand": "{brand}" }}"#,
+                unique_name("BDT")
+            ),
+        )
+        .await;
+        let mut printers = Vec::new();
+        for _ in 0..2 {
+            printers.push(
+                create_fixture(
+                    &client,
+                    "printers",
+                    format!(
+                        r#"{{ "name": "{}", "model": "Detail", "brand": "{brand}", "toner": "{toner}", "drum": "{drum}" }}"#,
+                        unique_name("BDP")
+                    ),
+                )
+                .await,
+            );
+        }
+        printers.sort();
+
+        let response = client
+            .get(format!(
+                "http://{}/api/v1/brands/{brand}/detail",
+                var("HOST").unwrap()
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        let ids = |key: &str| -> Vec<String> {
+            body[key]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|item| item["id"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        assert_eq!(body["id"], brand);
+        assert_eq!(ids("printers"), printers);
+        let mut toners = vec![toner.clone(), own_toner];
+        toners.sort();
+        assert_eq!(ids("toners"), toners);
+        assert_eq!(ids("drums"), vec![drum]);
+        assert_eq!(body["totals"]["printers"], 2);
+        assert_eq!(body["totals"]["toners"], 2);
+        assert_eq!(body["totals"]["drums"], 1);
+
+        // Unknown brand
+        let response = client
+            .get(format!(
+                "http://{}/api/v1/brands/{}/detail",
+                var("HOST").unwrap(),
+                uuid::Uuid::new_v4()
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn duplicate_toner_warning_endpoint() {
+        setup();
+        let client = client();
+        let brand = create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("DTB")),
+        )
+        .await;
+        let endpoint = format!("http://{}/api/v1/supplies/toners", var("HOST").unwrap());
+        let create = |color: &str, page_yield: i32| {
+            client
+                .post(&endpoint)
+                .header("Content-Type", "application/json")
+                .body(format!(
+                    r#"{{ "name": "{}", "brand": "{brand}", "color": "{color}", "page_yield": {page_yield} }}"#,
+                    unique_name("DT")
+                ))
+                .send()
+        };
+
+        let response = create("Black", 3000).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let first: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(first["warnings"], serde_json::json!([]));
+
+        // Same brand, color and yield: created, with a warning.
+        let response = create(" black", 3000).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let second: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(second["warnings"].as_array().unwrap().len(), 1);
+
+        // Another yield is a different cartridge.
+        let response = create("Black", 6000).await.unwrap();
+        let third: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(third["warnings"], serde_json::json!([]));
+
+        // Invalid yield
+        let response = create("Black", 0).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let duplicates: Vec<serde_json::Value> = client
+            .get(format!("{endpoint}/duplicates"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let group = duplicates
+            .iter()
+            .find(|group| group["brand"] == brand)
+            .unwrap();
+        assert_eq!(group["color"], "black");
+        assert_eq!(group["page_yield"], 3000);
+        let mut ids = vec![first["id"].clone(), second["id"].clone()];
+        ids.sort_by_key(|id| id.as_str().unwrap().to_string());
+        assert_eq!(group["toners"], serde_json::json!(ids));
+        assert_eq!(
+            duplicates
+                .iter()
+                .filter(|group| group["brand"] == brand)
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn base_path_links_endpoint() {
+        let server = TestServer::start(&[("BASE_PATH", "/inventory")]).await;
+        let client = client();
+
+        for path in ["supplies/toners", "printers", "movements"] {
+            let page: serde_json::Value = client
+                .get(server.url(&format!("{path}?limit=1&offset=1")))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            assert_eq!(
+                page["_links"]["first"],
+                format!("/inventory/api/v1/{path}?limit=1&offset=0")
+            );
+            assert_eq!(
+                page["_links"]["prev"],
+                format!("/inventory/api/v1/{path}?limit=1&offset=0")
+            );
+        }
+
+        // Created resources are located under the prefix too.
+        let response = client
+            .post(server.url("brands"))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "name": "{}" }}"#, unique_name("BPL")))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let location = response.headers()["location"].to_str().unwrap().to_owned();
+        let id: String = response.json().await.unwrap();
+        assert_eq!(location, format!("/inventory/api/v1/brand/{id}"));
+    }
+
+    #[tokio::test]
+    async fn printers_grouped_by_toner_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let mut printers = Vec::new();
+        for _ in 0..2 {
+            printers.push(
+                create_fixture(
+                    &client,
+                    "printers",
+                    format!(
+                        r#"{{ "name": "{}", "model": "Grouped", "brand": "{brand}", "toner": "{toner}", "drum": "{drum}" }}"#,
+                        unique_name("GBT")
+                    ),
+                )
+                .await,
+            );
+        }
+        printers.sort();
+        let endpoint = format!(
+            "http://{}/api/v1/printers/grouped-by-toner",
+            var("HOST").unwrap()
+        );
+
+        let groups: Vec<serde_json::Value> = client
+            .get(format!("{endpoint}?toner={toner}"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0]["toner"]["id"], toner);
+        assert!(groups[0]["toner"]["name"]
+            .as_str()
+            .unwrap()
+            .starts_with("FXT"));
+        let ids: Vec<&str> = groups[0]["printers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|printer| printer["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, printers);
+
+        // Without a filter, each toner appears in a single group.
+        let groups: Vec<serde_json::Value> = client
+            .get(&endpoint)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let mut toners: Vec<&str> = groups
+            .iter()
+            .map(|group| group["toner"]["id"].as_str().unwrap())
+            .collect();
+        assert!(toners.contains(&toner.as_str()));
+        let count = toners.len();
+        toners.sort();
+        toners.dedup();
+        assert_eq!(toners.len(), count);
+    }
+
+    #[tokio::test]
+    async fn printer_name_same_as_model_endpoint() {
+        let strict = TestServer::start(&[("STRICT_VALIDATION", "true")]).await;
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let create = |endpoint: String| {
+            let name = unique_name("NSM");
+            client
+                .post(endpoint)
+                .header("Content-Type", "application/json")
+                .body(format!(
+                    r#"{{ "name": "{name}", "model": "{}", "brand": "{brand}", "toner": "{toner}", "drum": "{drum}" }}"#,
+                    name.to_lowercase()
+                ))
+                .send()
+        };
+        let message = "Printer name is the same as its model.";
+
+        // A warning by default...
+        let response = create(format!("http://{}/api/v1/printers", var("HOST").unwrap()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["warnings"], serde_json::json!([message]));
+
+        // ...and a rejection under `STRICT_VALIDATION`.
+        let response = create(strict.url("printers")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body, message);
+    }
+
+    #[tokio::test]
+    async fn decommission_printer_endpoint() {
+        setup();
+        let client = client();
+        let (printer, toner) = {
+            let (brand, toner, drum) = create_printer_fixtures(&client).await;
+            let printer = create_fixture(
+                &client,
+                "printers",
+                format!(
+                    r#"{{ "name": "{}", "model": "Retiring", "brand": "{brand}", "toner": "{toner}", "drum": "{drum}" }}"#,
+                    unique_name("DCP")
+                ),
+            )
+            .await;
+            (printer, toner)
+        };
+        let host = var("HOST").unwrap();
+        let endpoint = format!("http://{host}/api/v1/printers/{printer}/decommission");
+        let decommission = |body: &'static str| {
+            client
+                .post(&endpoint)
+                .header("Content-Type", "application/json")
+                .body(body)
+                .send()
+        };
+
+        // Empty reason
+        let response = decommission(r#"{ "reason": " " }"#).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = decommission(r#"{ "reason": "End of lease", "return_toner": true }"#)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["id"], printer);
+        assert_eq!(body["status"], "retired");
+        assert_eq!(body["decommission_reason"], "End of lease");
+        assert!(
+            chrono::DateTime::parse_from_rfc3339(body["decommissioned_at"].as_str().unwrap())
+                .is_ok()
+        );
+
+        // The installed toner is back in stock.
+        let toner: serde_json::Value = client
+            .get(format!("http://{host}/api/v1/supplies/toner/{toner}"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(toner["stock"], 1);
+
+        // Already retired
+        let response = decommission(r#"{ "reason": "Again" }"#).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        // Unknown printer
+        let response = client
+            .post(format!(
+                "http://{host}/api/v1/printers/{}/decommission",
+                uuid::Uuid::new_v4()
+            ))
+            .header("Content-Type", "application/json")
+            .body(r#"{ "reason": "Missing" }"#)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn pretty_json_endpoint() {
+        setup();
+        let client = client();
+        let brand = create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("PJB")),
+        )
+        .await;
+        let endpoint = format!("http://{}/api/v1/brand/{brand}", var("HOST").unwrap());
+
+        let minified = client.get(&endpoint).send().await.unwrap();
+        assert_eq!(minified.status(), StatusCode::OK);
+        let minified = minified.text().await.unwrap();
+        assert!(!minified.contains('\n'));
+
+        let pretty = client
+            .get(format!("{endpoint}?pretty=true"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(pretty.status(), StatusCode::OK);
+        let pretty = pretty.text().await.unwrap();
+        assert!(pretty.contains("\n  \""));
+
+        // Same document either way.
+        let minified: serde_json::Value = serde_json::from_str(&minified).unwrap();
+        let pretty: serde_json::Value = serde_json::from_str(&pretty).unwrap();
+        assert_eq!(pretty, minified);
+    }
+
+    #[tokio::test]
+    async fn name_not_allowed_endpoint() {
+        setup();
+        let client = client();
+        let response = client
+            .post(format!("http://{}/api/v1/brands", var("HOST").unwrap()))
+            .header("Content-Type", "application/json")
+            .body(r#"{ "name": "Tab\tBrand" }"#)
+            .send()
+            .await
+            .unwrap();
+
+        // Control characters are never allowed.
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["code"], "NAME_NOT_ALLOWED");
+        assert_eq!(
+            body["message"],
+            "Brand name contains characters that are not allowed."
+        );
+
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let response = client
+            .post(format!("http://{}/api/v1/printers", var("HOST").unwrap()))
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{ "name": "Bell\u0007", "model": "Bell", "brand": "{brand}", "toner": "{toner}", "drum": "{drum}" }}"#
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["code"], "NAME_NOT_ALLOWED");
+    }
+
+    #[tokio::test]
+    async fn name_pattern_endpoint() {
+        let server = TestServer::start(&[("NAME_PATTERN", "[A-Z][A-Za-z0-9 ]*")]).await;
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let post = |path: &str, body: String| {
+            client
+                .post(server.url(path))
+                .header("Content-Type", "application/json")
+                .body(body)
+                .send()
+        };
+
+        // Names matching the pattern are taken as usual.
+        let response = post(
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("NPB")),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        // Others are rejected, with the pattern they had to match.
+        for (path, body, message) in [
+            (
+                "brands",
+                format!(r#"{{ "name": "{}" }}"#, unique_name("npb")),
+                "Brand name contains characters that are not allowed.",
+            ),
+            (
+                "printers",
+                format!(
+                    r#"{{ "name": "{}", "model": "Pattern", "brand": "{brand}", "toner": "{toner}", "drum": "{drum}" }}"#,
+                    unique_name("NP-")
+                ),
+                "Printer name contains characters that are not allowed.",
+            ),
+        ] {
+            let response = post(path, body).await.unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{path}");
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["code"], "NAME_NOT_ALLOWED");
+            assert_eq!(body["message"], message);
+            assert_eq!(body["params"]["pattern"], "[A-Z][A-Za-z0-9 ]*");
+        }
+
+        // The configuration shows the pattern as set, the schema anchored.
+        let config: serde_json::Value = client
+            .get(server.url("admin/config"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(config["name_pattern"], "[A-Z][A-Za-z0-9 ]*");
+        let schema: serde_json::Value = client
+            .get(server.url("schema/brand"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(
+            schema["properties"]["name"]["pattern"],
+            "^(?:[A-Z][A-Za-z0-9 ]*)$"
+        );
+    }
+
+    #[tokio::test]
+    async fn recent_ops_endpoint() {
+        // Its own server, so no other test's mutations push this one out.
+        let server = TestServer::start(&[]).await;
+        let client = client();
+        let path = format!("/api/v1/printers/{}/decommission", uuid::Uuid::new_v4());
+        let response = client
+            .post(server.url(path.trim_start_matches("/api/v1/")))
+            .header("Content-Type", "application/json")
+            .body(r#"{ "reason": "Missing" }"#)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = client
+            .get(server.url("admin/recent-ops"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let operations: Vec<serde_json::Value> = response.json().await.unwrap();
+        assert_eq!(operations.len(), 1);
+        let operation = operations
+            .iter()
+            .find(|operation| operation["path"] == path)
+            .unwrap();
+        assert_eq!(operation["method"], "POST");
+        assert_eq!(operation["status"], 404);
+        assert!(chrono::DateTime::parse_from_rfc3339(operation["at"].as_str().unwrap()).is_ok());
+
+        // Reads are not recorded.
+        assert!(operations
+            .iter()
+            .all(|operation| operation["method"] != "GET"));
+
+        // Admin only
+        if let Some(key) = api_key("read") {
+            let response = reqwest::Client::new()
+                .get(server.url("admin/recent-ops"))
+                .header("X-API-Key", key)
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+    }
+
+    #[tokio::test]
+    async fn create_location_endpoint() {
+        setup();
+        let client = client();
+        let base = var("BASE_PATH").unwrap_or_default();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let printer = format!(
+            r#"{{ "name": "{}", "model": "Located", "brand": "{brand}", "toner": "{toner}", "drum": "{drum}" }}"#,
+            unique_name("LOC")
+        );
+
+        for (collection, item, body) in [
+            (
+                "brands",
+                "brand",
+                format!(r#"{{ "name": "{}" }}"#, unique_name("LOC")),
+            ),
+            (
+                "supplies/toners",
+                "supplies/toner",
+                format!(r#"{{ "name": "{}" }}"#, unique_name("LOC")),
+            ),
+            (
+                "supplies/drums",
+                "supplies/drum",
+                format!(r#"{{ "name": "{}" }}"#, unique_name("LOC")),
+            ),
+            ("printers", "printer", printer),
+        ] {
+            let response = client
+                .post(format!(
+                    "http://{}/api/v1/{collection}",
+                    var("HOST").unwrap()
+                ))
+                .header("Content-Type", "application/json")
+                .body(body)
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+            let location = response.headers()[reqwest::header::LOCATION]
+                .to_str()
+                .unwrap()
+                .to_string();
+            let created: serde_json::Value = response.json().await.unwrap();
+            let id = created.get("id").unwrap_or(&created).as_str().unwrap();
+            assert_eq!(location, format!("{base}/api/v1/{item}/{id}"));
+
+            // The header points at the new resource
+            let response = client
+                .get(format!(
+                    "http://{}{}",
+                    var("HOST").unwrap(),
+                    location.strip_prefix(&base).unwrap()
+                ))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    }
+
+    #[tokio::test]
+    async fn bulk_printer_status_endpoint() {
+        setup();
+        let client = client();
+        let host = var("HOST").unwrap();
+        let active = create_printer_fixture(&client).await;
+        let retired = create_printer_fixture(&client).await;
+        let missing = uuid::Uuid::new_v4();
+        let response = client
+            .post(format!(
+                "http://{host}/api/v1/printers/{retired}/decommission"
+            ))
+            .header("Content-Type", "application/json")
+            .body(r#"{ "reason": "Broken" }"#)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bulk_status = |body: String| {
+            client
+                .post(format!("http://{host}/api/v1/printers/bulk-status"))
+                .header("Content-Type", "application/json")
+                .body(body)
+                .send()
+        };
+
+        let response = bulk_status(format!(
+            r#"{{ "ids": ["{active}", "{retired}", "{missing}", "{missing}"], "status": "maintenance" }}"#
+        ))
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["updated"], 1);
+        assert_eq!(body["not_found"], serde_json::json!([missing]));
+        assert_eq!(body["retired"], serde_json::json!([retired]));
+
+        // Only the active printer changed
+        for (id, status) in [(&active, "maintenance"), (&retired, "retired")] {
+            let printer: serde_json::Value = client
+                .get(format!("http://{host}/api/v1/printer/{id}"))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            assert_eq!(printer["status"], status);
+        }
+
+        // Empty or oversized lists, retiring in bulk and unknown statuses are
+        // rejected
+        let too_many: Vec<uuid::Uuid> = (0..1001).map(|_| uuid::Uuid::new_v4()).collect();
+        for body in [
+            r#"{ "ids": [], "status": "maintenance" }"#.to_string(),
+            serde_json::json!({ "ids": too_many, "status": "maintenance" }).to_string(),
+            format!(r#"{{ "ids": ["{active}"], "status": "retired" }}"#),
+            format!(r#"{{ "ids": ["{active}"], "status": "broken" }}"#),
+        ] {
+            let response = bulk_status(body).await.unwrap();
+            assert!(response.status().is_client_error());
+        }
+    }
+
+    #[tokio::test]
+    async fn printer_documents_endpoint() {
+        setup();
+        let client = client();
+        let host = var("HOST").unwrap();
+        let printer = create_printer_fixture(&client).await;
+        let endpoint = format!("http://{host}/api/v1/printers/{printer}/documents");
+        let add = |body: &'static str| {
+            client
+                .post(&endpoint)
+                .header("Content-Type", "application/json")
+                .body(body)
+                .send()
+        };
+
+        // Invalid label or URL
+        for body in [
+            r#"{ "label": " ", "url": "https://example.com/manual.pdf" }"#,
+            r#"{ "label": "Manual", "url": "ftp://example.com/manual.pdf" }"#,
+            r#"{ "label": "Manual", "url": "manual.pdf" }"#,
+        ] {
+            let response = add(body).await.unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+
+        let response = add(r#"{ "label": "Manual", "url": "https://example.com/manual.pdf" }"#)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let document: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(document["printer_id"], printer);
+        assert_eq!(document["label"], "Manual");
+        let document = document["id"].as_str().unwrap().to_string();
+
+        // Listed on its own and in the printer detail
+        let documents: Vec<serde_json::Value> = client
+            .get(&endpoint)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(documents.len(), 1);
+        let detail: serde_json::Value = client
+            .get(format!("http://{host}/api/v1/printer/{printer}"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(detail["id"], printer);
+        assert_eq!(
+            detail["documents"][0]["url"],
+            "https://example.com/manual.pdf"
+        );
+
+        // Capped per printer
+        let max: usize = var("PRINTER_MAX_DOCUMENTS")
+            .ok()
+            .and_then(|max| max.parse().ok())
+            .unwrap_or(20);
+        for _ in 1..max {
+            let response = add(r#"{ "label": "Driver", "url": "https://example.com/driver" }"#)
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+        let response = add(r#"{ "label": "Driver", "url": "https://example.com/driver" }"#)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let remove = || client.delete(format!("{endpoint}/{document}")).send();
+        let response = remove().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = remove().await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // Unknown printer
+        let response = client
+            .post(format!(
+                "http://{host}/api/v1/printers/{}/documents",
+                uuid::Uuid::new_v4()
+            ))
+            .header("Content-Type", "application/json")
+            .body(r#"{ "label": "Manual", "url": "https://example.com/manual.pdf" }"#)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn concurrent_duplicate_brand_endpoint() {
+        setup();
+        let client = client();
+        let endpoint = format!("http://{}/api/v1/brands", var("HOST").unwrap());
+        let name = unique_name("RACE");
+        let pool = sqlx::PgPool::connect(&var("DATABASE_URL").unwrap())
+            .await
+            .unwrap();
+
+        // An uncommitted brand of the same name: the request's duplicate
+        // check does not see it, and its insert waits on the unique index.
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query("INSERT INTO brands (id, name) VALUES (gen_random_uuid(), $1);")
+            .bind(&name)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        let (holder,): (i32,) = sqlx::query_as("SELECT pg_backend_pid();")
+            .fetch_one(&mut *tx)
+            .await
+            .unwrap();
+
+        let request = tokio::spawn(
+            client
+                .post(&endpoint)
+                .header("Content-Type", "application/json")
+                .body(format!(r#"{{ "name": "{name}" }}"#))
+                .send(),
+        );
+
+        wait_until_blocked(&pool, holder).await;
+        tx.commit().await.unwrap();
+
+        let response = request.await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["code"], "DUPLICATE_NAME");
+    }
+
+    #[tokio::test]
+    async fn concurrent_duplicate_toner_endpoint() {
+        setup();
+        let client = client();
+        let endpoint = format!("http://{}/api/v1/supplies/toners", var("HOST").unwrap());
+        let name = unique_name("RACT");
+        let pool = sqlx::PgPool::connect(&var("DATABASE_URL").unwrap())
+            .await
+            .unwrap();
+
+        // An uncommitted toner of the same name and color, stored in another
+        // case, so the request's insert waits on the unique index.
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query("INSERT INTO toners (id, name, color) VALUES (gen_random_uuid(), $1, 'Cyan');")
+            .bind(&name)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        let (holder,): (i32,) = sqlx::query_as("SELECT pg_backend_pid();")
+            .fetch_one(&mut *tx)
+            .await
+            .unwrap();
+
+        let request = tokio::spawn(
+            client
+                .post(&endpoint)
+                .header("Content-Type", "application/json")
+                .body(format!(r#"{{ "name": "{name}", "color": "cyan" }}"#))
+                .send(),
+        );
+        wait_until_blocked(&pool, holder).await;
+        tx.commit().await.unwrap();
+
+        let response = request.await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["code"], "DUPLICATE_TONER");
+        assert_eq!(
+            body["params"],
+            serde_json::json!({ "brand": null, "name": name, "color": "Cyan" })
+        );
+    }
+
+    #[tokio::test]
+    async fn toner_alternatives_endpoint() {
+        setup();
+        let client = client();
+        let host = var("HOST").unwrap();
+        let mut toners = Vec::new();
+        for _ in 0..3 {
+            toners.push(
+                create_fixture(
+                    &client,
+                    "supplies/toners",
+                    format!(r#"{{ "name": "{}" }}"#, unique_name("ALT")),
+                )
+                .await,
+            );
+        }
+        let endpoint =
+            |toner: &str| format!("http://{host}/api/v1/supplies/toners/{toner}/alternatives");
+        let alternatives = |toner: &str| {
+            let request = client.get(endpoint(toner)).send();
+            async move {
+                let response = request.await.unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                let toners: Vec<serde_json::Value> = response.json().await.unwrap();
+                toners
+                    .iter()
+                    .map(|toner| toner["id"].as_str().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
+        let link = |toner: &str, alternative: String| {
+            client
+                .post(endpoint(toner))
+                .header("Content-Type", "application/json")
+                .body(format!(r#"{{ "toner": "{alternative}" }}"#))
+                .send()
+        };
+
+        // Self-links and unknown toners are rejected
+        let response = link(&toners[0], toners[0].clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let response = link(&toners[0], uuid::Uuid::new_v4().to_string())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // Links read both ways, and adding one twice keeps a single link
+        for _ in 0..2 {
+            let response = link(&toners[0], toners[1].clone()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = link(&toners[2], toners[0].clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let mut expected = vec![toners[1].clone(), toners[2].clone()];
+        let mut found = alternatives(&toners[0]).await;
+        expected.sort();
+        found.sort();
+        assert_eq!(found, expected);
+        assert_eq!(alternatives(&toners[1]).await, [toners[0].as_str()]);
+        assert_eq!(alternatives(&toners[2]).await, [toners[0].as_str()]);
+
+        // Removing from either side unlinks both
+        let remove = || {
+            client
+                .delete(format!("{}/{}", endpoint(&toners[1]), toners[0]))
+                .send()
+        };
+        let response = remove().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = remove().await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(alternatives(&toners[0]).await, [toners[2].as_str()]);
+        assert!(alternatives(&toners[1]).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn printer_status_default_endpoint() {
+        setup();
+        let client = client();
+        let host = var("HOST").unwrap();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let body = |status: &str| {
+            format!(
+                r#"{{ "name": "{}", "model": "Defaults", "brand": "{brand}", "toner": "{toner}", "drum": "{drum}"{status} }}"#,
+                unique_name("DEF")
+            )
+        };
+
+        for (status, expected) in [
+            ("", "active"),
+            (r#", "status": "maintenance""#, "maintenance"),
+        ] {
+            let printer = create_fixture(&client, "printers", body(status)).await;
+            let printer: serde_json::Value = client
+                .get(format!("http://{host}/api/v1/printer/{printer}"))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            assert_eq!(printer["status"], expected);
+            assert_eq!(printer["owner_department"], serde_json::Value::Null);
+        }
+
+        // Retiring goes through decommissioning
+        let response = client
+            .post(format!("http://{host}/api/v1/printers"))
+            .header("Content-Type", "application/json")
+            .body(body(r#", "status": "retired""#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn read_only_endpoint() {
+        let read_only = TestServer::start(&[("READ_ONLY", "true")]).await;
+        let client = client();
+        let create = |endpoint: String| {
+            client
+                .post(endpoint)
+                .header("Content-Type", "application/json")
+                .body(format!(r#"{{ "name": "{}" }}"#, unique_name("RO")))
+                .send()
+        };
+
+        let response = create(format!("http://{}/api/v1/brands", var("HOST").unwrap()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = create(read_only.url("brands")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Reads still work, lookups sent as a body included, with a read key
+        let response = client.get(read_only.url("brands")).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let read = api_key("read").unwrap();
+        for (path, body) in [
+            ("exists", format!(r#"{{ "brands": ["{brand}"] }}"#)),
+            (
+                "printers/validate-compatibility",
+                format!(r#"{{ "brand": "{brand}", "toner": "{toner}", "drum": "{drum}" }}"#),
+            ),
+        ] {
+            let response = reqwest::Client::new()
+                .post(read_only.url(path))
+                .header("X-API-Key", &read)
+                .header("Content-Type", "application/json")
+                .body(body)
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn stable_serialization_endpoint() {
+        setup();
+        let client = client();
+        let host = var("HOST").unwrap();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        for _ in 0..2 {
+            create_fixture(
+                &client,
+                "printers",
+                format!(
+                    r#"{{ "name": "{}", "model": "Stable", "brand": "{brand}", "toner": "{toner}", "drum": "{drum}" }}"#,
+                    unique_name("STB")
+                ),
+            )
+            .await;
+        }
+        let missing = uuid::Uuid::new_v4();
+
+        // Keyed responses follow the ids, not the order they were asked in.
+        let exists = |brands: String| {
+            let request = client
+                .post(format!("http://{host}/api/v1/exists"))
+                .header("Content-Type", "application/json")
+                .body(format!(r#"{{ "brands": {brands} }}"#))
+                .send();
+            async move { request.await.unwrap().bytes().await.unwrap() }
+        };
+        assert_eq!(
+            exists(format!(r#"["{brand}", "{missing}"]"#)).await,
+            exists(format!(r#"["{missing}", "{brand}"]"#)).await
+        );
+
+        // Aggregations and nested documents come out byte for byte the same.
+        for path in [
+            format!("printers/grouped-by-toner?toner={toner}"),
+            format!("printers/models?brand={brand}"),
+            format!("brands/{brand}/detail"),
+            format!("brands/{brand}/detail?pretty=true"),
+        ] {
+            let get = || async {
+                client
+                    .get(format!("http://{host}/api/v1/{path}"))
+                    .send()
+                    .await
+                    .unwrap()
+                    .bytes()
+                    .await
+                    .unwrap()
+            };
+            assert_eq!(get().await, get().await, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn table_stats_endpoint() {
+        setup();
+        let client = client();
+        let endpoint = format!("http://{}/api/v1/admin/table-stats", var("HOST").unwrap());
+        create_printer_fixture(&client).await;
+
+        let response = client.get(&endpoint).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let stats: Vec<serde_json::Value> = response.json().await.unwrap();
+        let tables: Vec<&str> = stats
+            .iter()
+            .map(|table| table["table"].as_str().unwrap())
+            .collect();
+        assert_eq!(tables, ["brands", "printers", "toners", "drums"]);
+        for table in &stats {
+            assert!(table["rows"].as_i64().unwrap() >= 1, "{table}");
+            assert!(table["total_bytes"].as_i64().unwrap() > 0, "{table}");
+        }
+
+        // Admin only
+        if let Some(write) = api_key("write") {
+            let response = reqwest::Client::new()
+                .get(&endpoint)
+                .header("X-API-Key", write)
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+    }
+
+    #[tokio::test]
+    async fn nil_id_endpoint() {
+        setup();
+        let client = client();
+        let host = var("HOST").unwrap();
+        let nil = uuid::Uuid::nil();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+
+        for (method, path, body) in [
+            (
+                reqwest::Method::PUT,
+                "brands",
+                format!(r#"{{ "id": "{nil}", "name": "{}" }}"#, unique_name("NIL")),
+            ),
+            (
+                reqwest::Method::DELETE,
+                "printers",
+                format!(r#"{{ "id": "{nil}" }}"#),
+            ),
+            (
+                reqwest::Method::POST,
+                "printers",
+                format!(
+                    r#"{{ "name": "{}", "model": "Nil", "brand": "{nil}", "toner": "{toner}", "drum": "{drum}" }}"#,
+                    unique_name("NIL")
+                ),
+            ),
+            (
+                reqwest::Method::POST,
+                "supplies/toners",
+                format!(
+                    r#"{{ "name": "{}", "brand": "{nil}" }}"#,
+                    unique_name("NIL")
+                ),
+            ),
+            (
+                reqwest::Method::POST,
+                "movements/toner",
+                format!(r#"{{ "toner_id": "{nil}", "quantity": 1 }}"#),
+            ),
+        ] {
+            let response = client
+                .request(method.clone(), format!("http://{host}/api/v1/{path}"))
+                .header("Content-Type", "application/json")
+                .body(body)
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(
+                response.status(),
+                StatusCode::BAD_REQUEST,
+                "{method} {path}"
+            );
+            let message: String = response.json().await.unwrap();
+            assert_eq!(message, "Id cannot be the nil UUID.");
+        }
+
+        // Real ids still go through
+        let response = client
+            .put(format!("http://{host}/api/v1/brands"))
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{ "id": "{brand}", "name": "{}" }}"#,
+                unique_name("NIL")
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn printer_photo_endpoint() {
+        setup();
+
+        fn multipart(content_type: &str, data: &[u8]) -> Vec<u8> {
+            let mut body = format!(
+                "--photo-boundary\r\n\
+                 Content-Disposition: form-data; name=\"photo\"; filename=\"photo\"\r\n\
+                 Content-Type: {content_type}\r\n\r\n"
+            )
+            .into_bytes();
+            body.extend_from_slice(data);
+            body.extend_from_slice(b"\r\n--photo-boundary--\r\n");
+            body
+        }
+
+        let client = client();
+        let printer = create_printer_fixture(&client).await;
+        let endpoint = format!(
+            "http://{}/api/v1/printers/{printer}/photo",
+            var("HOST").unwrap()
+        );
+        let content_type = "multipart/form-data; boundary=photo-boundary";
+
+        let response = client.get(&endpoint).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let png = b"\x89PNG\r\n\x1A\n\0\0\0\rIHDR".to_vec();
+        let response = client
+            .post(&endpoint)
+            .header("Content-Type", content_type)
+            .body(multipart("image/png", &png))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let photo: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(photo["content_type"], "image/png");
+        assert_eq!(photo["size"], png.len());
+
+        let response = client.get(&endpoint).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "image/png");
+        assert_eq!(response.bytes().await.unwrap().as_ref(), png.as_slice());
+
+        // Declared type does not match the bytes
+        let response = client
+            .post(&endpoint)
+            .header("Content-Type", content_type)
+            .body(multipart("image/jpeg", &png))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let response = client
+            .post(&endpoint)
+            .header("Content-Type", "application/json")
+            .body("{}")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let unknown = format!(
+            "http://{}/api/v1/printers/{}/photo",
+            var("HOST").unwrap(),
+            uuid::Uuid::new_v4()
+        );
+        let response = client
+            .post(&unknown)
+            .header("Content-Type", content_type)
+            .body(multipart("image/png", &png))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn request_schema_endpoint() {
+        setup();
+
+        let client = client();
+        let schema = |kind: &str| format!("http://{}/api/v1/schema/{kind}", var("HOST").unwrap());
+
+        let response = client.get(schema("brand")).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let brand: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(brand["type"], "object");
+        assert_eq!(brand["required"], serde_json::json!(["name"]));
+        assert_eq!(brand["properties"]["name"]["minLength"], 4);
+        assert_eq!(brand["properties"]["name"]["maxLength"], 20);
+        assert_eq!(brand["additionalProperties"], false);
+
+        let response = client.get(schema("printer")).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let printer: serde_json::Value = response.json().await.unwrap();
+        for field in ["name", "model", "brand", "toner", "drum"] {
+            assert!(printer["required"]
+                .as_array()
+                .unwrap()
+                .contains(&field.into()));
+        }
+        assert!(printer["properties"]["model"]["maxLength"].is_u64());
+
+        let response = client.get(schema("toner")).send().await.unwrap();
+        let toner: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(toner["properties"]["min_stock"]["minimum"], 0.0);
+
+        let response = client.get(schema("drum")).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = client.get(schema("cartridge")).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn printer_brand_swap_endpoint() {
+        setup();
+
+        let client = client();
+        let printer = create_printer_fixture(&client).await;
+        let new_brand = create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("SWB")),
+        )
+        .await;
+        let brand_endpoint =
+            |id: &str| format!("http://{}/api/v1/printers/{id}/brand", var("HOST").unwrap());
+
+        let printer_brand =
+            |printer: serde_json::Value| printer["brand"].as_str().unwrap().to_string();
+        let endpoint = format!("http://{}/api/v1/printer/{printer}", var("HOST").unwrap());
+        let before = printer_brand(
+            client
+                .get(&endpoint)
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap(),
+        );
+        assert_ne!(before, new_brand);
+
+        let response = client
+            .patch(brand_endpoint(&printer))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "brand": "{new_brand}" }}"#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(printer_brand(response.json().await.unwrap()), new_brand);
+
+        let pool = sqlx::PgPool::connect(&var("DATABASE_URL").unwrap())
+            .await
+            .unwrap();
+        let changes: Option<serde_json::Value> = sqlx::query_scalar(
+            "SELECT changes FROM audit_log WHERE entity_id = $1 AND action = 'update' ORDER BY id DESC LIMIT 1;",
+        )
+        .bind(uuid::Uuid::parse_str(&printer).unwrap())
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            changes,
+            Some(serde_json::json!({ "brand": { "old": before, "new": new_brand } }))
+        );
+
+        // Name taken by a printer of the new brand
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let name = unique_name("SWN");
+        let printer_body = |brand: &str, toner: &str| {
+            format!(
+                r#"{{
+                    "name": "{name}",
+                    "model": "TEST model",
+                    "brand": "{brand}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"
+                }}"#
+            )
+        };
+        create_fixture(&client, "printers", printer_body(&new_brand, &toner)).await;
+        let same_name = create_fixture(&client, "printers", printer_body(&brand, &toner)).await;
+        let response = client
+            .patch(brand_endpoint(&same_name))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "brand": "{new_brand}" }}"#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["code"], "DUPLICATE_NAME");
+
+        // Toner of another brand than the new one
+        let branded_toner = create_fixture(
+            &client,
+            "supplies/toners",
+            format!(
+                r#"{{ "name": "{}", "brand": "{brand}" }}"#,
+                unique_name("SWT")
+            ),
+        )
+        .await;
+        let mismatched = create_fixture(
+            &client,
+            "printers",
+            printer_body(&brand, &branded_toner).replace(&name, &unique_name("SWM")),
+        )
+        .await;
+        let response = client
+            .patch(brand_endpoint(&mismatched))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "brand": "{new_brand}" }}"#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(
+            body["warnings"],
+            serde_json::json!(["Toner brand does not match the printer brand."])
+        );
+
+        let server = TestServer::start(&[("TONER_BRAND_VALIDATION", "strict")]).await;
+        let response = client
+            .patch(server.url(&format!("printers/{mismatched}/brand")))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "brand": "{brand}" }}"#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = client
+            .patch(server.url(&format!("printers/{mismatched}/brand")))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "brand": "{new_brand}" }}"#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Unknown brand
+        let response = client
+            .patch(brand_endpoint(&printer))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "brand": "{}" }}"#, uuid::Uuid::new_v4()))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Unknown printer
+        let response = client
+            .patch(brand_endpoint(&uuid::Uuid::new_v4().to_string()))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "brand": "{new_brand}" }}"#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn snapshots_endpoint() {
+        setup();
+        let _turn = ADMIN_OPERATIONS.lock().await;
+
+        let client = client();
+        let take = format!("http://{}/api/v1/admin/snapshots", var("HOST").unwrap());
+
+        // While another snapshot is being taken the request is refused.
+        let pool = sqlx::PgPool::connect(&var("DATABASE_URL").unwrap())
+            .await
+            .unwrap();
+        let mut running = pool.begin().await.unwrap();
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('take-snapshot'));")
+            .execute(&mut *running)
+            .await
+            .unwrap();
+        let response = client.post(&take).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        running.rollback().await.unwrap();
+
+        let response = client.post(&take).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        // The counts are checked in `counts.rs`, away from concurrent tests.
+        let snapshot: serde_json::Value = response.json().await.unwrap();
+        let taken_at = snapshot["taken_at"].as_str().unwrap();
+
+        let endpoint = format!("http://{}/api/v1/snapshots", var("HOST").unwrap());
+        let response = client
+            .get(&endpoint)
+            .query(&[("from", taken_at), ("to", taken_at)])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let page: serde_json::Value = response.json().await.unwrap();
+        let ids: Vec<_> = page["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|snapshot| &snapshot["id"])
+            .collect();
+        assert!(ids.contains(&&snapshot["id"]));
+
+        let response = client
+            .get(&endpoint)
+            .query(&[("to", "2000-01-01T00:00:00Z")])
+            .send()
+            .await
+            .unwrap();
+        let page: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(page["total"], 0);
+
+        // Range is reversed
+        let response = client
+            .get(&endpoint)
+            .query(&[("from", taken_at), ("to", "2000-01-01T00:00:00Z")])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn printer_lease_endpoint() {
+        setup();
+
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        // The server counts days from the database's date, not this host's.
+        let pool = sqlx::PgPool::connect(&var("DATABASE_URL").unwrap())
+            .await
+            .unwrap();
+        let (today,): (chrono::NaiveDate,) = sqlx::query_as("SELECT CURRENT_DATE;")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let lease_end = today + chrono::Duration::days(10);
+        let lease_ended = today - chrono::Duration::days(3);
+        let printer_body = |acquisition: &str| {
+            format!(
+                r#"{{
+                    "name": "{}",
+                    "model": "TEST model",
+                    "brand": "{brand}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"{acquisition}
+                }}"#,
+                unique_name("LSP")
+            )
+        };
+        let endpoint = format!("http://{}/api/v1/printers", var("HOST").unwrap());
+
+        // Lease end date missing or not allowed
+        for acquisition in [
+            r#", "acquisition_type": "leased""#.to_string(),
+            format!(r#", "lease_end_date": "{lease_end}""#),
+        ] {
+            let response = client
+                .post(&endpoint)
+                .header("Content-Type", "application/json")
+                .body(printer_body(&acquisition))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+
+        let owned = create_fixture(&client, "printers", printer_body("")).await;
+        let leased = create_fixture(
+            &client,
+            "printers",
+            printer_body(&format!(
+                r#", "acquisition_type": "leased", "lease_end_date": "{lease_end}""#
+            )),
+        )
+        .await;
+
+        let expiring = |within_days: i32| {
+            let client = client.clone();
+            async move {
+                let response = client
+                    .get(format!(
+                        "http://{}/api/v1/printers/expiring-leases?within_days={within_days}",
+                        var("HOST").unwrap()
+                    ))
+                    .send()
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                response.json::<Vec<serde_json::Value>>().await.unwrap()
+            }
+        };
+
+        let expired = create_fixture(
+            &client,
+            "printers",
+            printer_body(&format!(
+                r#", "acquisition_type": "leased", "lease_end_date": "{lease_ended}""#
+            )),
+        )
+        .await;
+
+        let leases = expiring(30).await;
+        let lease = leases.iter().find(|lease| lease["id"] == leased).unwrap();
+        assert_eq!(lease["lease_end_date"], lease_end.to_string());
+        assert_eq!(lease["days_left"], 10);
+        assert!(leases.iter().all(|lease| lease["id"] != owned));
+
+        // An already expired lease is listed with the days since it ended
+        let lease = leases.iter().find(|lease| lease["id"] == expired).unwrap();
+        assert_eq!(lease["lease_end_date"], lease_ended.to_string());
+        assert_eq!(lease["days_left"], -3);
+
+        let leases = expiring(5).await;
+        assert!(leases.iter().all(|lease| lease["id"] != leased));
+        assert!(leases.iter().any(|lease| lease["id"] == expired));
+
+        // Negative window
+        let response = client
+            .get(format!(
+                "http://{}/api/v1/printers/expiring-leases?within_days=-1",
+                var("HOST").unwrap()
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Lease terms are kept when an update omits them
+        let response = client
+            .put(&endpoint)
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{
+                    "id": "{leased}",
+                    "name": "{}",
+                    "model": "TEST model",
+                    "brand": "{brand}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"
+                }}"#,
+                unique_name("LSU")
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let printer = format!("http://{}/api/v1/printer/{leased}", var("HOST").unwrap());
+        let response = client.get(&printer).send().await.unwrap();
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["acquisition_type"], "leased");
+        assert_eq!(body["lease_end_date"], lease_end.to_string());
+    }
+
+    #[tokio::test]
+    async fn near_duplicate_printers_endpoint() {
+        setup();
+
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        // Letters only, so names of other runs share little with these.
+        let token: String = uuid::Uuid::new_v4().simple().to_string()[..6]
+            .chars()
+            .map(|c| char::from(b'g' + c.to_digit(16).unwrap() as u8))
+            .collect();
+        let mut ids = Vec::new();
+        for name in ["LaserJet 400", "Laserjet400", "LaserJet 4000"] {
+            let id = create_fixture(
+                &client,
+                "printers",
+                format!(
+                    r#"{{
+                        "name": "{token} {name}",
+                        "model": "TEST model",
+                        "brand": "{brand}",
+                        "toner": "{toner}",
+                        "drum": "{drum}"
+                    }}"#
+                ),
+            )
+            .await;
+            ids.push(id);
+        }
+
+        let endpoint = format!(
+            "http://{}/api/v1/admin/near-duplicate-printers",
+            var("HOST").unwrap()
+        );
+        let response = client.get(&endpoint).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let groups: Vec<serde_json::Value> = response.json().await.unwrap();
+        let group = groups
+            .iter()
+            .find(|group| {
+                group["printers"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .any(|printer| printer["id"] == ids[0])
+            })
+            .unwrap();
+        let mut members: Vec<&str> = group["printers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|printer| printer["id"].as_str().unwrap())
+            .collect();
+        members.sort();
+        let mut expected: Vec<&str> = ids.iter().map(String::as_str).collect();
+        expected.sort();
+        assert_eq!(members, expected);
+
+        // Same name once spaces and case are ignored
+        let exact = group["pairs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|pair| {
+                [&pair["printer"], &pair["other"]].contains(&&serde_json::json!(ids[0]))
+                    && [&pair["printer"], &pair["other"]].contains(&&serde_json::json!(ids[1]))
+            })
+            .unwrap();
+        assert_eq!(exact["similarity"], 1.0);
+        assert_eq!(group["pairs"][0]["similarity"], 1.0);
+
+        // Threshold out of range
+        let response = client
+            .get(format!("{endpoint}?threshold=0"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn max_toners_per_brand_endpoint() {
+        let server = TestServer::start(&[("MAX_TONERS_PER_BRAND", "2")]).await;
+        let _turn = ADMIN_OPERATIONS.lock().await;
+        let client = client();
+        let endpoint = server.url("supplies/toners");
+        let brand = || {
+            let client = client.clone();
+            let endpoint = server.url("brands");
+            async move {
+                let response = client
+                    .post(endpoint)
+                    .header("Content-Type", "application/json")
+                    .body(format!(r#"{{ "name": "{}" }}"#, unique_name("MTB")))
+                    .send()
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::CREATED);
+                response.json::<String>().await.unwrap()
+            }
+        };
+        let create = |brand: Option<&str>| {
+            let brand = brand.map_or(String::new(), |brand| format!(r#", "brand": "{brand}""#));
+            client
+                .post(&endpoint)
+                .header("Content-Type", "application/json")
+                .body(format!(r#"{{ "name": "{}"{brand} }}"#, unique_name("MTT")))
+                .send()
+        };
+        let assert_limit = |error: serde_json::Value, count: i64| {
+            assert_eq!(error["code"], "BRAND_TONER_LIMIT");
+            assert_eq!(error["params"]["limit"], 2);
+            assert_eq!(error["params"]["count"], count);
+        };
+
+        // Creates racing for the last places: only two get one
+        let full = brand().await;
+        let statuses: Vec<StatusCode> =
+            futures_util::future::join_all((0..6).map(|_| create(Some(&full))))
+                .await
+                .into_iter()
+                .map(|response| response.unwrap().status())
+                .collect();
+        assert_eq!(
+            statuses
+                .iter()
+                .filter(|status| **status == StatusCode::CREATED)
+                .count(),
+            2,
+            "{statuses:?}"
+        );
+        assert!(
+            statuses
+                .iter()
+                .all(|status| *status == StatusCode::CREATED || *status == StatusCode::CONFLICT),
+            "{statuses:?}"
+        );
+
+        let response = create(Some(&full)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_limit(response.json().await.unwrap(), 2);
+
+        // Toners without a brand are not counted against any.
+        let response = create(None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let unbranded: String = response.json::<serde_json::Value>().await.unwrap()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        // Moving a toner into a full brand
+        let update = |brand: &str| {
+            client
+                .put(&endpoint)
+                .header("Content-Type", "application/json")
+                .body(format!(
+                    r#"{{ "id": "{unbranded}", "name": "{}", "brand": "{brand}" }}"#,
+                    unique_name("MTU")
+                ))
+                .send()
+        };
+        let response = update(&full).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_limit(response.json().await.unwrap(), 2);
+
+        // A toner staying in its brand keeps its place
+        let other = brand().await;
+        assert_eq!(update(&other).await.unwrap().status(), StatusCode::OK);
+        assert_eq!(update(&other).await.unwrap().status(), StatusCode::OK);
+
+        // Merging into a brand that cannot take the toners
+        let response = client
+            .post(server.url("admin/brands/merge"))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "from": "{other}", "into": "{full}" }}"#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_limit(response.json().await.unwrap(), 3);
+
+        // Importing toners into a full brand
+        let imported = uuid::Uuid::new_v4();
+        let response = client
+            .post(server.url("admin/import"))
+            .header("Content-Type", "application/json")
+            .body(
+                serde_json::json!({
+                    "toners": [{
+                        "id": imported,
+                        "name": unique_name("MTI"),
+                        "stock": 0,
+                        "brand": full,
+                        "min_stock": 0,
+                        "color": null,
+                        "page_yield": null
+                    }]
+                })
+                .to_string(),
+            )
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let report: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(report["toners"]["imported"], 0);
+        assert_eq!(report["conflicts"][0]["id"], imported.to_string());
+    }
+
+    #[tokio::test]
+    async fn effective_config_endpoint() {
+        setup();
+        let client = client();
+        let endpoint = format!("http://{}/api/v1/admin/config", var("HOST").unwrap());
+
+        let response = client.get(&endpoint).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.text().await.unwrap();
+        let config: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(config["bind_address"], var("HOST").unwrap());
+        assert!(config["db_max_connections"].as_u64().unwrap() > 0);
+        assert!(config["default_page_size"].as_i64().unwrap() > 0);
+        assert!(config["read_only"].is_boolean());
+
+        // Secrets are redacted
+        let database_url = url::Url::parse(&var("DATABASE_URL").unwrap()).unwrap();
+        let reported = url::Url::parse(config["database_url"].as_str().unwrap()).unwrap();
+        assert_eq!(reported.host_str(), database_url.host_str());
+        assert_eq!(reported.username(), database_url.username());
+        if let Some(password) = database_url.password() {
+            assert_eq!(reported.password(), Some("REDACTED"));
+            assert!(!body.contains(password));
+        }
+        if let Some(admin) = api_key("admin") {
+            assert!(!body.contains(&admin));
+            assert!(config["api_keys"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|key| key["scope"] == "admin"));
+        }
+
+        // Admin only
+        if let Some(write) = api_key("write") {
+            let response = reqwest::Client::new()
+                .get(&endpoint)
+                .header("X-API-Key", write)
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+    }
+
+    #[tokio::test]
+    async fn idempotent_delete_endpoint() {
+        let idempotent = TestServer::start(&[("IDEMPOTENT_DELETES", "true")]).await;
+        let client = client();
+        let delete = |endpoint: String, id: String| {
+            client
+                .delete(endpoint)
+                .header("Content-Type", "application/json")
+                .body(format!(r#"{{ "id": "{id}" }}"#))
+                .send()
+        };
+
+        for (collection, prefix) in [("brands", "IDB"), ("supplies/drums", "IDD")] {
+            let create = || {
+                create_fixture(
+                    &client,
+                    collection,
+                    format!(r#"{{ "name": "{}" }}"#, unique_name(prefix)),
+                )
+            };
+
+            // By default the retry finds the row already gone.
+            let id = create().await;
+            let endpoint = format!("http://{}/api/v1/{collection}", var("HOST").unwrap());
+            let response = delete(endpoint.clone(), id.clone()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{collection}");
+            let response = delete(endpoint, id).await.unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND, "{collection}");
+
+            // Under `IDEMPOTENT_DELETES` the delete and its retry answer alike.
+            let id = create().await;
+            for _ in 0..2 {
+                let response = delete(idempotent.url(collection), id.clone())
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::NO_CONTENT, "{collection}");
+                assert!(response.text().await.unwrap().is_empty());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn printer_graph_endpoint() {
+        setup();
+        let client = client();
+        let host = var("HOST").unwrap();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let printer = create_fixture(
+            &client,
+            "printers",
+            format!(
+                r#"{{
+                    "name": "{}",
+                    "model": "Graph",
+                    "brand": "{brand}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"
+                }}"#,
+                unique_name("GRP")
+            ),
+        )
+        .await;
+        let compatible = create_fixture(
+            &client,
+            "supplies/toners",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("GRT")),
+        )
+        .await;
+        let response = client
+            .post(format!(
+                "http://{host}/api/v1/supplies/toners/{toner}/alternatives"
+            ))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "toner": "{compatible}" }}"#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let tag = unique_name("graph").replace(' ', "-");
+        let response = client
+            .post(format!("http://{host}/api/v1/printers/{printer}/tags"))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "tag": "{tag}" }}"#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = client
+            .get(format!("http://{host}/api/v1/printers/{printer}/graph"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let graph: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(graph["root"], printer.as_str());
+        let nodes: Vec<(&str, &str)> = graph["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|node| (node["kind"].as_str().unwrap(), node["id"].as_str().unwrap()))
+            .collect();
+        let tag_id = nodes
+            .iter()
+            .find(|(kind, _)| *kind == "tag")
+            .map(|(_, id)| *id)
+            .unwrap();
+        assert_eq!(
+            nodes,
+            [
+                ("printer", printer.as_str()),
+                ("brand", brand.as_str()),
+                ("toner", toner.as_str()),
+                ("drum", drum.as_str()),
+                ("toner", compatible.as_str()),
+                ("tag", tag_id),
+            ]
+        );
+        let edges: Vec<(&str, &str, &str)> = graph["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|edge| {
+                (
+                    edge["source"].as_str().unwrap(),
+                    edge["relation"].as_str().unwrap(),
+                    edge["target"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                (printer.as_str(), "brand", brand.as_str()),
+                (printer.as_str(), "toner", toner.as_str()),
+                (printer.as_str(), "drum", drum.as_str()),
+                (toner.as_str(), "compatible", compatible.as_str()),
+                (printer.as_str(), "tag", tag_id),
+            ]
+        );
+
+        // Missing printer
+        let response = client
+            .get(format!(
+                "http://{host}/api/v1/printers/{}/graph",
+                uuid::Uuid::new_v4()
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn noop_brand_update_endpoint() {
+        setup();
+        let client = client();
+        let endpoint = format!("http://{}/api/v1/brands", var("HOST").unwrap());
+        let name = unique_name("NOP");
+        let brand = create_fixture(&client, "brands", format!(r#"{{ "name": "{name}" }}"#)).await;
+        let pool = sqlx::PgPool::connect(&var("DATABASE_URL").unwrap())
+            .await
+            .unwrap();
+        let updated_at = || {
+            sqlx::query_as::<_, (chrono::DateTime<chrono::Utc>, i64)>(
+                r#"
+                SELECT updated_at,
+                    (SELECT COUNT(*) FROM audit_log WHERE entity_id = brands.id AND action = 'update')
+                FROM brands
+                WHERE id = $1;
+                "#,
+            )
+            .bind(uuid::Uuid::parse_str(&brand).unwrap())
+            .fetch_one(&pool)
+        };
+        let update = |name: &str| {
+            client
+                .put(&endpoint)
+                .header("Content-Type", "application/json")
+                .body(format!(r#"{{ "id": "{brand}", "name": "{name}" }}"#))
+                .send()
+        };
+        let created = updated_at().await.unwrap();
+
+        // Same name, surrounding whitespace aside: nothing is written
+        let response = update(&format!("  {name} ")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.json::<String>().await.unwrap(), brand);
+        assert_eq!(updated_at().await.unwrap(), created);
+
+        // A new name is still written
+        let response = update(&unique_name("NOP")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let (renamed_at, updates) = updated_at().await.unwrap();
+        assert!(renamed_at > created.0);
+        assert_eq!(updates, 1);
+    }
+
+    #[tokio::test]
+    async fn toner_sku_uniqueness_endpoint() {
+        setup();
+        let client = client();
+        let endpoint = format!("http://{}/api/v1/supplies/toners", var("HOST").unwrap());
+        let brand = create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("SKB")),
+        )
+        .await;
+        let other_brand = create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("SKB")),
+        )
+        .await;
+        let name = unique_name("SKU");
+        let create = |brand: &str, color: &str| {
+            client
+                .post(&endpoint)
+                .header("Content-Type", "application/json")
+                .body(format!(
+                    r#"{{ "name": "{name}", "brand": "{brand}", "color": "{color}" }}"#
+                ))
+                .send()
+        };
+
+        // The name is reused under another color or brand
+        for (brand, color) in [(&brand, "Black"), (&brand, "Cyan"), (&other_brand, "Black")] {
+            let response = create(brand, color).await.unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+
+        // Same brand, name and color, whatever the case of the color
+        let response = create(&brand, " black").await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let error: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(error["code"], "DUPLICATE_TONER");
+        assert_eq!(error["params"]["brand"], brand.as_str());
+        assert_eq!(error["params"]["name"], name.as_str());
+        assert_eq!(error["params"]["color"], "Black");
+
+        // Updates keep the stored color when none is given
+        let magenta = create(&brand, "Magenta").await.unwrap();
+        let magenta: serde_json::Value = magenta.json().await.unwrap();
+        let update = |brand: &str, color: Option<&str>| {
+            let color = color.map_or(String::new(), |color| format!(r#", "color": "{color}""#));
+            client
+                .put(&endpoint)
+                .header("Content-Type", "application/json")
+                .body(format!(
+                    r#"{{ "id": "{}", "name": "{name}", "brand": "{brand}"{color} }}"#,
+                    magenta["id"].as_str().unwrap()
+                ))
+                .send()
+        };
+        let response = update(&brand, Some("Cyan")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let error: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(error["params"]["color"], "Cyan");
+        let response = update(&brand, None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = update(&other_brand, None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn events_endpoint() {
+        setup();
+        let client = client();
+        let mut stream = client
+            .get(format!("http://{}/api/v1/events", var("HOST").unwrap()))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(stream.status(), StatusCode::OK);
+        assert_eq!(
+            stream.headers()["content-type"].to_str().unwrap(),
+            "text/event-stream"
+        );
+        let mut simple = client
+            .get(format!("http://{}/api/v1/events", var("HOST").unwrap()))
+            .header("Accept", "text/event-stream; ids=simple")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(simple.status(), StatusCode::OK);
+
+        let brand = create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("EVT")),
+        )
+        .await;
+        let simple_brand = uuid::Uuid::parse_str(&brand).unwrap().simple().to_string();
+
+        for (stream, id) in [(&mut stream, &brand), (&mut simple, &simple_brand)] {
+            // Other tests' changes may arrive first.
+            let expected = format!(r#"{{"entity":"brand","id":"{id}","operation":"create"}}"#);
+            let mut received = String::new();
+            while !received.contains(&expected) {
+                let chunk = tokio::time::timeout(std::time::Duration::from_secs(5), stream.chunk())
+                    .await
+                    .expect("no change event within 5 seconds")
+                    .unwrap()
+                    .unwrap();
+                received.push_str(&String::from_utf8_lossy(&chunk));
+            }
+            assert!(received.contains(&format!("event: change\ndata: {expected}\n")));
+        }
+    }
+
+    #[tokio::test]
+    async fn brand_contact_endpoint() {
+        setup();
+        let client = client();
+        let host = var("HOST").unwrap();
+        let endpoint = format!("http://{host}/api/v1/brands");
+        let contact = r#""website_url": "https://example.com/support",
+            "support_email": "help@example.com",
+            "support_phone": "+1 (555) 010-0199""#;
+
+        let brand = create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}", {contact} }}"#, unique_name("CTB")),
+        )
+        .await;
+        let show = || async {
+            let response = client
+                .get(format!("http://{host}/api/v1/brand/{brand}"))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            response.json::<serde_json::Value>().await.unwrap()
+        };
+        let shown = show().await;
+        assert_eq!(shown["website_url"], "https://example.com/support");
+        assert_eq!(shown["support_email"], "help@example.com");
+        assert_eq!(shown["support_phone"], "+1 (555) 010-0199");
+
+        for (field, value) in [
+            ("website_url", "ftp://example.com"),
+            ("support_email", "help@"),
+            ("support_email", "help desk@example.com"),
+            ("support_email", "help@localhost"),
+            ("support_phone", "12345"),
+            ("support_phone", "555-0199 ext 2"),
+            ("support_phone", "1234567890123456"),
+        ] {
+            let response = client
+                .post(&endpoint)
+                .header("Content-Type", "application/json")
+                .body(format!(
+                    r#"{{ "name": "{}", "{field}": "{value}" }}"#,
+                    unique_name("CTB")
+                ))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(
+                response.status(),
+                StatusCode::BAD_REQUEST,
+                "{field}: {value}"
+            );
+        }
+
+        // Updates replace the contacts, clearing those left out
+        let response = client
+            .put(&endpoint)
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{ "id": "{brand}", "name": "{}", "support_phone": "555.010.0199" }}"#,
+                shown["name"].as_str().unwrap()
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let shown = show().await;
+        assert_eq!(shown["website_url"], serde_json::Value::Null);
+        assert_eq!(shown["support_email"], serde_json::Value::Null);
+        assert_eq!(shown["support_phone"], "555.010.0199");
+    }
+
+    #[tokio::test]
+    async fn printer_compatibility_endpoint() {
+        setup();
+        let client = client();
+        let host = var("HOST").unwrap();
+        let (brand, _, drum) = create_printer_fixtures(&client).await;
+        let other_brand = create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("CMB")),
+        )
+        .await;
+        let toner = |brand: &str| {
+            create_fixture(
+                &client,
+                "supplies/toners",
+                format!(
+                    r#"{{ "name": "{}", "brand": "{brand}" }}"#,
+                    unique_name("CMT")
+                ),
+            )
+        };
+        let own_toner = toner(&brand).await;
+        let other_toner = toner(&other_brand).await;
+        let response = client
+            .post(format!(
+                "http://{host}/api/v1/supplies/toners/{other_toner}/alternatives"
+            ))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "toner": "{own_toner}" }}"#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let validate = |toner: &str, drum: &str| {
+            client
+                .post(format!(
+                    "http://{host}/api/v1/printers/validate-compatibility"
+                ))
+                .header("Content-Type", "application/json")
+                .body(format!(
+                    r#"{{ "brand": "{brand}", "toner": "{toner}", "drum": "{drum}" }}"#
+                ))
+                .send()
+        };
+
+        let response = validate(&own_toner, &drum).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let report: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(report["compatible"], true);
+        for reference in ["brand", "toner", "drum"] {
+            assert_eq!(report[reference]["status"], "ok");
+        }
+
+        // A toner of another brand, with its alternative of the right brand
+        let missing = uuid::Uuid::new_v4().to_string();
+        let response = validate(&other_toner, &missing).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let report: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(report["compatible"], false);
+        assert_eq!(report["brand"]["status"], "ok");
+        assert_eq!(report["toner"]["status"], "brand_mismatch");
+        assert_eq!(
+            report["toner"]["alternatives"],
+            serde_json::json!([own_toner])
+        );
+        assert_eq!(report["drum"]["status"], "not_found");
+
+        let response = validate(&missing, &drum).await.unwrap();
+        let report: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(report["toner"]["status"], "not_found");
+    }
+
+    #[tokio::test]
+    async fn oversized_offset_endpoint() {
+        setup();
+        let client = client();
+        let host = var("HOST").unwrap();
+        let max_offset: i64 = var("MAX_OFFSET").map_or(10_000, |max| max.parse().unwrap());
+
+        for path in ["brands", "printers", "supplies/toners"] {
+            let response = client
+                .get(format!("http://{host}/api/v1/{path}?offset={max_offset}"))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{path}");
+
+            let response = client
+                .get(format!(
+                    "http://{host}/api/v1/{path}?offset={}",
+                    max_offset + 1
+                ))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{path}");
+            let message: String = response.json().await.unwrap();
+            assert!(message.contains("cursor pagination"), "{message}");
+        }
+
+        let response = client
+            .get(format!("http://{host}/api/v1/brands?offset=999999999"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn replace_toner_endpoint() {
+        setup();
+        let _turn = ADMIN_OPERATIONS.lock().await;
+        let client = client();
+        let (brand, from, drum) = create_printer_fixtures(&client).await;
+        let to = create_fixture(
+            &client,
+            "supplies/toners",
+            format!(
+                r#"{{ "name": "{}", "brand": "{brand}" }}"#,
+                unique_name("RPT")
+            ),
+        )
+        .await;
+        let mut printers = Vec::new();
+        for _ in 0..2 {
+            printers.push(
+                create_fixture(
+                    &client,
+                    "printers",
+                    format!(
+                        r#"{{
+                            "name": "{}",
+                            "model": "TEST model",
+                            "brand": "{brand}",
+                            "toner": "{from}",
+                            "drum": "{drum}"
+                        }}"#,
+                        unique_name("RPP")
+                    ),
+                )
+                .await,
+            );
+        }
+
+        let host = var("HOST").unwrap();
+        let endpoint = |from: &str, to: &str| {
+            format!("http://{host}/api/v1/admin/toners/{from}/replace-with/{to}")
+        };
+
+        // A toner cannot be replaced with itself.
+        let response = client.post(endpoint(&from, &from)).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Both toners must exist.
+        let response = client
+            .post(endpoint(&from, "4340c4a2-eac5-4b51-9baa-40b498605a8c"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = client.post(endpoint(&from, &to)).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let replaced: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(replaced["from"], from.as_str());
+        assert_eq!(replaced["to"], to.as_str());
+        assert_eq!(replaced["printers"], 2);
+
+        for printer in &printers {
+            let printer: serde_json::Value = client
+                .get(format!("http://{host}/api/v1/printer/{printer}"))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            assert_eq!(printer["toner"], to.as_str());
+        }
+
+        let pool = sqlx::PgPool::connect(&var("DATABASE_URL").unwrap())
+            .await
+            .unwrap();
+        let (changes,): (serde_json::Value,) = sqlx::query_as(
+            "SELECT changes FROM audit_log WHERE entity_id = $1 AND action = 'update' ORDER BY id DESC LIMIT 1;",
+        )
+        .bind(uuid::Uuid::parse_str(&printers[0]).unwrap())
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(changes["toner"]["old"], from.as_str());
+        assert_eq!(changes["toner"]["new"], to.as_str());
+
+        // Nothing is left on the old toner.
+        let response = client.post(endpoint(&from, &to)).send().await.unwrap();
+        let replaced: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(replaced["printers"], 0);
+        assert_eq!(replaced["warnings"], serde_json::json!([]));
+
+        // While another replacement runs the request is refused.
+        let mut running = pool.begin().await.unwrap();
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('replace-toner'));")
+            .execute(&mut *running)
+            .await
+            .unwrap();
+        let response = client.post(endpoint(&to, &from)).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        running.rollback().await.unwrap();
+
+        // A replacement of another brand than the printers
+        let other_brand = create_fixture(
+            &client,
+            "brands",
+            format!(r#"{{ "name": "{}" }}"#, unique_name("RPB")),
+        )
+        .await;
+        let other = create_fixture(
+            &client,
+            "supplies/toners",
+            format!(
+                r#"{{ "name": "{}", "brand": "{other_brand}" }}"#,
+                unique_name("RPO")
+            ),
+        )
+        .await;
+
+        let strict = TestServer::start(&[("TONER_BRAND_VALIDATION", "strict")]).await;
+        let response = client
+            .post(strict.url(&format!("admin/toners/{to}/replace-with/{other}")))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = client.post(endpoint(&to, &other)).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let replaced: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(replaced["printers"], 2);
+        assert_eq!(
+            replaced["warnings"],
+            serde_json::json!(["Toner brand does not match the printer brand."])
+        );
+    }
+
+    #[tokio::test]
+    async fn printer_connection_type_endpoint() {
+        setup();
+        let client = client();
+        let host = var("HOST").unwrap();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let tag = unique_name("con").to_lowercase();
+        let body = |connection: &str| {
+            format!(
+                r#"{{
+                    "name": "{}",
+                    "model": "TEST model",
+                    "brand": "{brand}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"{connection}
+                }}"#,
+                unique_name("CNP")
+            )
+        };
+
+        // Only the known connection types are accepted.
+        let response = client
+            .post(format!("http://{host}/api/v1/printers"))
+            .header("Content-Type", "application/json")
+            .body(body(r#", "connection_type": "parallel""#))
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_client_error());
+
+        let wifi =
+            create_fixture(&client, "printers", body(r#", "connection_type": "wifi""#)).await;
+        let unknown = create_fixture(&client, "printers", body("")).await;
+        for printer in [&wifi, &unknown] {
+            let response = client
+                .post(format!("http://{host}/api/v1/printers/{printer}/tags"))
+                .header("Content-Type", "application/json")
+                .body(format!(r#"{{ "tag": "{tag}" }}"#))
+                .send()
+                .await
+                .unwrap();
+            assert!(response.status().is_success());
+        }
+
+        let printer: serde_json::Value = client
+            .get(format!("http://{host}/api/v1/printer/{unknown}"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(printer["connection_type"], serde_json::Value::Null);
+
+        let listed: serde_json::Value = client
+            .get(format!(
+                "http://{host}/api/v1/printers?tag={tag}&connection_type=wifi"
+            ))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(listed["total"], 1);
+        assert_eq!(listed["data"][0]["id"], wifi.as_str());
+
+        // Updates keep the connection type when it is omitted.
+        let response = client
+            .put(format!("http://{host}/api/v1/printers"))
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{
+                    "id": "{wifi}",
+                    "name": "{}",
+                    "model": "TEST model",
+                    "brand": "{brand}",
+                    "toner": "{toner}",
+                    "drum": "{drum}"
+                }}"#,
+                unique_name("CNU")
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let printer: serde_json::Value = client
+            .get(format!("http://{host}/api/v1/printer/{wifi}"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(printer["connection_type"], "wifi");
+
+        let counts: Vec<serde_json::Value> = client
+            .get(format!("http://{host}/api/v1/printer-count/by-connection"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let wifi_count = counts
+            .iter()
+            .find(|count| count["connection_type"] == "wifi")
+            .unwrap();
+        assert!(wifi_count["count"].as_i64().unwrap() >= 1);
+        assert!(counts
+            .iter()
+            .any(|count| count["connection_type"].is_null()));
+    }
+
+    #[tokio::test]
+    async fn body_limit_endpoint() {
+        setup();
+        let client = client();
+        let host = var("HOST").unwrap();
+        // Whitespace keeps the bodies valid JSON past the single-item limit.
+        let padding = " ".repeat(128 * 1024);
+
+        let response = client
+            .post(format!("http://{host}/api/v1/brands"))
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{ "name": "{}" }}{padding}"#,
+                unique_name("BDL")
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // Bulk routes take larger bodies.
+        let printer = create_printer_fixture(&client).await;
+        let response = client
+            .post(format!("http://{host}/api/v1/printers/bulk-status"))
+            .header("Content-Type", "application/json")
+            .body(format!(
+                r#"{{ "ids": ["{printer}"], "status": "maintenance" }}{padding}"#
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn name_length_in_characters_endpoint() {
+        setup();
+        let client = client();
+        let (brand, toner, drum) = create_printer_fixtures(&client).await;
+        let host = var("HOST").unwrap();
+        // 20 characters but 32 bytes, and one character more.
+        let accented = |extra: &str| {
+            format!(
+                "{}{}{extra}",
+                "é".repeat(12),
+                &uuid::Uuid::new_v4().simple().to_string()[..8]
+            )
+        };
+
+        for path in ["printers", "supplies/toners", "supplies/drums"] {
+            let body = |name: String| {
+                if path == "printers" {
+                    format!(
+                        r#"{{
+                            "name": "{name}",
+                            "model": "TEST model",
+                            "brand": "{brand}",
+                            "toner": "{toner}",
+                            "drum": "{drum}"
+                        }}"#
+                    )
+                } else {
+                    format!(r#"{{ "name": "{name}" }}"#)
+                }
+            };
+
+            create_fixture(&client, path, body(accented(""))).await;
+
+            let response = client
+                .post(format!("http://{host}/api/v1/{path}"))
+                .header("Content-Type", "application/json")
+                .body(body(accented("é")))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            let error: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(error["code"], "NAME_TOO_LONG");
+            assert_eq!(error["params"]["actual"], 21);
+        }
+    }
+
+    #[tokio::test]
+    async fn printer_malformed_reference_endpoint() {
+        setup();
+        let client = client();
+        let host = var("HOST").unwrap();
+        let (brand, toner, _) = create_printer_fixtures(&client).await;
+        let printer = create_printer_fixture(&client).await;
+        let fields = format!(
+            r#""name": "{}", "model": "TEST model", "brand": "{brand}", "toner": "{toner}", "drum": "not-an-id""#,
+            unique_name("MRF")
+        );
+
+        let response = client
+            .post(format!("http://{host}/api/v1/printers"))
+            .header("Content-Type", "application/json")
+            .body(format!("{{ {fields} }}"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = client
+            .put(format!("http://{host}/api/v1/printers"))
+            .header("Content-Type", "application/json")
+            .body(format!(r#"{{ "id": "{printer}", {fields} }}"#))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
 }